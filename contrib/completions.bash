@@ -8,13 +8,68 @@ _exa()
             return
             ;;
 
-        -L|--level)
+        -L|--level|--min-depth|--threads)
             COMPREPLY=( $( compgen -W '{0..9}' -- "$cur" ) )
             return
             ;;
 
         -s|--sort)
-            COMPREPLY=( $( compgen -W 'name filename Name Filename size filesize extension Extension modified accessed created type inode none --' -- "$cur" ) )
+            COMPREPLY=( $( compgen -W 'name filename Name Filename name-case name-nocase name-version name-locale size filesize extension Extension modified accessed created type inode blocks links git none --' -- "$cur" ) )
+            return
+            ;;
+
+        --git-only|--git-exclude)
+            COMPREPLY=( $( compgen -W 'new untracked modified deleted renamed typechange ignored clean --' -- $cur ) )
+            return
+            ;;
+
+        --category)
+            COMPREPLY=( $( compgen -W 'image video audio document archive code crypto temp compiled --' -- $cur ) )
+            return
+            ;;
+
+        --color-depth|--colour-depth)
+            COMPREPLY=( $( compgen -W 'truecolor 256 16 none --' -- $cur ) )
+            return
+            ;;
+
+        --porcelain)
+            COMPREPLY=( $( compgen -W 'fzf v1 --' -- $cur ) )
+            return
+            ;;
+
+        --plain-columns)
+            COMPREPLY=( $( compgen -W 'permissions size date blocks user group links inode git custom name --' -- $cur ) )
+            return
+            ;;
+
+        --theme)
+            COMPREPLY=( $( compgen -W 'default solarized-dark solarized-light gruvbox dracula monochrome --' -- $cur ) )
+            return
+            ;;
+
+        --only-types)
+            COMPREPLY=( $( compgen -W 'dirs files links sockets pipes devices --' -- $cur ) )
+            return
+            ;;
+
+        --owner)
+            COMPREPLY=( $( compgen -u -- "$cur" ) )
+            return
+            ;;
+
+        --owner-group)
+            COMPREPLY=( $( compgen -g -- "$cur" ) )
+            return
+            ;;
+
+        --dotfiles)
+            COMPREPLY=( $( compgen -W 'first last mixed --' -- $cur ) )
+            return
+            ;;
+
+        --normalize)
+            COMPREPLY=( $( compgen -W 'nfc nfd --' -- $cur ) )
             return
             ;;
 
@@ -10,16 +10,31 @@ complete -c exa -s 'x' -l 'across'       -d "Sort the grid across, rather than d
 complete -c exa -s 'R' -l 'recurse'      -d "Recurse into directories"
 complete -c exa -s 'T' -l 'tree'         -d "Recurse into directories as a tree"
 complete -c exa -s 'F' -l 'classify'     -d "Display type indicator by file names"
-complete -c exa        -l 'color'        -d "When to use terminal colours"
-complete -c exa        -l 'colour'       -d "When to use terminal colours"
-complete -c exa        -l 'color-scale'  -d "Highlight levels of file sizes distinctly"
-complete -c exa        -l 'colour-scale' -d "Highlight levels of file sizes distinctly"
+complete -c exa        -l 'porcelain'    -x -d "Print one line per file for other programs" -a "fzf v1"
+complete -c exa        -l 'snapshot'     -r -d "Write a manifest of the listed files"
+complete -c exa        -l 'color'        -d "When to use terminal colours" -a "always auto never"
+complete -c exa        -l 'colour'       -d "When to use terminal colours" -a "always auto never"
+complete -c exa        -l 'color-scale'  -d "Colour numbers or ages on a scale" -a "size age age-names blocks links"
+complete -c exa        -l 'colour-scale' -d "Colour numbers or ages on a scale" -a "size age age-names blocks links"
+complete -c exa        -l 'color-depth'  -x -d "How many colours the terminal has" -a "truecolor 256 16 none"
+complete -c exa        -l 'colour-depth' -x -d "How many colours the terminal has" -a "truecolor 256 16 none"
+complete -c exa        -l 'highlight-owners' -d "Highlight files owned by someone else, or by root"
+complete -c exa        -l 'plain-columns' -x -d "Which columns to show without colours" -a "permissions size date blocks user group links inode git custom name"
+complete -c exa        -l 'theme'     -x -d "Which built-in colour theme to use" -a "default solarized-dark solarized-light gruvbox dracula monochrome"
+complete -c exa        -l 'threads'      -x -d "How many threads to read files with"
+complete -c exa        -l 'stdin'        -d "Also list the paths read from standard input"
+complete -c exa        -l 'quiet'        -d "Don't warn about unreadable files"
+complete -c exa        -l 'deterministic' -d "List files the same way everywhere"
+complete -c exa        -l 'no-config'     -d "Don't read options from the config file"
 
 # Filtering and sorting options
 complete -c exa -l 'group-directories-first' -d "Sort directories before other files"
 complete -c exa -s 'a' -l 'all'       -d "Show and 'dot' files"
+complete -c exa -s 'A' -l 'almost-all' -d "Show hidden files, but never '.' and '..'"
+complete -c exa        -l 'dotfiles' -x -d "Where to list dot files" -a "first last mixed"
 complete -c exa -s 'd' -l 'list-dirs' -d "List directories like regular files"
 complete -c exa -s 'L' -l 'level'     -d "Limit the depth of recursion" -a "1 2 3 4 5 6 7 8 9"
+complete -c exa        -l 'min-depth' -d "Only list files at least this deep" -a "1 2 3 4 5 6 7 8 9"
 complete -c exa -s 'r' -l 'reverse'   -d "Reverse the sort order"
 complete -c exa -s 's' -l 'sort'   -x -d "Which field to sort by" -a "
     accessed\t'Sort by file accessed time'
@@ -31,15 +46,45 @@ complete -c exa -s 's' -l 'sort'   -x -d "Which field to sort by" -a "
     filename\t'Sort by filename'
     Filename\t'Sort by filename (case-insensitive)'
     inode\t'Sort by file inode'
+    blocks\t'Sort by number of blocks'
+    links\t'Sort by number of hard links'
+    git\t'Sort by Git status'
     modified\t'Sort by file modified time'
     name\t'Sort by filename'
     Name\t'Sort by filename (case-insensitive)'
+    name-case\t'Sort by filename (case-sensitive)'
+    name-nocase\t'Sort by filename (case-insensitive)'
+    name-version\t'Sort by filename, comparing version numbers'
+    name-locale\t'Sort by filename, using the locale’s collation rules'
     none\t'Do not sort files at all'
     size\t'Sort by file size'
     type\t'Sort by file type'
 "
 
 complete -c exa -s 'I' -l 'ignore-glob' -d "Ignore files that match these glob patterns" -r
+complete -c exa        -l 'prune-glob'    -d "Skip directories that match these glob patterns" -r
+complete -c exa        -l 'regex'         -d "Only list files whose names match this regex" -r
+complete -c exa        -l 'regex-exclude' -d "Ignore files whose names match this regex" -r
+complete -c exa        -l 'size-filter'   -d "Only list files of this size" -x
+complete -c exa        -l 'newer-than'     -d "Only list files changed after this time" -x
+complete -c exa        -l 'changed-within' -d "Only list files changed after this time" -x
+complete -c exa        -l 'older-than'     -d "Only list files changed before this time" -x
+complete -c exa        -l 'owner'          -d "Only list files owned by this user" -x -a "(__fish_complete_users)"
+complete -c exa        -l 'owner-group'    -d "Only list files owned by this group" -x -a "(__fish_complete_groups)"
+complete -c exa        -l 'only-dirs'      -d "Only list directories"
+complete -c exa        -l 'only-files'     -d "Only list regular files"
+complete -c exa        -l 'only-links'     -d "Only list symbolic links"
+complete -c exa        -l 'only-types'     -d "Only list these types of file" -x -a "dirs files links sockets pipes devices"
+complete -c exa        -l 'perm'           -d "Only list files with these permission bits" -x
+complete -c exa        -l 'category'       -d "Only list these kinds of file" -x -a "image video audio document archive code crypto temp compiled"
+complete -c exa        -l 'xattr-filter'   -d "Only list files with this extended attribute" -x
+complete -c exa        -l 'empty'          -d "Only list empty files and directories"
+complete -c exa        -l 'non-empty'      -d "Only list files and directories that aren't empty"
+complete -c exa        -l 'dot-ignore'     -d "Skip files listed in .ignore and .fdignore files"
+complete -c exa        -l 'dedupe'         -d "Only list the first of several links to the same file"
+complete -c exa        -l 'normalize'      -x -d "Unicode normalization form to put file names in" -a "nfc nfd"
+complete -c exa        -l 'max-results'    -d "Only list this many files in total" -x
+complete -c exa        -l 'since'          -d "Only list files added or changed since a snapshot" -r
 
 # Long view options
 complete -c exa -s 'b' -l 'binary'   -d "List file sizes with binary prefixes"
@@ -67,3 +112,6 @@ complete -c exa        -l 'time-style' -x -d "How to format timestamps" -a "
 # Optional extras
 complete -c exa -s 'g' -l 'git'      -d "List each file's Git status, if tracked"
 complete -c exa -s '@' -l 'extended' -d "List each file's extended attributes and sizes"
+complete -c exa        -l 'xattr-column' -x -d "List each file's value for this extended attribute"
+complete -c exa        -l 'git-only'    -x -d "Only list files with these Git statuses" -a "new untracked modified deleted renamed typechange ignored clean"
+complete -c exa        -l 'git-exclude' -x -d "Don't list files with these Git statuses" -a "new untracked modified deleted renamed typechange ignored clean"
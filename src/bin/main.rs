@@ -1,5 +1,6 @@
 extern crate exa;
-use exa::Exa;
+use exa::{Exa, interrupt};
+use exa::options::{add_config_options, add_env_options, translate_ls_flags};
 
 use std::ffi::OsString;
 use std::env::args_os;
@@ -8,16 +9,28 @@ use std::process::exit;
 
 
 fn main() {
-    let args: Vec<OsString> = args_os().skip(1).collect();
+    let args = add_env_options(args_os().skip(1).collect())
+                   .map(translate_ls_flags)
+                   .and_then(add_config_options);
+
+    let args: Vec<OsString> = match args {
+        Ok(args)  => args,
+        Err(e)    => {
+            let _ = writeln!(stderr(), "{}", e);
+            exit(exits::OPTIONS_ERROR);
+        },
+    };
     match Exa::new(args.iter(), &mut stdout()) {
         Ok(mut exa) => {
+            interrupt::install();
+
             match exa.run() {
                 Ok(exit_status) => exit(exit_status),
                 Err(e) => {
                     match e.kind() {
-                        ErrorKind::BrokenPipe => exit(exits::SUCCESS),
+                        ErrorKind::BrokenPipe => exit(exits::BROKEN_PIPE),
                         _ => {
-                            writeln!(stderr(), "{}", e).unwrap();
+                            let _ = writeln!(stderr(), "{}", e);
                             exit(exits::RUNTIME_ERROR);
                         },
                     };
@@ -26,13 +39,15 @@ fn main() {
         },
 
         Err(ref e) if e.is_error() => {
-            writeln!(stderr(), "{}", e).unwrap();
+            let _ = writeln!(stderr(), "{}", e);
             exit(exits::OPTIONS_ERROR);
         },
 
-        Err(ref e) => {
-            writeln!(stdout(), "{}", e).unwrap();
-            exit(exits::SUCCESS);
+        // The help text can be long enough to get piped into something
+        // that stops reading before the end of it.
+        Err(ref e) => match writeln!(stdout(), "{}", e) {
+            Err(ref e) if e.kind() == ErrorKind::BrokenPipe  => exit(exits::BROKEN_PIPE),
+            _                                                => exit(exits::SUCCESS),
         },
     };
 }
@@ -44,6 +59,15 @@ mod exits {
     use libc::{self, c_int};
 
     pub const SUCCESS:       c_int = libc::EXIT_SUCCESS;
-    pub const RUNTIME_ERROR: c_int = libc::EXIT_FAILURE;
-    pub const OPTIONS_ERROR: c_int = 3 as c_int;
+    pub const OPTIONS_ERROR: c_int = 2 as c_int;
+
+    /// The status for when an error stopped the listing part of the way
+    /// through. This is different from `exa::FILE_ERROR_STATUS`, which is
+    /// for files that couldn’t be read while the rest still got listed.
+    pub const RUNTIME_ERROR: c_int = 4 as c_int;
+
+    /// The status for when the output got piped into a program that
+    /// stopped reading it, which is what the shell reports when a program
+    /// gets killed by `SIGPIPE`.
+    pub const BROKEN_PIPE:   c_int = 128 + libc::SIGPIPE;
 }
@@ -1,3 +1,45 @@
+//! exa is a modern replacement for `ls`. As well as the `exa` binary, this
+//! crate exposes its listing engine as a library, so that other tools can
+//! read, filter, sort, and render files in the same way exa does:
+//!
+//! - `fs` holds the `File` and `Dir` types, which contain files and their
+//!   metadata, and the `FileFilter` that filters and sorts them;
+//! - `output` holds the `View` and the renderers for each type of view;
+//! - `options` turns command-line arguments into the `Options` that
+//!   combine all of these.
+//!
+//! The simplest way to set everything up is to parse a list of arguments
+//! in the same way the binary does, then write the files with
+//! `render_files`, which reads their details on a pool of threads:
+//!
+//! ```no_run
+//! extern crate exa;
+//! extern crate scoped_threadpool;
+//!
+//! use std::ffi::OsString;
+//! use std::io::stdout;
+//! use std::path::PathBuf;
+//!
+//! use exa::fs::Dir;
+//! use exa::options::Options;
+//! use scoped_threadpool::Pool;
+//!
+//! # fn main() {
+//! let args = vec![ OsString::from("--long") ];
+//! let (options, _) = Options::getopts(&args).unwrap();
+//!
+//! let mut pool = Pool::new(options.threads as u32);
+//! let dir = Dir::read_dir(PathBuf::from("."), false).unwrap();
+//! let mut files: Vec<_> = dir.files(options.filter.dot_filter)
+//!                            .filter_map(Result::ok)
+//!                            .collect();
+//!
+//! options.filter.filter_child_files(&mut files);
+//! options.filter.sort_files(&mut files);
+//! exa::render_files(&options, Some(&dir), files, &mut pool, &mut stdout()).unwrap();
+//! # }
+//! ```
+
 #![warn(trivial_casts, trivial_numeric_casts)]
 #![warn(unused_results)]
 
@@ -9,8 +51,10 @@ extern crate locale;
 extern crate natord;
 extern crate num_cpus;
 extern crate number_prefix;
+extern crate regex;
 extern crate scoped_threadpool;
 extern crate term_grid;
+extern crate unicode_normalization;
 extern crate unicode_width;
 extern crate users;
 extern crate zoneinfo_compiled;
@@ -22,21 +66,31 @@ extern crate term_size;
 extern crate lazy_static;
 
 
+use std::cell::Cell;
 use std::ffi::{OsStr, OsString};
-use std::io::{stderr, Write, Result as IOResult};
-use std::path::{Component, PathBuf};
+use std::fmt;
+use std::io::{self, stderr, stdin, BufWriter, Write, Result as IOResult};
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
 
 use ansi_term::{ANSIStrings, Style};
+use scoped_threadpool::Pool;
 
 use fs::{Dir, File};
-use options::Options;
+use fs::path_list;
+use fs::spill::{self, Listing, NameOrder, SortedNames};
+use options::{Options, PathArg, Warnings};
 pub use options::Misfire;
-use output::{escape, lines, grid, grid_details, details, View, Mode};
+use output::{escape_os, lines, porcelain, grid, grid_details, details, Colours, View, Mode};
+use output::file_name::Classify;
+use log::Level;
 
-mod fs;
-mod info;
-mod options;
-mod output;
+pub mod fs;
+pub mod info;
+pub mod interrupt;
+pub mod log;
+pub mod options;
+pub mod output;
 
 
 /// The main program wrapper.
@@ -51,39 +105,82 @@ pub struct Exa<'args, 'w, W: Write + 'w> {
     pub writer: &'w mut W,
 
     /// List of the free command-line arguments that should correspond to file
-    /// names (anything that isn’t an option).
-    pub args: Vec<&'args OsStr>,
+    /// names (anything that isn’t an option), with any `@file` arguments
+    /// already expanded.
+    pub args: Vec<PathArg<'args>>,
+
+    /// Whether any files couldn’t be read, which changes the exit status.
+    /// This gets set while the options are borrowed to render files, so it
+    /// can’t be a plain `bool`.
+    errors: Cell<bool>,
+
+    /// Whether any files have been listed, which also changes the exit
+    /// status when they’re being filtered.
+    listed: bool,
+
+    /// Where to write the manifest of the listed files, if one was asked
+    /// for. This gets opened when the listing starts.
+    snapshot: Option<BufWriter<std::fs::File>>,
+
+    /// How many more files can be listed before the maximum number of
+    /// results is reached, if there is one. This counts down across every
+    /// directory in the run, not just the current one.
+    results_left: Option<usize>,
+
+    /// The threads that files’ metadata gets read on. There’s only one
+    /// pool for the whole run, rather than one being started for each
+    /// directory.
+    pool: Pool,
 }
 
 impl<'args, 'w, W: Write + 'w> Exa<'args, 'w, W> {
     pub fn new<I>(args: I, writer: &'w mut W) -> Result<Exa<'args, 'w, W>, Misfire>
     where I: Iterator<Item=&'args OsString> {
         Options::getopts(args).map(move |(options, args)| {
-            Exa { options, writer, args }
+            let results_left = options.filter.max_results;
+            let pool = Pool::new(options.threads as u32);
+            Exa { options, writer, args, errors: Cell::new(false), listed: false, snapshot: None, results_left, pool }
         })
     }
 
     pub fn run(&mut self) -> IOResult<i32> {
         let mut files = Vec::new();
         let mut dirs = Vec::new();
-        let mut exit_status = 0;
 
-        // List the current directory by default, like ls.
-        if self.args.is_empty() {
-            self.args = vec![ OsStr::new(".") ];
+        // List the current directory by default, like ls, unless the paths
+        // are coming from somewhere else.
+        if self.args.is_empty() && !self.options.stdin {
+            self.args = vec![ PathArg::Given(OsStr::new(".")) ];
         }
 
-        for file_path in &self.args {
-            match File::new(PathBuf::from(file_path), None, None) {
-                Err(e) => {
-                    exit_status = 2;
-                    writeln!(stderr(), "{:?}: {}", file_path, e)?;
-                },
+        if self.options.bench {
+            return self.run_bench();
+        }
+
+        if let Some(ref path) = self.options.snapshot {
+            let file = std::fs::File::create(path)
+                           .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))?;
+            self.snapshot = Some(BufWriter::new(file));
+        }
+
+        let spill_order = self.spill_order();
+        let names_only = self.lists_names_only();
+
+        for file_path in self.paths()? {
+            match File::new(file_path.clone(), None, None) {
+                Err(e) => self.warn(true, format_args!("{:?}: {}", file_path, e))?,
                 Ok(f) => {
                     if f.is_directory() && !self.options.dir_action.treat_dirs_as_files() {
-                        match f.to_dir(self.options.should_scan_for_git()) {
-                            Ok(d) => dirs.push(d),
-                            Err(e) => writeln!(stderr(), "{:?}: {}", file_path, e)?,
+                        let git = self.options.should_scan_for_git();
+                        let listing = match spill_order {
+                            Some(ref order)    => spill::read_dir(f.path.to_path_buf(), git, self.options.filter.dot_filter, order, self.options.spill_threshold),
+                            None if names_only => Dir::read_dir_with_types(f.path.to_path_buf(), git).map(Listing::Read),
+                            None               => f.to_dir(git).map(Listing::Read),
+                        };
+
+                        match listing {
+                            Ok(l) => dirs.push(l),
+                            Err(e) => self.warn(true, format_args!("{:?}: {}", file_path, e))?,
                         }
                     }
                     else {
@@ -101,83 +198,720 @@ impl<'args, 'w, W: Write + 'w> Exa<'args, 'w, W> {
         let is_only_dir = dirs.len() == 1 && no_files;
 
         self.options.filter.filter_argument_files(&mut files);
+        self.options.filter.dedupe_files(&mut files);
+        let overflow = self.take_results(&mut files);
         self.print_files(None, files)?;
+        self.print_overflow(overflow)?;
+
+        let mut first = no_files;
+        for listing in dirs {
+            match listing {
+                Listing::Read(dir)       => self.print_dirs(vec![ dir ], 1, &mut first, is_only_dir)?,
+                Listing::Spilled(names)  => self.print_sorted_names(names, &mut first, is_only_dir)?,
+            }
+        }
+
+        let removed = match self.options.filter.since_filter.manifest {
+            Some(ref manifest)  => manifest.removed(),
+            None                => Vec::new(),
+        };
+        self.print_removed(removed, &mut first)?;
 
-        self.print_dirs(dirs, no_files, is_only_dir, exit_status)
+        if let Some(ref mut snapshot) = self.snapshot {
+            snapshot.flush()?;
+        }
+
+        // If the user pressed Ctrl-C, the files that were read before then
+        // have been printed, so they just need telling that there would
+        // have been more.
+        if interrupt::requested() {
+            self.writer.flush()?;
+            if self.options.warnings != Warnings::Nothing {
+                writeln!(stderr(), "exa: interrupted; the listing is incomplete")?;
+            }
+            return Ok(interrupt::EXIT_STATUS);
+        }
+
+        if self.errors.get() {
+            Ok(FILE_ERROR_STATUS)
+        }
+        else if !self.listed && self.options.filter.is_filtering() {
+            Ok(NO_MATCHES_STATUS)
+        }
+        else {
+            Ok(0)
+        }
+    }
+
+    /// Prints a warning about a file that couldn’t be read, unless the
+    /// user asked for quiet, and remembers that there was one for the exit
+    /// status. Warnings about the arguments are kept with one `--quiet`.
+    fn warn(&self, about_argument: bool, message: fmt::Arguments) -> IOResult<()> {
+        self.errors.set(true);
+
+        match self.options.warnings {
+            Warnings::Everything                       => writeln!(stderr(), "{}", message),
+            Warnings::ArgumentsOnly if about_argument  => writeln!(stderr(), "{}", message),
+            _                                          => Ok(()),
+        }
     }
 
-    fn print_dirs(&mut self, dir_files: Vec<Dir>, mut first: bool, is_only_dir: bool, exit_status: i32) -> IOResult<i32> {
+    /// The paths of the files to list, which are the arguments, with any
+    /// `-` replaced by the paths read from standard input. Giving `--stdin`
+    /// adds those paths after the arguments. List files that couldn’t be
+    /// read get warned about in their place, like missing arguments.
+    fn paths(&self) -> IOResult<Vec<PathBuf>> {
+        let mut paths = Vec::new();
+
+        for arg in &self.args {
+            match *arg {
+                PathArg::Given(arg) if arg == "-"  => paths.extend(path_list::read(stdin())?),
+                PathArg::Given(arg)                => paths.push(PathBuf::from(arg)),
+                PathArg::Listed(ref path)          => paths.push(path.clone()),
+                PathArg::Unreadable(list, ref e)   => self.warn(true, format_args!("{:?}: {}", list, e))?,
+            }
+        }
+
+        if self.options.stdin {
+            paths.extend(path_list::read(stdin())?);
+        }
+
+        Ok(paths)
+    }
+
+    /// Lists the contents of the given directories, followed by the ones
+    /// inside them when recursing. The depth is that of the files inside
+    /// the directories, counted from the argument they were found under, so
+    /// the files directly inside an argument are at depth 1.
+    fn print_dirs(&mut self, dir_files: Vec<Dir>, depth: usize, first: &mut bool, is_only_dir: bool) -> IOResult<()> {
         for dir in dir_files {
+            if interrupt::requested() {
+                break;
+            }
+
+            // Directories above the minimum depth don’t get listed, but still
+            // get recursed into.
+            let too_shallow = self.options.dir_action.recurse_options()
+                                  .map_or(false, |r| !r.tree && r.is_too_shallow(depth));
 
-            // Put a gap between directories, or between the list of files and
-            // the first directory.
-            if first {
-                first = false;
+            if !too_shallow {
+                self.print_dir_heading(&dir.path, first, is_only_dir)?;
             }
-            else {
-                write!(self.writer, "\n")?;
+
+            if self.lists_names_only() {
+                self.print_names(&dir)?;
+                continue;
             }
 
-            if !is_only_dir {
-                let mut bits = Vec::new();
-                escape(dir.path.display().to_string(), &mut bits, Style::default(), Style::default());
-                writeln!(self.writer, "{}:", ANSIStrings(&bits))?;
+            if let Some(height) = self.screen_height() {
+                self.print_by_screen(&dir, height)?;
+                continue;
             }
 
+            if self.can_stream() {
+                let child_dirs = self.stream_files(&dir, depth, too_shallow)?;
+                self.print_dirs(child_dirs, depth + 1, first, false)?;
+                continue;
+            }
+
+            let timer = log::time("stat", &dir.path);
             let mut children = Vec::new();
-            for file in dir.files(self.options.filter.dot_filter) {
+            for file in dir.files_in_parallel(self.options.filter.dot_filter, &mut self.pool) {
                 match file {
                     Ok(file)       => children.push(file),
-                    Err((path, e)) => writeln!(stderr(), "[{}: {}]", path.display(), e)?,
+                    Err((path, e)) => self.warn(false, format_args!("[{}: {}]", path.display(), e))?,
                 }
             };
+            drop(timer);
 
+            let read = children.len();
+            let timer = log::time("sort", &dir.path);
+            self.options.filter.normalize_files(&mut children);
             self.options.filter.filter_child_files(&mut children);
             self.options.filter.sort_files(&mut children);
+            self.options.filter.dedupe_files(&mut children);
+            drop(timer);
+
+            let overflow = self.take_results(&mut children);
+            log::log("dirs", Level::Info, format_args!("{}: {} files read, {} listed", dir.path.display(), read, children.len()));
 
             if let Some(recurse_opts) = self.options.dir_action.recurse_options() {
-                let depth = dir.path.components().filter(|&c| c != Component::CurDir).count() + 1;
                 if !recurse_opts.tree && !recurse_opts.is_too_deep(depth) {
 
                     let mut child_dirs = Vec::new();
                     for child_dir in children.iter().filter(|f| f.is_directory()) {
-                        match child_dir.to_dir(false) {
+                        match child_dir.to_dir(self.options.filter.needs_git()) {
                             Ok(d)  => child_dirs.push(d),
-                            Err(e) => writeln!(stderr(), "{}: {}", child_dir.path.display(), e)?,
+                            Err(e) => self.warn(false, format_args!("{}: {}", child_dir.path.display(), e))?,
                         }
                     }
 
-                    self.print_files(Some(&dir), children)?;
-                    match self.print_dirs(child_dirs, false, false, exit_status) {
-                        Ok(_) => (),
-                        Err(e) => return Err(e),
+                    if !too_shallow {
+                        let _timer = log::time("render", &dir.path);
+                        self.print_files(Some(&dir), children)?;
+                        self.print_overflow(overflow)?;
                     }
+
+                    self.print_dirs(child_dirs, depth + 1, first, false)?;
                     continue;
                 }
             }
 
-            self.print_files(Some(&dir), children)?;
+            if !too_shallow {
+                let _timer = log::time("render", &dir.path);
+                self.print_files(Some(&dir), children)?;
+                self.print_overflow(overflow)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Puts a gap between directories, or between the list of files and the
+    /// first directory, followed by the directory’s path, unless it’s the
+    /// only thing being listed. Porcelain formats have neither, as every
+    /// line in them already has the whole path.
+    fn print_dir_heading(&mut self, path: &Path, first: &mut bool, is_only_dir: bool) -> IOResult<()> {
+        if let Mode::Porcelain(_) = self.options.view.mode {
+            return Ok(());
+        }
+
+        if *first {
+            *first = false;
+        }
+        else {
+            write!(self.writer, "\n")?;
+        }
+
+        if !is_only_dir {
+            let mut bits = Vec::new();
+            escape_os(path.as_os_str(), &mut bits, Style::default(), Style::default());
+            writeln!(self.writer, "{}:", ANSIStrings(&bits))?;
+        }
+
+        Ok(())
+    }
+
+    /// Prints the paths of the files that were in the manifest given to
+    /// `--since` but don’t exist anymore, after everything else, under a
+    /// heading of their own. Porcelain formats leave them out, as each of
+    /// their lines describes a file that exists, but they still count as
+    /// having listed something for the exit status.
+    fn print_removed(&mut self, paths: Vec<PathBuf>, first: &mut bool) -> IOResult<()> {
+        if paths.is_empty() {
+            return Ok(());
+        }
+
+        self.listed = true;
+        if let Mode::Porcelain(_) = self.options.view.mode {
+            return Ok(());
+        }
+
+        if !*first {
+            write!(self.writer, "\n")?;
+        }
+        *first = false;
+
+        writeln!(self.writer, "Removed:")?;
+
+        let colours = &self.options.view.colours;
+        for path in paths {
+            let mut bits = Vec::new();
+            escape_os(path.as_os_str(), &mut bits, colours.git.deleted, colours.control_char);
+            writeln!(self.writer, "{}", ANSIStrings(&bits))?;
+        }
+
+        Ok(())
+    }
+
+    /// The order to sort the names of directories in if they turn out to be
+    /// too big to sort in memory, which is only possible in the lines view
+    /// when not recursing, and when the files are only being sorted by their
+    /// names. `None` means the directories always get read in full.
+    fn spill_order(&self) -> Option<NameOrder> {
+        match self.options.view.mode {
+            Mode::Lines if !self.options.filter.dedupe
+                        && self.options.filter.normalization.is_none()
+                        && !self.options.filter.needs_git()
+                        && self.options.dir_action.recurse_options().is_none() => NameOrder::for_filter(&self.options.filter),
+            _ => None,
+        }
+    }
+
+    /// Prints the files in a directory that was too big to sort in memory,
+    /// one at a time as their names get merged into order, filtering each
+    /// one as it goes.
+    ///
+    /// The files’ parent is a `Dir` with no contents, as keeping them is
+    /// what spilling avoids, so compiled files don’t get highlighted by
+    /// looking for their source files next to them.
+    fn print_sorted_names(&mut self, names: SortedNames, first: &mut bool, is_only_dir: bool) -> IOResult<()> {
+        let dir = Dir::with_contents(names.path.clone(), Vec::new(), Vec::new(), false);
+        self.print_dir_heading(&dir.path, first, is_only_dir)?;
+
+        let ignore_rules = self.options.filter.ignore_rules_for(&dir.path);
+        let mut overflow = 0;
+
+        for next in names {
+            if interrupt::requested() {
+                break;
+            }
+
+            let (path, name) = next?;
+            let file = match File::new(path.clone(), &dir, name) {
+                Ok(file) => file,
+                Err(e)   => { self.warn(false, format_args!("[{}: {}]", path.display(), e))?; continue },
+            };
+
+            if self.options.filter.is_child_ignored(&file, ignore_rules.as_ref()) {
+                continue;
+            }
+
+            if !self.take_result() {
+                overflow += 1;
+                continue;
+            }
+
+            self.print_files(Some(&dir), vec![ file ])?;
+        }
+
+        self.print_overflow(overflow)
+    }
+
+    /// Whether the files in directories can be listed using nothing but
+    /// their names, without reading their metadata: in the lines view, with
+    /// no colours or type indicators, when not recursing, and when no files
+    /// are being filtered, or sorted by anything other than their names, or
+    /// written to a manifest.
+    fn lists_names_only(&self) -> bool {
+        let filter = &self.options.filter;
+        let view = &self.options.view;
+
+        match view.mode {
+            Mode::Lines  => view.colours == Colours::plain()
+                         && view.style.classify == Classify::JustFilenames
+                         && (filter.is_unsorted() || NameOrder::for_filter(filter).is_some())
+                         && !filter.is_filtering()
+                         && !filter.dedupe
+                         && filter.normalization.is_none()
+                         && self.options.snapshot.is_none()
+                         && self.options.dir_action.recurse_options().is_none(),
+            _            => false,
+        }
+    }
+
+    /// Prints the names of the files in a directory, for when nothing else
+    /// about them is being shown. This skips making a `File` for each one,
+    /// except for symlinks, which get their targets shown too. The lines
+    /// get collected into a buffer and written in large chunks, rather than
+    /// one at a time.
+    fn print_names(&mut self, dir: &Dir) -> IOResult<()> {
+        use std::borrow::Cow;
+
+        let mut names: Vec<(Cow<str>, &OsStr, bool)> = dir.names(self.options.filter.dot_filter)
+                                                          .into_iter()
+                                                          .map(|(name, is_link)| (name.to_string_lossy(), name, is_link))
+                                                          .collect();
+
+        if let Some(order) = NameOrder::for_filter(&self.options.filter) {
+            names.sort_by(|a, b| order.compare(&a.0, &b.0));
+        }
+
+        let overflow = self.take_results(&mut names);
+        self.listed |= !names.is_empty();
+
+        let View { ref colours, ref style, .. } = self.options.view;
+        let mut buffer = Vec::with_capacity(NAMES_BUFFER_SIZE);
+
+        for (string, name, is_link) in names {
+            if interrupt::requested() {
+                break;
+            }
+
+            if is_link {
+                match File::new(dir.join(Path::new(name)), dir, string.into_owned()) {
+                    Ok(file)  => lines::Render { files: vec![ file ], colours, style }.render(&mut buffer)?,
+                    Err(e)    => self.warn(false, format_args!("[{}: {}]", dir.join(Path::new(name)).display(), e))?,
+                }
+            }
+            else if let (&Cow::Borrowed(_), true) = (&string, name.as_bytes().iter().all(|&b| b >= 0x20)) {
+                // Names that are valid UTF-8 and have no control characters
+                // don’t need escaping, so their bytes get written as they are.
+                buffer.extend_from_slice(name.as_bytes());
+                buffer.push(b'\n');
+            }
+            else {
+                let mut bits = Vec::new();
+                escape_os(name, &mut bits, Style::default(), Style::default());
+                writeln!(buffer, "{}", ANSIStrings(&bits))?;
+            }
+
+            if buffer.len() >= NAMES_BUFFER_SIZE {
+                self.writer.write_all(&buffer)?;
+                buffer.clear();
+            }
+        }
+
+        self.writer.write_all(&buffer)?;
+        self.print_overflow(overflow)
+    }
+
+    /// Whether the files in a directory can be printed as soon as they’re
+    /// read, rather than having to be collected, sorted, and laid out first.
+    /// This is only possible in the lines view and the porcelain formats,
+    /// when the files aren’t being sorted or deduplicated and aren’t being
+    /// drawn as a tree.
+    fn can_stream(&self) -> bool {
+        match self.options.view.mode {
+            Mode::Lines         |
+            Mode::Porcelain(_)  => self.options.filter.is_unsorted()
+                                && !self.options.filter.dedupe
+                                && self.options.filter.normalization.is_none()
+                                && self.options.dir_action.recurse_options().map_or(true, |r| !r.tree),
+            _                   => false,
+        }
+    }
+
+    /// Prints the files in a directory one at a time, in the order they
+    /// were read, filtering each one as it goes. When recursing, the
+    /// directories that were found get returned so they can be listed
+    /// afterwards, in the same order.
+    fn stream_files(&mut self, dir: &Dir, depth: usize, too_shallow: bool) -> IOResult<Vec<Dir>> {
+        // Files get read and printed at the same time here, so their times
+        // both get counted as reading them.
+        let _timer = log::time("stat", &dir.path);
+        let ignore_rules = self.options.filter.ignore_rules_for(&dir.path);
+        let recurse = self.options.dir_action.recurse_options()
+                          .map_or(false, |r| !r.is_too_deep(depth));
+
+        let mut child_dirs = Vec::new();
+        let mut read = 0;
+        let mut printed = 0;
+        let mut overflow = 0;
+
+        for file in dir.files(self.options.filter.dot_filter) {
+            if interrupt::requested() {
+                break;
+            }
+
+            match file {
+                Ok(file) => {
+                    read += 1;
+
+                    if self.options.filter.is_child_ignored(&file, ignore_rules.as_ref()) {
+                        continue;
+                    }
+
+                    if !self.take_result() {
+                        overflow += 1;
+                        continue;
+                    }
+
+                    printed += 1;
+
+                    if recurse && file.is_directory() {
+                        match file.to_dir(self.options.filter.needs_git()) {
+                            Ok(d)  => child_dirs.push(d),
+                            Err(e) => self.warn(false, format_args!("{}: {}", file.path.display(), e))?,
+                        }
+                    }
+
+                    if !too_shallow {
+                        self.print_files(Some(dir), vec![ file ])?;
+                    }
+                },
+                Err((path, e)) => self.warn(false, format_args!("[{}: {}]", path.display(), e))?,
+            }
+        }
+
+        if !too_shallow {
+            self.print_overflow(overflow)?;
+        }
+
+        log::log("dirs", Level::Info, format_args!("{}: {} files read, {} listed", dir.path.display(), read, printed));
+        Ok(child_dirs)
+    }
+
+    /// The height of the terminal, if the files in directories should be
+    /// printed as soon as there are enough of them to fill the screen: in
+    /// the grid view, on a terminal, when the files aren’t being sorted or
+    /// deduplicated and directories aren’t being recursed into.
+    fn screen_height(&self) -> Option<usize> {
+        use term_size::dimensions_stdout;
+
+        match self.options.view.mode {
+            Mode::Grid(_) if self.options.filter.is_unsorted()
+                          && !self.options.filter.dedupe
+                          && self.options.filter.normalization.is_none()
+                          && !self.options.deterministic
+                          && self.options.dir_action.recurse_options().is_none() => dimensions_stdout().map(|d| d.1),
+            _ => None,
+        }
+    }
+
+    /// Prints the files in a directory in the grid view, in the order they
+    /// were read, filtering each one as it goes. Once enough files have
+    /// been read to fill a screen of the given height, they get printed
+    /// straight away, and the rest of the files get a grid of their own
+    /// when the directory has been read.
+    fn print_by_screen(&mut self, dir: &Dir, height: usize) -> IOResult<()> {
+        use std::mem;
+
+        let ignore_rules = self.options.filter.ignore_rules_for(&dir.path);
+        let mut files = Vec::new();
+        let mut overflow = 0;
+        let mut screen_printed = false;
+
+        for file in dir.files(self.options.filter.dot_filter) {
+            if interrupt::requested() {
+                break;
+            }
+
+            match file {
+                Ok(file) => {
+                    if self.options.filter.is_child_ignored(&file, ignore_rules.as_ref()) {
+                        continue;
+                    }
+
+                    if !self.take_result() {
+                        overflow += 1;
+                        continue;
+                    }
+
+                    files.push(file);
+
+                    // Files only get laid out every screen’s height of
+                    // them, as none can fill a screen before then.
+                    if !screen_printed && files.len() % height == 0 && self.fills_screen(&files, height) {
+                        let screen = mem::replace(&mut files, Vec::new());
+                        self.print_files(Some(dir), screen)?;
+                        self.writer.flush()?;
+                        screen_printed = true;
+                    }
+                },
+                Err((path, e)) => self.warn(false, format_args!("[{}: {}]", path.display(), e))?,
+            }
+        }
+
+        self.print_files(Some(dir), files)?;
+        self.print_overflow(overflow)
+    }
+
+    /// Whether the given files would take up at least the given number of
+    /// rows in the grid view.
+    fn fills_screen(&self, files: &[File], height: usize) -> bool {
+        match self.options.view.mode {
+            Mode::Grid(ref opts)  => grid::row_count(files, &self.options.view.colours, &self.options.view.style, opts) >= height,
+            _                     => false,
         }
+    }
 
-        Ok(exit_status)
+    /// Removes every file after the number of results still left to list
+    /// from the given vector, which should already have been sorted,
+    /// counting the ones that were kept against the maximum. Returns the
+    /// number of files that were removed.
+    fn take_results<F>(&mut self, files: &mut Vec<F>) -> usize {
+        match self.results_left {
+            Some(ref mut left) if files.len() > *left => {
+                let count = files.len() - *left;
+                files.truncate(*left);
+                *left = 0;
+                count
+            },
+            Some(ref mut left) => {
+                *left -= files.len();
+                0
+            },
+            None => 0,
+        }
+    }
+
+    /// Counts one more file against the maximum number of results,
+    /// returning `false` if it has already been reached, in which case the
+    /// file shouldn’t be listed.
+    fn take_result(&mut self) -> bool {
+        match self.results_left {
+            Some(0)             => false,
+            Some(ref mut left)  => { *left -= 1; true },
+            None                => true,
+        }
+    }
+
+    /// Prints a note saying how many files weren’t listed because of the
+    /// maximum number of results, if there were any.
+    fn print_overflow(&mut self, count: usize) -> IOResult<()> {
+        if count == 0 {
+            return Ok(());
+        }
+
+        if let Mode::Porcelain(_) = self.options.view.mode {
+            return Ok(());
+        }
+
+        let numeric = if self.options.deterministic { locale::Numeric::english() }
+                      else { locale::Numeric::load_user_locale().unwrap_or_else(|_| locale::Numeric::english()) };
+
+        let note = format!("… and {} more", numeric.format_int(count));
+        writeln!(self.writer, "{}", self.options.view.colours.punctuation.paint(note))
     }
 
     /// Prints the list of files using whichever view is selected.
-    /// For various annoying logistical reasons, each one handles
-    /// printing differently...
     fn print_files(&mut self, dir: Option<&Dir>, files: Vec<File>) -> IOResult<()> {
-        if !files.is_empty() {
-            let View { ref mode, ref colours, ref style } = self.options.view;
+        self.listed |= !files.is_empty();
 
-            match *mode {
-                Mode::Lines                  => lines::Render { files, colours, style }.render(self.writer),
-                Mode::Grid(ref opts)         => grid::Render { files, colours, style, opts }.render(self.writer),
-                Mode::Details(ref opts)      => details::Render { dir, files, colours, style, opts, filter: &self.options.filter, recurse: self.options.dir_action.recurse_options() }.render(self.writer),
-                Mode::GridDetails(ref grid, ref details) => grid_details::Render { dir, files, colours, style, grid, details, filter: &self.options.filter }.render(self.writer),
+        if let Some(ref mut snapshot) = self.snapshot {
+            for file in &files {
+                porcelain::render_v1(file, snapshot)?;
             }
         }
-        else {
-            Ok(())
+        render_files(&self.options, dir, files, &mut self.pool, self.writer)
+    }
+
+    /// Times each phase of listing the directories given as arguments, and
+    /// prints how long each one took instead of the listing itself. The
+    /// files still get rendered, but into a sink, so the time the terminal
+    /// takes to display them doesn’t get counted.
+    fn run_bench(&mut self) -> IOResult<i32> {
+        use std::io::sink;
+        use std::time::Instant;
+        use fs::feature::Git;
+
+        for path in self.paths()? {
+            let start = Instant::now();
+            let dir = match Dir::read_dir(path.clone(), false) {
+                Ok(d)  => d,
+                Err(e) => {
+                    self.warn(true, format_args!("{:?}: {}", path, e))?;
+                    continue;
+                },
+            };
+            let readdir_time = start.elapsed();
+
+            let start = Instant::now();
+            let mut files: Vec<File> = dir.files_in_parallel(self.options.filter.dot_filter, &mut self.pool)
+                                          .into_iter()
+                                          .filter_map(Result::ok)
+                                          .collect();
+            let stat_time = start.elapsed();
+
+            let start = Instant::now();
+            let _ = Git::scan(&path);
+            let git_time = start.elapsed();
+
+            let start = Instant::now();
+            self.options.filter.filter_child_files(&mut files);
+            self.options.filter.sort_files(&mut files);
+            let sort_time = start.elapsed();
+
+            let count = files.len();
+            let start = Instant::now();
+            render_files(&self.options, Some(&dir), files, &mut self.pool, &mut sink())?;
+            let render_time = start.elapsed();
+
+            writeln!(self.writer, "{}: {} files", path.display(), count)?;
+            for &(phase, time) in &[ ("readdir", readdir_time), ("stat", stat_time), ("git", git_time),
+                                     ("sort", sort_time), ("render", render_time) ] {
+                let millis = time.as_secs() as f64 * 1000.0 + f64::from(time.subsec_nanos()) / 1_000_000.0;
+                writeln!(self.writer, "  {:<8} {:>10.3}ms", phase, millis)?;
+            }
         }
+
+        Ok(if self.errors.get() { FILE_ERROR_STATUS } else { 0 })
+    }
+}
+
+
+/// How many bytes of file names to collect before writing them out, when
+/// printing nothing but names.
+const NAMES_BUFFER_SIZE: usize = 64 * 1024;
+
+/// The exit status to use when some of the files couldn’t be read, though
+/// the rest got listed.
+pub const FILE_ERROR_STATUS: i32 = 1;
+
+/// The exit status to use when files were being filtered, and none of them
+/// matched.
+pub const NO_MATCHES_STATUS: i32 = 3;
+
+
+/// Renders the list of files to the given writer using whichever view is
+/// selected, reading any details it needs on the given pool of threads.
+/// For various annoying logistical reasons, each one handles printing
+/// differently...
+pub fn render_files<W: Write>(options: &Options, dir: Option<&Dir>, files: Vec<File>, pool: &mut Pool, w: &mut W) -> IOResult<()> {
+    if !files.is_empty() {
+        let View { ref mode, ref colours, ref style } = options.view;
+
+        match *mode {
+            Mode::Lines                  => lines::Render { files, colours, style }.render(w),
+            Mode::Porcelain(format)      => porcelain::Render { files, colours, style, format }.render(w),
+            Mode::Grid(ref opts)         => grid::Render { files, colours, style, opts }.render(w),
+            Mode::Details(ref opts)      => details::Render { dir, files, colours, style, opts, filter: &options.filter, recurse: options.dir_action.recurse_options() }.render(pool, w),
+            Mode::GridDetails(ref grid, ref details) => grid_details::Render { dir, files, colours, style, grid, details, filter: &options.filter }.render(w),
+        }
+    }
+    else {
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::env::temp_dir;
+    use std::fs::{create_dir_all, remove_dir_all};
+    use std::process;
+
+    /// Makes a directory tree to recurse through, with `top/a/b/file` and
+    /// `top/x` in it, under an absolute path that’s different for each test.
+    fn tree(name: &str) -> PathBuf {
+        let root = temp_dir().join(format!("exa-depth-{}-{}", name, process::id()));
+        create_dir_all(root.join("top/a/b")).unwrap();
+        let _ = std::fs::File::create(root.join("top/a/b/file")).unwrap();
+        let _ = std::fs::File::create(root.join("top/x")).unwrap();
+        root
+    }
+
+    fn list(args: &[&str], path: &Path) -> String {
+        let mut args: Vec<OsString> = args.iter().map(OsString::from).collect();
+        args.push(path.as_os_str().to_os_string());
+
+        let mut output = Vec::new();
+        {
+            let mut exa = Exa::new(args.iter(), &mut output).unwrap();
+            let _ = exa.run().unwrap();
+        }
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn min_depth_from_absolute_path() {
+        let root = tree("absolute");
+        let output = list(&[ "-1R", "--colour=never", "--min-depth=2" ], &root.join("top"));
+        remove_dir_all(&root).unwrap();
+
+        let expected = format!("{0}/top/a:\nb\n\n{0}/top/a/b:\nfile\n", root.display());
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn min_depth_from_deeper_path() {
+        let root = tree("deeper");
+        let output = list(&[ "-1R", "--colour=never", "--min-depth=2" ], &root.join("top/a"));
+        remove_dir_all(&root).unwrap();
+
+        let expected = format!("{}/top/a/b:\nfile\n", root.display());
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn level_from_absolute_path() {
+        let root = tree("level");
+        let output = list(&[ "-1R", "--colour=never", "--level=2" ], &root.join("top"));
+        remove_dir_all(&root).unwrap();
+
+        let expected = format!("a\nx\n\n{}/top/a:\nb\n", root.display());
+        assert_eq!(output, expected);
     }
 }
@@ -1,10 +1,15 @@
+use std::ffi::OsStr;
 use std::io::{self, Result as IOResult};
 use std::fs;
+use std::os::unix::ffi::OsStrExt;
 use std::path::{Path, PathBuf};
 use std::slice::Iter as SliceIter;
 
+use scoped_threadpool::Pool;
+
 use fs::feature::Git;
 use fs::{File, fields};
+use log::{self, Level};
 
 
 /// A **Dir** provides a cached list of the file paths in a directory that's
@@ -18,6 +23,11 @@ pub struct Dir {
     /// A vector of the files that have been read from this directory.
     contents: Vec<PathBuf>,
 
+    /// The types of the files in `contents`, in the same order, as reported
+    /// by the directory itself. This is empty unless the directory was read
+    /// with `read_dir_with_types`.
+    types: Vec<fs::FileType>,
+
     /// The path that was read.
     pub path: PathBuf,
 
@@ -37,12 +47,54 @@ impl Dir {
     /// entries, so if the user wants to see them, we’ll have to add them
     /// ourselves after the files have been read.
     pub fn read_dir(path: PathBuf, git: bool) -> IOResult<Dir> {
+        let timer = log::time("readdir", &path);
         let contents: Vec<PathBuf> = try!(fs::read_dir(&path)?
                                                  .map(|result| result.map(|entry| entry.path()))
                                                  .collect());
+        drop(timer);
+
+        Ok(Dir::with_contents(path, contents, Vec::new(), git))
+    }
+
+    /// Create a new Dir object in the same way as `read_dir`, but also keep
+    /// the type of each file, so they can be listed by name without having
+    /// to read their metadata.
+    ///
+    /// Most filesystems report each file’s type along with its name, but
+    /// on the ones that don’t, this has to read the metadata anyway, so it’s
+    /// only worth doing when nothing else is going to need it.
+    pub fn read_dir_with_types(path: PathBuf, git: bool) -> IOResult<Dir> {
+        let mut contents = Vec::new();
+        let mut types = Vec::new();
+
+        let timer = log::time("readdir", &path);
+        for entry in fs::read_dir(&path)? {
+            let entry = entry?;
+            types.push(entry.file_type()?);
+            contents.push(entry.path());
+        }
+        drop(timer);
 
-        let git = if git { Git::scan(&path).ok() } else { None };
-        Ok(Dir { contents, path, git })
+        Ok(Dir::with_contents(path, contents, types, git))
+    }
+
+    /// Create a new Dir object from the paths of the files that have
+    /// already been read from the directory at the given path, and their
+    /// types, if they’re known.
+    pub fn with_contents(path: PathBuf, contents: Vec<PathBuf>, types: Vec<fs::FileType>, git: bool) -> Dir {
+        let git = if git { Dir::scan_for_git(&path) } else { None };
+        Dir { contents, types, path, git }
+    }
+
+    /// Looks for the Git repository that the directory at the given path
+    /// is in, if any, logging how long it took.
+    fn scan_for_git(path: &Path) -> Option<Git> {
+        let _timer = log::time("git", path);
+        let git = Git::scan(path).ok();
+
+        let found = if git.is_some() { "in a repository" } else { "not in a repository" };
+        log::log("git", Level::Trace, format_args!("{} is {}", path.display(), found));
+        git
     }
 
     /// Produce an iterator of IO results of trying to read all the files in
@@ -56,6 +108,90 @@ impl Dir {
         }
     }
 
+    /// Read all the files in this directory at once, getting their metadata
+    /// on a pool of threads. The results are in the same order that `files`
+    /// would produce them.
+    ///
+    /// Each file needs its own `lstat` call, and on big directories or slow
+    /// network filesystems the time spent waiting for these dominates, so
+    /// it’s worth having several of them in flight, one for each thread in
+    /// the given pool. Small directories aren’t worth handing to the pool,
+    /// and get read on this thread.
+    pub fn files_in_parallel<'dir>(&'dir self, dots: DotFilter, pool: &mut Pool) -> Vec<Result<File<'dir>, (PathBuf, io::Error)>> {
+        let mut files = self.files(dots);
+        let mut results = Vec::with_capacity(self.contents.len() + 2);
+
+        // The `.` and `..` entries, if any, come first.
+        loop {
+            if let Dots::FilesNext = files.dots { break }
+            results.extend(files.next());
+        }
+
+        let dotfiles = files.dotfiles;
+        let paths: Vec<(&PathBuf, String)> = files.inner
+            .map(|path| (path, File::filename(path)))
+            .filter(|&(_, ref filename)| dotfiles || !filename.starts_with("."))
+            .collect();
+
+        let threads = pool.thread_count() as usize;
+        if threads <= 1 || paths.len() < PARALLEL_THRESHOLD {
+            results.extend(paths.into_iter().map(|(path, filename)| self.file_at(path, filename)));
+            return results;
+        }
+
+        let mut slots: Vec<Option<Result<File, (PathBuf, io::Error)>>> = paths.iter().map(|_| None).collect();
+        let chunk_size = (paths.len() + threads - 1) / threads;
+
+        pool.scoped(|scoped| {
+            for (chunk, outputs) in paths.chunks(chunk_size).zip(slots.chunks_mut(chunk_size)) {
+                scoped.execute(move || {
+                    for (&(path, ref filename), output) in chunk.iter().zip(outputs.iter_mut()) {
+                        *output = Some(self.file_at(path, filename.clone()));
+                    }
+                });
+            }
+        });
+
+        results.extend(slots.into_iter().filter_map(|slot| slot));
+        results
+    }
+
+    /// The names of the files in this directory, in the order they were
+    /// read, along with whether each one is a symlink, without reading any
+    /// of their metadata. Files whose types weren’t kept when the directory
+    /// was read count as symlinks, as they might be.
+    pub fn names(&self, dots: DotFilter) -> Vec<(&OsStr, bool)> {
+        let mut names = Vec::with_capacity(self.contents.len() + 2);
+
+        if let Dots::DotNext = dots.dots() {
+            names.push((OsStr::new("."), false));
+            names.push((OsStr::new(".."), false));
+        }
+
+        for (index, path) in self.contents.iter().enumerate() {
+            let name = match path.file_name() {
+                Some(name)  => name,
+                None        => continue,
+            };
+
+            if !dots.shows_dotfiles() && name.as_bytes().first() == Some(&b'.') {
+                continue;
+            }
+
+            let is_link = self.types.get(index).map_or(true, |t| t.is_symlink());
+            names.push((name, is_link));
+        }
+
+        names
+    }
+
+    /// Get the metadata for one of the paths that has been read from this
+    /// directory, turning it into a `File`.
+    fn file_at<'dir>(&'dir self, path: &'dir PathBuf, filename: String) -> Result<File<'dir>, (PathBuf, io::Error)> {
+        File::new(path.as_path(), self, filename)
+            .map_err(|e| (path.clone(), e))
+    }
+
     /// Whether this directory contains a file with the given path.
     pub fn contains(&self, path: &Path) -> bool {
         self.contents.iter().any(|p| p.as_path() == path)
@@ -82,6 +218,11 @@ impl Dir {
 }
 
 
+/// The number of files a directory needs to have before it’s worth reading
+/// their metadata on more than one thread.
+const PARALLEL_THRESHOLD: usize = 64;
+
+
 /// Iterator over reading the contents of a directory as `File` objects.
 pub struct Files<'dir> {
 
@@ -117,7 +258,7 @@ impl<'dir> Files<'dir> {
                 let filename = File::filename(path);
                 if !self.dotfiles && filename.starts_with(".") { continue }
 
-                return Some(File::new(path.clone(), self.dir, filename)
+                return Some(File::new(path.as_path(), self.dir, filename)
                                  .map_err(|e| (path.clone(), e)))
             }
             else {
@@ -148,7 +289,7 @@ impl<'dir> Iterator for Files<'dir> {
     fn next(&mut self) -> Option<Self::Item> {
         if let Dots::DotNext = self.dots {
             self.dots = Dots::DotDotNext;
-            Some(File::new(self.dir.path.to_path_buf(), self.dir, String::from("."))
+            Some(File::new(self.dir.path.as_path(), self.dir, String::from("."))
                       .map_err(|e| (Path::new(".").to_path_buf(), e)))
         }
         else if let Dots::DotDotNext = self.dots {
@@ -188,7 +329,7 @@ impl Default for DotFilter {
 impl DotFilter {
 
     /// Whether this filter should show dotfiles in a listing.
-    fn shows_dotfiles(&self) -> bool {
+    pub fn shows_dotfiles(&self) -> bool {
         match *self {
             DotFilter::JustFiles       => false,
             DotFilter::Dotfiles        => true,
@@ -48,6 +48,10 @@ pub struct RecurseOptions {
     /// The maximum number of times that recursion should descend to, if one
     /// is specified.
     pub max_depth: Option<usize>,
+
+    /// The minimum depth that files have to be at to be listed, if one is
+    /// specified. Directories above this depth still get recursed into.
+    pub min_depth: Option<usize>,
 }
 
 impl RecurseOptions {
@@ -61,4 +65,13 @@ impl RecurseOptions {
             }
         }
     }
+
+    /// Returns whether the files at the given depth would be too shallow
+    /// to be listed.
+    pub fn is_too_shallow(&self, depth: usize) -> bool {
+        match self.min_depth {
+            None    => false,
+            Some(d) => depth < d,
+        }
+    }
 }
\ No newline at end of file
@@ -40,10 +40,13 @@ impl Git {
 
     /// Get the combined status for all the files whose paths begin with the
     /// path that gets passed in. This is used for getting the status of
-    /// directories, which don't really have an 'official' status.
+    /// directories, which don't really have an 'official' status. Ignored
+    /// files inside the directory don't count, as that would make it look
+    /// ignored itself; only the directory being ignored does.
     pub fn dir_status(&self, dir: &Path) -> f::Git {
         let s = self.statuses.iter()
                              .filter(|p| p.0.starts_with(dir))
+                             .filter(|p| p.0 == dir || !p.1.contains(git2::STATUS_IGNORED))
                              .fold(git2::Status::empty(), |a, b| a | b.1);
 
         f::Git { staged: index_status(s), unstaged: working_tree_status(s) }
@@ -58,6 +61,7 @@ fn working_tree_status(status: git2::Status) -> f::GitStatus {
         s if s.contains(git2::STATUS_WT_DELETED)     => f::GitStatus::Deleted,
         s if s.contains(git2::STATUS_WT_RENAMED)     => f::GitStatus::Renamed,
         s if s.contains(git2::STATUS_WT_TYPECHANGE)  => f::GitStatus::TypeChange,
+        s if s.contains(git2::STATUS_IGNORED)        => f::GitStatus::Ignored,
         _                                            => f::GitStatus::NotModified,
     }
 }
@@ -74,3 +78,32 @@ fn index_status(status: git2::Status) -> f::GitStatus {
         _                                               => f::GitStatus::NotModified,
     }
 }
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn git(statuses: &[(&str, git2::Status)]) -> Git {
+        Git { statuses: statuses.iter().map(|&(path, status)| (PathBuf::from(path), status)).collect() }
+    }
+
+    #[test]
+    fn ignored_file_in_tracked_dir() {
+        let git = git(&[ ("/repo/src/main.o", git2::STATUS_IGNORED) ]);
+        assert_eq!(git.dir_status(Path::new("/repo/src")).unstaged, f::GitStatus::NotModified);
+        assert_eq!(git.status(Path::new("/repo/src/main.o")).unstaged, f::GitStatus::Ignored);
+    }
+
+    #[test]
+    fn ignored_dir() {
+        let git = git(&[ ("/repo/target/", git2::STATUS_IGNORED) ]);
+        assert_eq!(git.dir_status(Path::new("/repo/target")).unstaged, f::GitStatus::Ignored);
+    }
+
+    #[test]
+    fn modified_file_beside_ignored_one() {
+        let git = git(&[ ("/repo/src/main.o", git2::STATUS_IGNORED), ("/repo/src/main.c", git2::STATUS_WT_MODIFIED) ]);
+        assert_eq!(git.dir_status(Path::new("/repo/src")).unstaged, f::GitStatus::Modified);
+    }
+}
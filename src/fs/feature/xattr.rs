@@ -1,18 +1,21 @@
-//! Extended attribute support for Darwin and Linux systems.
+//! Extended attribute support for Darwin and Linux systems. This can be
+//! compiled out by turning off the `xattr` feature, in which case no file
+//! has any attributes.
 #![allow(trivial_casts)]  // for ARM
 extern crate libc;
 
 use std::io;
 use std::path::Path;
 
-pub const ENABLED: bool = cfg!(feature="git") && cfg!(any(target_os="macos", target_os="linux"));
+pub const ENABLED: bool = cfg!(feature="xattr") && cfg!(any(target_os="macos", target_os="linux"));
 
 pub trait FileAttributes {
     fn attributes(&self) -> io::Result<Vec<Attribute>>;
     fn symlink_attributes(&self) -> io::Result<Vec<Attribute>>;
+    fn attribute_value(&self, name: &str) -> io::Result<Option<Vec<u8>>>;
 }
 
-#[cfg(any(target_os = "macos", target_os = "linux"))]
+#[cfg(all(feature="xattr", any(target_os = "macos", target_os = "linux")))]
 impl FileAttributes for Path {
     fn attributes(&self) -> io::Result<Vec<Attribute>> {
         list_attrs(&lister::Lister::new(FollowSymlinks::Yes), self)
@@ -21,9 +24,13 @@ impl FileAttributes for Path {
     fn symlink_attributes(&self) -> io::Result<Vec<Attribute>> {
         list_attrs(&lister::Lister::new(FollowSymlinks::No), self)
     }
+
+    fn attribute_value(&self, name: &str) -> io::Result<Option<Vec<u8>>> {
+        get_attr(&lister::Lister::new(FollowSymlinks::Yes), self, name)
+    }
 }
 
-#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+#[cfg(not(all(feature="xattr", any(target_os = "macos", target_os = "linux"))))]
 impl FileAttributes for Path {
     fn attributes(&self) -> io::Result<Vec<Attribute>> {
         Ok(vec![])
@@ -32,10 +39,14 @@ impl FileAttributes for Path {
     fn symlink_attributes(&self) -> io::Result<Vec<Attribute>> {
         Ok(vec![])
     }
+
+    fn attribute_value(&self, _: &str) -> io::Result<Option<Vec<u8>>> {
+        Ok(None)
+    }
 }
 
 /// Attributes which can be passed to `Attribute::list_with_flags`
-#[cfg(any(target_os = "macos", target_os = "linux"))]
+#[cfg(all(feature="xattr", any(target_os = "macos", target_os = "linux")))]
 #[derive(Copy, Clone)]
 pub enum FollowSymlinks {
     Yes,
@@ -49,11 +60,12 @@ pub struct Attribute {
     pub size: usize,
 }
 
-#[cfg(any(target_os = "macos", target_os = "linux"))]
+#[cfg(all(feature="xattr", any(target_os = "macos", target_os = "linux")))]
 pub fn list_attrs(lister: &lister::Lister, path: &Path) -> io::Result<Vec<Attribute>> {
     use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
 
-    let c_path = match path.to_str().and_then(|s| { CString::new(s).ok() }) {
+    let c_path = match CString::new(path.as_os_str().as_bytes()).ok() {
         Some(cstring) => cstring,
         None => return Err(io::Error::new(io::ErrorKind::Other, "Error: path somehow contained a NUL?")),
     };
@@ -100,7 +112,51 @@ pub fn list_attrs(lister: &lister::Lister, path: &Path) -> io::Result<Vec<Attrib
     Ok(names)
 }
 
-#[cfg(target_os = "macos")]
+/// The error number that gets returned when a file doesn’t have the
+/// attribute being asked for.
+#[cfg(all(feature="xattr", target_os = "linux"))]
+const ENOATTR: i32 = libc::ENODATA;
+
+#[cfg(all(feature="xattr", target_os = "macos"))]
+const ENOATTR: i32 = libc::ENOATTR;
+
+/// Reads the value of the attribute with the given name, returning `None`
+/// if the file doesn’t have that attribute.
+#[cfg(all(feature="xattr", any(target_os = "macos", target_os = "linux")))]
+pub fn get_attr(lister: &lister::Lister, path: &Path, name: &str) -> io::Result<Option<Vec<u8>>> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = match CString::new(path.as_os_str().as_bytes()).ok() {
+        Some(cstring) => cstring,
+        None => return Err(io::Error::new(io::ErrorKind::Other, "Error: path somehow contained a NUL?")),
+    };
+
+    let c_name = match CString::new(name) {
+        Ok(cstring) => cstring,
+        Err(_)      => return Ok(None),
+    };
+
+    let bufsize = lister.getxattr(&c_path, c_name.as_bytes_with_nul());
+    if bufsize < 0 {
+        let error = io::Error::last_os_error();
+        return match error.raw_os_error() {
+            Some(ENOATTR)  => Ok(None),
+            _              => Err(error),
+        };
+    }
+
+    let mut buf = vec![0u8; bufsize as usize];
+    let size = lister.getxattr_value(&c_path, c_name.as_bytes_with_nul(), &mut buf);
+    if size < 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    buf.truncate(size as usize);
+    Ok(Some(buf))
+}
+
+#[cfg(all(feature="xattr", target_os = "macos"))]
 mod lister {
     use std::ffi::CString;
     use libc::{c_int, size_t, ssize_t, c_char, c_void, uint32_t};
@@ -167,10 +223,21 @@ mod lister {
                 )
             }
         }
+
+        pub fn getxattr_value(&self, c_path: &CString, name: &[u8], value: &mut Vec<u8>) -> ssize_t {
+            unsafe {
+                getxattr(
+                    c_path.as_ptr(),
+                    name.as_ptr() as *const c_char,
+                    value.as_mut_ptr() as *mut c_void,
+                    value.len(), 0, self.c_flags
+                )
+            }
+        }
     }
 }
 
-#[cfg(target_os = "linux")]
+#[cfg(all(feature="xattr", target_os = "linux"))]
 mod lister {
     use std::ffi::CString;
     use libc::{size_t, ssize_t, c_char, c_void};
@@ -250,5 +317,21 @@ mod lister {
                 )
             }
         }
+
+        pub fn getxattr_value(&self, c_path: &CString, name: &[u8], value: &mut Vec<u8>) -> ssize_t {
+            let getxattr = match self.follow_symlinks {
+                FollowSymlinks::Yes => getxattr,
+                FollowSymlinks::No  => lgetxattr,
+            };
+
+            unsafe {
+                getxattr(
+                    c_path.as_ptr() as *const _,
+                    name.as_ptr() as *const c_char,
+                    value.as_mut_ptr() as *mut c_void,
+                    value.len()
+                )
+            }
+        }
     }
 }
@@ -42,7 +42,7 @@ pub type uid_t = u32;
 /// regular file. (See the `filetype` module for those checks.)
 ///
 /// Its ordering is used when sorting by type.
-#[derive(PartialEq, Eq, PartialOrd, Ord)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Copy, Clone)]
 pub enum Type {
     Directory, File, Link, Pipe, Socket, CharDevice, BlockDevice, Special,
 }
@@ -176,6 +176,7 @@ pub struct Time {
 /// A file’s status in a Git repository. Whether a file is in a repository or
 /// not is handled by the Git module, rather than having a “null” variant in
 /// this enum.
+#[derive(PartialEq, Debug, Copy, Clone)]
 pub enum GitStatus {
 
     /// This file hasn’t changed since the last commit.
@@ -196,6 +197,9 @@ pub enum GitStatus {
 
     /// A file that’s had its type (such as the file permissions) changed.
     TypeChange,
+
+    /// A file that matches one of the patterns in the ignored files list.
+    Ignored,
 }
 
 /// A file’s complete Git status. It’s possible to make changes to a file, add
@@ -212,4 +216,13 @@ impl Git {
     pub fn empty() -> Git {
         Git { staged: GitStatus::NotModified, unstaged: GitStatus::NotModified }
     }
+
+    /// Whether either the staged or the unstaged status is the given one.
+    /// A file only counts as not modified if *neither* of them has changed.
+    pub fn includes(&self, status: GitStatus) -> bool {
+        match status {
+            GitStatus::NotModified => self.staged == status && self.unstaged == status,
+            _                      => self.staged == status || self.unstaged == status,
+        }
+    }
 }
@@ -1,8 +1,11 @@
 //! Files, and methods and fields to access their metadata.
 
+use std::borrow::Cow;
+use std::ffi::{OsStr, OsString};
 use std::fs;
 use std::io::Error as IOError;
 use std::io::Result as IOResult;
+use std::os::unix::ffi::OsStrExt;
 use std::os::unix::fs::{MetadataExt, PermissionsExt, FileTypeExt};
 use std::path::{Path, PathBuf};
 
@@ -26,6 +29,11 @@ pub struct File<'dir> {
     /// colour when displaying the path.
     pub name: String,
 
+    /// The bytes of this file’s name, if they aren’t valid UTF-8, so some
+    /// of them had to be replaced to make `name`. Every other name has the
+    /// same bytes as `name`, so they don’t get kept twice.
+    pub name_bytes: Option<OsString>,
+
     /// The file’s name’s extension, if present, extracted from the name.
     ///
     /// This is queried many times over, so it’s worth caching it.
@@ -36,7 +44,10 @@ pub struct File<'dir> {
     /// Even though the file's name is extracted, the path needs to be kept
     /// around, as certain operations involve looking up the file's absolute
     /// location (such as the Git status, or searching for compiled files).
-    pub path: PathBuf,
+    ///
+    /// Files read from a directory borrow their path from the list of paths
+    /// that the `Dir` already holds, rather than each having a copy.
+    pub path: Cow<'dir, Path>,
 
     /// A cached `metadata` call for this file.
     ///
@@ -54,19 +65,34 @@ pub struct File<'dir> {
     /// contain a reference to it, which is used in certain operations (such
     /// as looking up a file's Git status).
     pub parent_dir: Option<&'dir Dir>,
+
+    /// The number of other files that resolve to the same inode as this one
+    /// and were removed from the listing in its favour by `--dedupe`.
+    ///
+    /// This is always zero unless deduplication is turned on, in which case
+    /// it gets filled in after the files have been sorted.
+    pub duplicates: usize,
+
+    /// Whether another file in the same directory has a name that only
+    /// differs from this one’s in its Unicode normalization, so the two
+    /// look the same once `--normalize` has been applied to them.
+    pub normalization_clash: bool,
 }
 
 impl<'dir> File<'dir> {
-    pub fn new<PD, FN>(path: PathBuf, parent_dir: PD, filename: FN) -> IOResult<File<'dir>>
-    where PD: Into<Option<&'dir Dir>>,
+    pub fn new<P, PD, FN>(path: P, parent_dir: PD, filename: FN) -> IOResult<File<'dir>>
+    where P: Into<Cow<'dir, Path>>,
+          PD: Into<Option<&'dir Dir>>,
           FN: Into<Option<String>>
     {
+        let path       = path.into();
         let parent_dir = parent_dir.into();
         let metadata   = fs::symlink_metadata(&path)?;
         let name       = filename.into().unwrap_or_else(|| File::filename(&path));
+        let name_bytes = File::name_bytes(&path, &name);
         let ext        = File::ext(&path);
 
-        Ok(File { path, parent_dir, metadata, ext, name })
+        Ok(File { path, parent_dir, metadata, ext, name, name_bytes, duplicates: 0, normalization_clash: false })
     }
 
     /// A file’s name is derived from its string. This needs to handle directories
@@ -79,10 +105,52 @@ impl<'dir> File<'dir> {
         }
     }
 
+    /// The bytes of the given name, if it’s the last component of the given
+    /// path, and that isn’t valid UTF-8. Names such as `.` that were given
+    /// rather than taken from the path are always valid.
+    fn name_bytes(path: &Path, name: &str) -> Option<OsString> {
+        let back = match path.components().next_back() {
+            Some(back)  => back.as_os_str(),
+            None        => return None,
+        };
+
+        if back.to_str().is_none() && back.to_string_lossy() == name {
+            Some(back.to_os_string())
+        }
+        else {
+            None
+        }
+    }
+
+    /// This file’s name as the bytes to display it with. These only differ
+    /// from `name` when the name isn’t valid UTF-8, and some of its bytes
+    /// had to be replaced to turn it into a `String`.
+    pub fn raw_name(&self) -> &OsStr {
+        match self.name_bytes {
+            Some(ref bytes)  => bytes,
+            None             => OsStr::new(&self.name),
+        }
+    }
+
+    /// This file’s path without the `./` at the start, which is there for
+    /// every file listed from the current directory, as the rest of the
+    /// path already leads to the same file.
+    pub fn relative_path(&self) -> &Path {
+        use std::path::Component;
+
+        let mut components = self.path.components();
+        match components.next() {
+            Some(Component::CurDir) if components.next().is_some()  => self.path.strip_prefix(".").unwrap_or(&self.path),
+            _                                                         => &self.path,
+        }
+    }
+
     /// Extract an extension from a file path, if one is present, in lowercase.
     ///
     /// The extension is the series of characters after the last dot. This
     /// deliberately counts dotfiles, so the ".git" folder has the extension "git".
+    /// Only the bytes after the dot get converted to a string, rather than
+    /// the whole name.
     ///
     /// ASCII lowercasing is used because these extensions are only compared
     /// against a pre-compiled list of extensions which are known to only exist
@@ -91,11 +159,12 @@ impl<'dir> File<'dir> {
         use std::ascii::AsciiExt;
 
         let name = match path.file_name() {
-            Some(f) => f.to_string_lossy().to_string(),
+            Some(f) => f.as_bytes(),
             None => return None,
         };
 
-        name.rfind('.').map(|p| name[p+1..].to_ascii_lowercase())
+        name.iter().rposition(|&b| b == b'.')
+            .map(|p| String::from_utf8_lossy(&name[p+1..]).to_ascii_lowercase())
     }
 
     /// Whether this file is a directory on the filesystem.
@@ -110,7 +179,7 @@ impl<'dir> File<'dir> {
     /// Returns an IO error upon failure, but this shouldn't be used to check
     /// if a `File` is a directory or not! For that, just use `is_directory()`.
     pub fn to_dir(&self, scan_for_git: bool) -> IOResult<Dir> {
-        Dir::read_dir(self.path.clone(), scan_for_git)
+        Dir::read_dir(self.path.to_path_buf(), scan_for_git)
     }
 
     /// Whether this file is a regular file on the filesystem - that is, not a
@@ -127,6 +196,22 @@ impl<'dir> File<'dir> {
         self.is_file() && (self.metadata.permissions().mode() & bit) == bit
     }
 
+    /// Whether this file is empty: either a regular file with no bytes in
+    /// it, or a directory with no entries at all, including hidden ones.
+    /// Other types of file are never empty.
+    ///
+    /// Checking a directory involves reading it, so this is only done when
+    /// it’s actually asked for. A directory that can’t be read doesn’t count
+    /// as empty.
+    pub fn is_empty(&self) -> bool {
+        if self.is_directory() {
+            fs::read_dir(&self.path).map(|mut entries| entries.next().is_none()).unwrap_or(false)
+        }
+        else {
+            self.is_file() && self.metadata.len() == 0
+        }
+    }
+
     /// Whether this file is a symlink on the filesystem.
     pub fn is_link(&self) -> bool {
         self.metadata.file_type().is_symlink()
@@ -199,7 +284,8 @@ impl<'dir> File<'dir> {
         if let Ok(metadata) = fs::metadata(&absolute_path) {
             let ext  = File::ext(&path);
             let name = File::filename(&path);
-            FileTarget::Ok(File { parent_dir: None, path, ext, metadata, name })
+            let name_bytes = File::name_bytes(&path, &name);
+            FileTarget::Ok(File { parent_dir: None, path: Cow::Owned(path), ext, metadata, name, name_bytes, duplicates: 0, normalization_clash: false })
         }
         else {
             FileTarget::Broken(path)
@@ -517,3 +603,29 @@ mod filename_test {
         assert_eq!("/", File::filename(Path::new("/")))
     }
 }
+
+
+#[cfg(test)]
+mod name_bytes_test {
+    use super::File;
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::Path;
+
+    #[test]
+    fn valid() {
+        assert_eq!(None, File::name_bytes(Path::new("/tmp/caf\u{e9}"), "caf\u{e9}"))
+    }
+
+    #[test]
+    fn invalid() {
+        let path = Path::new(OsStr::from_bytes(b"/tmp/caf\xe9"));
+        assert_eq!(Some(OsStr::from_bytes(b"caf\xe9").to_os_string()), File::name_bytes(path, &File::filename(path)))
+    }
+
+    #[test]
+    fn given() {
+        let path = Path::new(OsStr::from_bytes(b"/tmp/caf\xe9"));
+        assert_eq!(None, File::name_bytes(path, "."))
+    }
+}
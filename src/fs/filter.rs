@@ -1,11 +1,20 @@
 use std::cmp::Ordering;
+use std::ffi::{OsStr, OsString};
+use std::os::unix::ffi::{OsStrExt, OsStringExt};
+use std::path::Path;
 use std::os::unix::fs::MetadataExt;
 
 use glob;
 use natord;
+use regex::bytes::Regex;
 
 use fs::File;
 use fs::DotFilter;
+use fs::fields as f;
+use fs::ignore::IgnoreRules;
+use fs::manifest::Manifest;
+use fs::feature::xattr::FileAttributes;
+use info::filetype::FileCategory;
 
 
 /// The **file filter** processes a vector of files before outputting them,
@@ -18,8 +27,15 @@ pub struct FileFilter {
     /// second. Some users prefer it like this.
     pub list_dirs_first: bool,
 
-    /// The metadata field to sort by.
-    pub sort_field: SortField,
+    /// The metadata fields to sort by, each with its own direction, in order
+    /// of priority: files that are equal according to one key get compared
+    /// using the next.
+    pub sort_keys: Vec<SortKey>,
+
+    /// Whether files that are equal according to every sort key should be
+    /// put in the order of their names’ bytes, rather than left in the
+    /// order they were read in, which can differ between filesystems.
+    pub ties_by_name: bool,
 
     /// Whether to reverse the sorting order. This would sort the largest
     /// files first, or files starting with Z, or the most-recently-changed
@@ -62,9 +78,73 @@ pub struct FileFilter {
     ///   this accumulated sludge.
     pub dot_filter: DotFilter,
 
+    /// Where dotfiles should be placed relative to the other files, once
+    /// they’ve been sorted.
+    pub dotfile_placement: DotfilePlacement,
+
     /// Glob patterns to ignore. Any file name that matches *any* of these
     /// patterns won't be displayed in the list.
     pub ignore_patterns: IgnorePatterns,
+
+    /// Glob patterns of directories to prune. Any directory whose name
+    /// matches one of these won’t be displayed, or recursed into.
+    pub prune_patterns: PrunePatterns,
+
+    /// Regular expressions that a file’s name must, or must not, match in
+    /// order to be displayed.
+    pub regexes: RegexFilter,
+
+    /// Sizes that a file’s size must be within in order to be displayed.
+    pub size_filter: SizeFilter,
+
+    /// Times that one of a file’s timestamps must be within in order for it
+    /// to be displayed.
+    pub time_filter: TimeFilter,
+
+    /// The users and groups that a file must, or must not, be owned by in
+    /// order to be displayed.
+    pub owner_filter: OwnerFilter,
+
+    /// The types of file that should be displayed.
+    pub type_filter: TypeFilter,
+
+    /// Permission bits that a file must, or must not, have in order to be
+    /// displayed.
+    pub perm_filter: PermFilter,
+
+    /// Extended attributes that a file must have in order to be displayed.
+    pub xattr_filter: XattrFilter,
+
+    /// The categories of file, such as images or source code, that should
+    /// be displayed.
+    pub category_filter: CategoryFilter,
+
+    /// Whether only empty files, or only non-empty ones, should be displayed.
+    pub empty_filter: EmptyFilter,
+
+    /// Whether to skip the files listed in `.ignore` and `.fdignore` files,
+    /// in the same way that ripgrep and fd do.
+    pub use_ignore_files: bool,
+
+    /// The Git statuses that a file must, or must not, have in order to be
+    /// displayed.
+    pub git_filter: GitFilter,
+
+    /// The manifest of an earlier listing, if only the files that have
+    /// been added or changed since then should be displayed.
+    pub since_filter: SinceFilter,
+
+    /// Whether to only list the first of several files that resolve to the
+    /// same inode, such as hard links to one file or symlinks to another.
+    pub dedupe: bool,
+
+    /// The Unicode normalization form to put files’ names into before
+    /// they get filtered, sorted, and displayed, if there is one.
+    pub normalization: Option<Normalization>,
+
+    /// The maximum number of files to list, counted across every directory
+    /// in the run, after they’ve been filtered and sorted, if there is one.
+    pub max_results: Option<usize>,
 }
 
 
@@ -72,7 +152,42 @@ impl FileFilter {
 /// Remove every file in the given vector that does *not* pass the
    /// filter predicate for files found inside a directory.
    pub fn filter_child_files(&self, files: &mut Vec<File>) {
-       files.retain(|f| !self.ignore_patterns.is_ignored(f));
+       let ignore_rules = match files.first().and_then(|f| f.path.parent()) {
+           Some(dir)  => self.ignore_rules_for(dir),
+           None       => None,
+       };
+
+       files.retain(|f| !self.is_child_ignored(f, ignore_rules.as_ref()));
+   }
+
+   /// Read the rules from the ignore files that apply to the given
+   /// directory, if they’re being respected at all.
+   pub fn ignore_rules_for(&self, dir: &Path) -> Option<IgnoreRules> {
+       if self.use_ignore_files {
+           Some(IgnoreRules::for_dir(dir))
+       }
+       else {
+           None
+       }
+   }
+
+   /// Whether a single file found inside a directory does *not* pass the
+   /// filter predicate, given the ignore rules for that directory.
+   pub fn is_child_ignored(&self, file: &File, ignore_rules: Option<&IgnoreRules>) -> bool {
+       self.ignore_patterns.is_ignored(file)
+           || self.prune_patterns.is_pruned(file)
+           || ignore_rules.map_or(false, |r| r.is_ignored(file))
+           || self.regexes.is_ignored(file)
+           || self.size_filter.is_ignored(file)
+           || self.time_filter.is_ignored(file)
+           || self.owner_filter.is_ignored(file)
+           || self.type_filter.is_ignored(file)
+           || self.perm_filter.is_ignored(file)
+           || self.xattr_filter.is_ignored(file)
+           || self.category_filter.is_ignored(file)
+           || self.empty_filter.is_ignored(file)
+           || self.git_filter.is_ignored(file)
+           || self.since_filter.is_ignored(file)
    }
 
    /// Remove every file in the given vector that does *not* pass the
@@ -84,62 +199,506 @@ impl FileFilter {
    /// dotfile, because it's been directly specified. But running
    /// "exa -I='*.ogg' music/*" should filter out the ogg files obtained
    /// from the glob, even though the globbing is done by the shell!
+   ///
+   /// The Git filter isn't applied here, as files specified on the
+   /// command-line aren't scanned for their Git statuses. Neither are the
+   /// include regexes, as the directory being listed hardly ever matches,
+   /// and directories aren't filtered by their owners, types, or permissions
+   /// for the same reason.
    pub fn filter_argument_files(&self, files: &mut Vec<File>) {
-       files.retain(|f| !self.ignore_patterns.is_ignored(f));
+       files.retain(|f| !self.ignore_patterns.is_ignored(f)
+                     && !self.regexes.is_excluded(f)
+                     && !self.size_filter.is_ignored(f)
+                     && !self.time_filter.is_ignored(f)
+                     && !(self.owner_filter.is_ignored(f) && !f.is_directory())
+                     && !(self.type_filter.is_ignored(f) && !f.is_directory())
+                     && !(self.perm_filter.is_ignored(f) && !f.is_directory())
+                     && !(self.xattr_filter.is_ignored(f) && !f.is_directory())
+                     && !(self.category_filter.is_ignored(f) && !f.is_directory())
+                     && !self.empty_filter.is_ignored(f)
+                     && !(self.since_filter.is_ignored(f) && !f.is_directory()));
+   }
+
+   /// Whether the directories being listed need to be scanned for their
+   /// Git statuses, either to filter or to sort their files.
+   pub fn needs_git(&self) -> bool {
+       self.git_filter.is_active() || self.sort_keys.iter().any(|k| k.field == SortField::GitStatus)
+   }
+
+   /// Whether any files would get removed from a listing by this filter,
+   /// other than dotfiles, which get hidden as the directory is read.
+   pub fn is_filtering(&self) -> bool {
+       self.ignore_patterns != IgnorePatterns::default()
+           || self.prune_patterns != PrunePatterns::default()
+           || self.regexes != RegexFilter::default()
+           || self.size_filter != SizeFilter::default()
+           || self.time_filter != TimeFilter::default()
+           || self.owner_filter != OwnerFilter::default()
+           || self.type_filter != TypeFilter::default()
+           || self.perm_filter != PermFilter::default()
+           || self.xattr_filter != XattrFilter::default()
+           || self.category_filter != CategoryFilter::default()
+           || self.empty_filter != EmptyFilter::default()
+           || self.use_ignore_files
+           || self.git_filter.is_active()
+           || self.since_filter != SinceFilter::default()
+   }
+
+   /// Whether files get listed in the order they were read from their
+   /// directory, with no sorting or rearranging at all. When this is the
+   /// case, files can be printed as soon as they’re read.
+   pub fn is_unsorted(&self) -> bool {
+       self.sort_keys.iter().all(|k| k.field == SortField::Unsorted)
+           && !self.reverse
+           && !self.list_dirs_first
+           && self.dotfile_placement == DotfilePlacement::Mixed
    }
 
    /// Sort the files in the given vector based on the sort field option.
    pub fn sort_files<'a, F>(&self, files: &mut Vec<F>)
    where F: AsRef<File<'a>> {
 
-       files.sort_by(|a, b| self.compare_files(a.as_ref(), b.as_ref()));
+       if !self.sort_keys.iter().all(|k| k.field == SortField::Unsorted) {
+           files.sort_by(|a, b| self.compare_files(a.as_ref(), b.as_ref()));
+       }
 
        if self.reverse {
            files.reverse();
        }
 
+       // Like with directories below, this relies on `sort_by` being stable.
+       match self.dotfile_placement {
+           DotfilePlacement::Mixed  => {},
+           DotfilePlacement::First  => files.sort_by(|a, b| is_dotfile(b.as_ref()).cmp(&is_dotfile(a.as_ref()))),
+           DotfilePlacement::Last   => files.sort_by(|a, b| is_dotfile(a.as_ref()).cmp(&is_dotfile(b.as_ref()))),
+       }
+
        if self.list_dirs_first {
            // This relies on the fact that `sort_by` is stable.
            files.sort_by(|a, b| b.as_ref().is_directory().cmp(&a.as_ref().is_directory()));
        }
    }
 
+   /// Put the names of the files in the given vector, which should all be
+   /// from the same directory, into the normalization form, if there is
+   /// one. Files whose names only differed in their normalization end up
+   /// with the same name, so they get marked as clashing.
+   pub fn normalize_files(&self, files: &mut Vec<File>) {
+       use std::collections::HashMap;
+
+       let form = match self.normalization {
+           Some(form)  => form,
+           None        => return,
+       };
+
+       let mut counts: HashMap<String, usize> = HashMap::new();
+       for file in files.iter_mut() {
+           file.name = form.apply(&file.name);
+           if let Some(bytes) = file.name_bytes.take() {
+               file.name_bytes = Some(form.apply_bytes(&bytes));
+           }
+           *counts.entry(file.name.clone()).or_insert(0) += 1;
+       }
+
+       for file in files.iter_mut() {
+           file.normalization_clash = counts[&file.name] > 1;
+       }
+   }
+
+   /// Remove every file that resolves to the same inode as a file before
+   /// it in the given vector, which should already have been sorted, and
+   /// count the removed files against the one that was kept.
+   pub fn dedupe_files(&self, files: &mut Vec<File>) {
+       use std::collections::HashMap;
+
+       if !self.dedupe {
+           return;
+       }
+
+       let mut firsts: HashMap<(u64, u64), usize> = HashMap::new();
+       let mut kept: Vec<File> = Vec::with_capacity(files.len());
+
+       for file in files.drain(..) {
+           let key = resolved_inode(&file);
+
+           if let Some(&index) = firsts.get(&key) {
+               kept[index].duplicates += 1;
+           }
+           else {
+               let _ = firsts.insert(key, kept.len());
+               kept.push(file);
+           }
+       }
+
+       *files = kept;
+   }
+
    /// Compares two files to determine the order they should be listed in,
-   /// depending on the search field.
+   /// depending on the sort keys. Each key only gets used to break a tie
+   /// between the files on the keys before it.
    pub fn compare_files(&self, a: &File, b: &File) -> Ordering {
-       use self::SortCase::{Sensitive, Insensitive};
-
-       match self.sort_field {
-           SortField::Unsorted  => Ordering::Equal,
-
-           SortField::Name(Sensitive)    => natord::compare(&a.name, &b.name),
-           SortField::Name(Insensitive)  => natord::compare_ignore_case(&a.name, &b.name),
-
-           SortField::Size          => a.metadata.len().cmp(&b.metadata.len()),
-           SortField::FileInode     => a.metadata.ino().cmp(&b.metadata.ino()),
-           SortField::ModifiedDate  => a.metadata.mtime().cmp(&b.metadata.mtime()),
-           SortField::AccessedDate  => a.metadata.atime().cmp(&b.metadata.atime()),
-           SortField::CreatedDate   => a.metadata.ctime().cmp(&b.metadata.ctime()),
-
-           SortField::FileType => match a.type_char().cmp(&b.type_char()) { // todo: this recomputes
-               Ordering::Equal  => natord::compare(&*a.name, &*b.name),
-               order            => order,
-           },
-
-           SortField::Extension(Sensitive) => match a.ext.cmp(&b.ext) {
-               Ordering::Equal  => natord::compare(&*a.name, &*b.name),
-               order            => order,
-           },
-
-           SortField::Extension(Insensitive) => match a.ext.cmp(&b.ext) {
-               Ordering::Equal  => natord::compare_ignore_case(&*a.name, &*b.name),
-               order            => order,
-           },
+       for key in &self.sort_keys {
+           match key.compare_files(a, b) {
+               Ordering::Equal  => continue,
+               order            => return order,
+           }
+       }
+
+       if self.ties_by_name {
+           name_bytes(a).cmp(name_bytes(b))
+       }
+       else {
+           Ordering::Equal
        }
    }
 }
 
 
+impl SortField {
+
+    /// Whether two files can be compared using this field given nothing
+    /// but their names, without having to read their metadata.
+    pub fn only_needs_names(&self) -> bool {
+        match *self {
+            SortField::Name(_)  => true,
+            _                   => false,
+        }
+    }
+
+    /// Compares two file names using just this field. Fields that need
+    /// more than the names treat every pair of names as equal.
+    pub fn compare_names(&self, a: &str, b: &str) -> Ordering {
+        use self::SortCase::{Sensitive, Insensitive};
+
+        match *self {
+            SortField::Name(Sensitive)    => natord::compare(a, b),
+            SortField::Name(Insensitive)  => natord::compare_ignore_case(a, b),
+            _                             => Ordering::Equal,
+        }
+    }
+
+    /// Compares two files using just this field.
+    pub fn compare_files(&self, a: &File, b: &File) -> Ordering {
+        use self::SortCase::{Sensitive, Insensitive};
+
+        match *self {
+            SortField::Unsorted  => Ordering::Equal,
+
+            SortField::Name(_)  => self.compare_names(&a.name, &b.name),
+
+            SortField::NameVersion => {
+                let (a, b) = (name_bytes(a), name_bytes(b));
+                match compare_versions(a, b) {
+                    Ordering::Equal  => a.cmp(b),
+                    order            => order,
+                }
+            },
+
+            SortField::NameLocale => {
+                let (a, b) = (name_bytes(a), name_bytes(b));
+                match compare_collated(a, b) {
+                    Ordering::Equal  => a.cmp(b),
+                    order            => order,
+                }
+            },
+
+            SortField::Size          => a.metadata.len().cmp(&b.metadata.len()),
+            SortField::FileInode     => a.metadata.ino().cmp(&b.metadata.ino()),
+            SortField::GitStatus     => git_rank(a).cmp(&git_rank(b)),
+            SortField::Blocks        => a.metadata.blocks().cmp(&b.metadata.blocks()),
+            SortField::Links         => a.metadata.nlink().cmp(&b.metadata.nlink()),
+            SortField::ModifiedDate  => a.metadata.mtime().cmp(&b.metadata.mtime()),
+            SortField::AccessedDate  => a.metadata.atime().cmp(&b.metadata.atime()),
+            SortField::CreatedDate   => a.metadata.ctime().cmp(&b.metadata.ctime()),
+
+            SortField::FileType => match a.type_char().cmp(&b.type_char()) { // todo: this recomputes
+                Ordering::Equal  => natord::compare(&*a.name, &*b.name),
+                order            => order,
+            },
+
+            SortField::Extension(Sensitive) => match sort_ext(a).cmp(&sort_ext(b)) {
+                Ordering::Equal  => natord::compare(&*a.name, &*b.name),
+                order            => order,
+            },
+
+            SortField::Extension(Insensitive) => match sort_ext(a).cmp(&sort_ext(b)) {
+                Ordering::Equal  => natord::compare_ignore_case(&*a.name, &*b.name),
+                order            => order,
+            },
+        }
+    }
+}
+
+
+/// Where a file should go when sorting by Git status, with the files that
+/// have pending work at the top: unstaged changes first, then staged ones,
+/// then untracked files, then ignored files, and finally unmodified files.
+fn git_rank(file: &File) -> u8 {
+    use fs::fields::GitStatus::*;
+
+    let status = file.git_status();
+    match status.unstaged {
+        Modified | Deleted | Renamed | TypeChange  => 0,
+        _ if status.staged != NotModified          => 1,
+        New                                        => 2,
+        Ignored                                    => 3,
+        NotModified                                => 4,
+    }
+}
+
+/// The extension to group a file by when sorting. Dotfiles such as
+/// `.bashrc` have no extension for this purpose, so they get grouped with
+/// the other files without one, rather than each being in its own group.
+fn sort_ext<'a>(file: &'a File) -> Option<&'a str> {
+    match file.ext {
+        Some(ref ext) if file.name.len() > ext.len() + 1  => Some(ext),
+        _                                                 => None,
+    }
+}
+
+/// The raw bytes of a file’s name, which might not be valid UTF-8.
+fn name_bytes<'a>(file: &'a File) -> &'a [u8] {
+    match file.path.file_name() {
+        Some(name)  => name.as_bytes(),
+        None        => file.name.as_bytes(),
+    }
+}
+
+/// Compares two file names using the collation rules of the user’s locale,
+/// as set by the `LC_COLLATE` or `LANG` environment variables, so accented
+/// characters sort next to their unaccented counterparts. Names that aren’t
+/// valid UTF-8 get compared byte-by-byte instead.
+fn compare_collated(a: &[u8], b: &[u8]) -> Ordering {
+    use std::ffi::CString;
+    use std::str;
+    use std::sync::Once;
+    use libc;
+
+    static SET_LOCALE: Once = Once::new();
+
+    if str::from_utf8(a).is_err() || str::from_utf8(b).is_err() {
+        return a.cmp(b);
+    }
+
+    let (a, b) = match (CString::new(a), CString::new(b)) {
+        (Ok(a), Ok(b))  => (a, b),
+        _               => return Ordering::Equal,
+    };
+
+    SET_LOCALE.call_once(|| unsafe {
+        let _ = libc::setlocale(libc::LC_COLLATE, b"\0".as_ptr() as *const libc::c_char);
+    });
+
+    unsafe { libc::strcoll(a.as_ptr(), b.as_ptr()) }.cmp(&0)
+}
+
+/// Compares two file names as version strings, in the same way as Debian
+/// compares package versions: runs of digits get compared numerically, and
+/// everything else gets compared byte-by-byte, except that letters sort
+/// before other characters, and a tilde sorts before anything, even the end
+/// of the name. This puts `v1.9.0` before `v1.10.0`, and `1.0~rc1` before
+/// `1.0`.
+///
+/// Names that only differ by leading zeroes compare as equal.
+fn compare_versions(mut a: &[u8], mut b: &[u8]) -> Ordering {
+    fn is_digit(byte: &u8) -> bool {
+        *byte >= b'0' && *byte <= b'9'
+    }
+
+    fn weight(byte: Option<&u8>) -> i32 {
+        match byte {
+            Some(&b'~')                                 => -1,
+            None                                        => 0,
+            Some(&b) if (b as char).is_alphabetic()     => i32::from(b),
+            Some(&b)                                    => i32::from(b) + 256,
+        }
+    }
+
+    fn split_while<F: Fn(&u8) -> bool>(bytes: &[u8], predicate: F) -> (&[u8], &[u8]) {
+        let index = bytes.iter().position(|b| !predicate(b)).unwrap_or(bytes.len());
+        bytes.split_at(index)
+    }
+
+    while !a.is_empty() || !b.is_empty() {
+        let (a_text, a_rest) = split_while(a, |b| !is_digit(b));
+        let (b_text, b_rest) = split_while(b, |b| !is_digit(b));
+
+        for i in 0 .. a_text.len().max(b_text.len()) {
+            match weight(a_text.get(i)).cmp(&weight(b_text.get(i))) {
+                Ordering::Equal  => continue,
+                order            => return order,
+            }
+        }
+
+        let (a_number, a_rest) = split_while(a_rest, is_digit);
+        let (b_number, b_rest) = split_while(b_rest, is_digit);
+
+        let a_number = split_while(a_number, |b| *b == b'0').1;
+        let b_number = split_while(b_number, |b| *b == b'0').1;
+
+        match a_number.len().cmp(&b_number.len()) {
+            Ordering::Equal  => match a_number.cmp(b_number) {
+                Ordering::Equal  => {},
+                order            => return order,
+            },
+            order => return order,
+        }
+
+        a = a_rest;
+        b = b_rest;
+    }
+
+    Ordering::Equal
+}
+
+
+/// The device and inode numbers of the file that the given file resolves
+/// to: the target of a symlink, or the file itself otherwise. Broken links
+/// resolve to themselves.
+fn resolved_inode(file: &File) -> (u64, u64) {
+    use std::fs;
+
+    if file.is_link() {
+        if let Ok(target) = fs::metadata(&file.path) {
+            return (target.dev(), target.ino());
+        }
+    }
+
+    (file.metadata.dev(), file.metadata.ino())
+}
+
+
+/// Whether a file is hidden by having a name that begins with a dot.
+fn is_dotfile(file: &File) -> bool {
+    file.name.starts_with('.')
+}
+
+
+/// A Unicode normalization form that files’ names can be put into, so
+/// names that look the same get displayed and sorted the same, whichever
+/// form they were written to the filesystem in.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum Normalization {
+
+    /// Canonical composition, or NFC, where an accented letter is one
+    /// character wherever possible. Linux programs usually write this.
+    Composed,
+
+    /// Canonical decomposition, or NFD, where an accented letter is its
+    /// base letter followed by combining accents. macOS writes this.
+    Decomposed,
+}
+
+impl Normalization {
+
+    /// Puts the given name into this normalization form.
+    pub fn apply(&self, name: &str) -> String {
+        use unicode_normalization::UnicodeNormalization;
+
+        match *self {
+            Normalization::Composed    => name.nfc().collect(),
+            Normalization::Decomposed  => name.nfd().collect(),
+        }
+    }
+
+    /// Puts the parts of the given name that are valid UTF-8 into this
+    /// normalization form, keeping the bytes between them as they are, so
+    /// a name that isn’t valid UTF-8 gets displayed the same way as `apply`
+    /// turns the rest of it.
+    pub fn apply_bytes(&self, name: &OsStr) -> OsString {
+        use std::str;
+
+        let mut bytes = Vec::with_capacity(name.len());
+        let mut rest = name.as_bytes();
+
+        loop {
+            match str::from_utf8(rest) {
+                Ok(valid) => {
+                    bytes.extend_from_slice(self.apply(valid).as_bytes());
+                    break;
+                },
+                Err(e) => {
+                    let (valid, invalid) = rest.split_at(e.valid_up_to());
+                    let valid = str::from_utf8(valid).unwrap_or("");  // always valid, as it’s up to the error
+                    bytes.extend_from_slice(self.apply(valid).as_bytes());
+                    bytes.push(invalid[0]);
+                    rest = &invalid[1..];
+                },
+            }
+        }
+
+        OsString::from_vec(bytes)
+    }
+}
+
+
+/// Where to place dotfiles in a sorted list of files.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum DotfilePlacement {
+
+    /// Sort dotfiles along with every other file. This is the default.
+    Mixed,
+
+    /// List all the dotfiles before the other files.
+    First,
+
+    /// List all the dotfiles after the other files.
+    Last,
+}
+
+impl Default for DotfilePlacement {
+    fn default() -> DotfilePlacement {
+        DotfilePlacement::Mixed
+    }
+}
+
+
+/// One of the keys to sort by: a field, and the direction to sort it in.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct SortKey {
+    pub field: SortField,
+    pub direction: SortDirection,
+}
+
+impl SortKey {
+
+    /// Compares two files using this key’s field, in this key’s direction.
+    pub fn compare_files(&self, a: &File, b: &File) -> Ordering {
+        match self.direction {
+            SortDirection::Ascending   => self.field.compare_files(a, b),
+            SortDirection::Descending  => self.field.compare_files(a, b).reverse(),
+        }
+    }
+
+    /// Compares two file names using this key’s field, in this key’s
+    /// direction.
+    pub fn compare_names(&self, a: &str, b: &str) -> Ordering {
+        match self.direction {
+            SortDirection::Ascending   => self.field.compare_names(a, b),
+            SortDirection::Descending  => self.field.compare_names(a, b).reverse(),
+        }
+    }
+}
+
+impl From<SortField> for SortKey {
+    fn from(field: SortField) -> SortKey {
+        SortKey { field, direction: SortDirection::Ascending }
+    }
+}
+
+/// The direction to sort a single field in. This is separate from the
+/// `--reverse` option, which reverses the entire list once it’s sorted.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum SortDirection {
+
+    /// Smallest, earliest, or first in the alphabet first.
+    Ascending,
+
+    /// Largest, latest, or last in the alphabet first.
+    Descending,
+}
+
+
 /// User-supplied field to sort by.
 #[derive(PartialEq, Debug, Copy, Clone)]
 pub enum SortField {
@@ -151,6 +710,14 @@ pub enum SortField {
     /// The file name. This is the default sorting.
     Name(SortCase),
 
+    /// The file name, compared as a version string, with runs of digits
+    /// being compared numerically. This puts `v1.9.0` before `v1.10.0`.
+    NameVersion,
+
+    /// The file name, compared using the collation rules of the user’s
+    /// locale, so accented letters sort alongside unaccented ones.
+    NameLocale,
+
     /// The file's extension, with extensionless files being listed first.
     Extension(SortCase),
 
@@ -161,6 +728,16 @@ pub enum SortField {
     /// the files were created on the hard drive.
     FileInode,
 
+    /// The number of blocks the file takes up on disk.
+    Blocks,
+
+    /// The number of hard links to the file.
+    Links,
+
+    /// The file’s Git status, with files that have uncommitted changes
+    /// being listed before those without.
+    GitStatus,
+
     /// The time at which this file was modified (the `mtime`).
     ///
     /// As this is stored as a Unix timestamp, rather than a local time
@@ -219,3 +796,484 @@ impl IgnorePatterns {
         self.patterns.iter().any(|p| p.matches(&file.name))
     }
 }
+
+
+/// Glob patterns of directories to prune. Unlike with the ignore patterns,
+/// only directories get matched, so a file with the same name as a pruned
+/// directory still gets listed.
+#[derive(PartialEq, Default, Debug, Clone)]
+pub struct PrunePatterns {
+    pub patterns: Vec<glob::Pattern>,
+}
+
+impl PrunePatterns {
+    fn is_pruned(&self, file: &File) -> bool {
+        file.is_directory() && self.patterns.iter().any(|p| p.matches(&file.name))
+    }
+}
+
+
+/// Regular expressions to filter files by. A file is only displayed if it
+/// matches one of the `include` regexes (when there are any), and none of
+/// the `exclude` ones.
+///
+/// The regexes are matched against the raw bytes of the file’s name, so
+/// names that aren’t valid UTF-8 can still be matched. A regex containing a
+/// `/` gets matched against the file’s whole path instead.
+///
+/// Directories never get hidden for failing to match an `include` regex, so
+/// that the files inside them can still be found when recursing.
+#[derive(Default, Debug, Clone)]
+pub struct RegexFilter {
+    pub include: Vec<Regex>,
+    pub exclude: Vec<Regex>,
+}
+
+impl RegexFilter {
+    fn is_ignored(&self, file: &File) -> bool {
+        if !self.include.is_empty() && !file.is_directory()
+        && !self.include.iter().any(|r| regex_matches(r, file)) {
+            return true;
+        }
+
+        self.is_excluded(file)
+    }
+
+    fn is_excluded(&self, file: &File) -> bool {
+        self.exclude.iter().any(|r| regex_matches(r, file))
+    }
+}
+
+fn regex_matches(regex: &Regex, file: &File) -> bool {
+    if regex.as_str().contains('/') {
+        regex.is_match(file.path.as_os_str().as_bytes())
+    }
+    else {
+        match file.path.file_name() {
+            Some(name) => regex.is_match(name.as_bytes()),
+            None       => regex.is_match(file.name.as_bytes()),
+        }
+    }
+}
+
+// Regexes don’t implement `PartialEq`, so compare the patterns they were
+// compiled from instead.
+impl PartialEq for RegexFilter {
+    fn eq(&self, other: &RegexFilter) -> bool {
+        fn strs(regexes: &[Regex]) -> Vec<&str> {
+            regexes.iter().map(|r| r.as_str()).collect()
+        }
+
+        strs(&self.include) == strs(&other.include)
+            && strs(&self.exclude) == strs(&other.exclude)
+    }
+}
+
+
+/// Sizes to filter files by. A file has to satisfy *every* predicate in
+/// order to be displayed, so a range can be given using one of each kind.
+///
+/// Directories don’t have a meaningful size, so they never get filtered.
+#[derive(PartialEq, Default, Debug, Clone)]
+pub struct SizeFilter {
+    pub predicates: Vec<SizePredicate>,
+}
+
+/// A comparison against a number of bytes.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum SizePredicate {
+
+    /// The file has to be larger than this many bytes.
+    LargerThan(u64),
+
+    /// The file has to be smaller than this many bytes.
+    SmallerThan(u64),
+
+    /// The file has to be exactly this many bytes.
+    Exactly(u64),
+}
+
+impl SizeFilter {
+    fn is_ignored(&self, file: &File) -> bool {
+        if file.is_directory() {
+            return false;
+        }
+
+        let size = file.metadata.len();
+        !self.predicates.iter().all(|p| p.matches(size))
+    }
+}
+
+impl SizePredicate {
+    fn matches(&self, size: u64) -> bool {
+        match *self {
+            SizePredicate::LargerThan(bytes)   => size > bytes,
+            SizePredicate::SmallerThan(bytes)  => size < bytes,
+            SizePredicate::Exactly(bytes)      => size == bytes,
+        }
+    }
+}
+
+
+/// Times to filter files by, as Unix timestamps. A file is only displayed
+/// if the chosen timestamp is after the `newer_than` time and before the
+/// `older_than` time, when either of those are given.
+///
+/// Like with sizes, directories never get filtered by time, as their
+/// timestamps only change when files are added or removed.
+#[derive(PartialEq, Default, Debug, Clone)]
+pub struct TimeFilter {
+    pub field: TimeField,
+    pub newer_than: Option<i64>,
+    pub older_than: Option<i64>,
+}
+
+/// Which of a file’s timestamps to filter by.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum TimeField {
+    Modified,
+    Accessed,
+    Created,
+}
+
+impl Default for TimeField {
+    fn default() -> TimeField {
+        TimeField::Modified
+    }
+}
+
+impl TimeFilter {
+    fn is_ignored(&self, file: &File) -> bool {
+        if file.is_directory() || (self.newer_than.is_none() && self.older_than.is_none()) {
+            return false;
+        }
+
+        let time = match self.field {
+            TimeField::Modified  => file.metadata.mtime(),
+            TimeField::Accessed  => file.metadata.atime(),
+            TimeField::Created   => file.metadata.ctime(),
+        };
+
+        if let Some(bound) = self.newer_than {
+            if time <= bound {
+                return true;
+            }
+        }
+
+        if let Some(bound) = self.older_than {
+            if time >= bound {
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+
+/// Users and groups to filter files by, as numeric IDs.
+#[derive(PartialEq, Default, Debug, Clone)]
+pub struct OwnerFilter {
+    pub users: IdFilter,
+    pub groups: IdFilter,
+}
+
+/// A set of user or group IDs. An ID is allowed if it’s in the `only` list
+/// (when that list isn’t empty), and isn’t in the `except` list.
+#[derive(PartialEq, Default, Debug, Clone)]
+pub struct IdFilter {
+    pub only: Vec<u32>,
+    pub except: Vec<u32>,
+}
+
+impl OwnerFilter {
+    fn is_ignored(&self, file: &File) -> bool {
+        !self.users.allows(file.metadata.uid()) || !self.groups.allows(file.metadata.gid())
+    }
+}
+
+impl IdFilter {
+    fn allows(&self, id: u32) -> bool {
+        (self.only.is_empty() || self.only.contains(&id)) && !self.except.contains(&id)
+    }
+}
+
+
+/// The types of file to display. An empty list means every type of file
+/// gets displayed.
+#[derive(PartialEq, Default, Debug, Clone)]
+pub struct TypeFilter {
+    pub types: Vec<f::Type>,
+
+    /// Whether directories should be kept regardless of the types, because
+    /// they need to be recursed into to find the files inside them.
+    pub keep_dirs: bool,
+}
+
+impl TypeFilter {
+    fn is_ignored(&self, file: &File) -> bool {
+        if self.types.is_empty() || (self.keep_dirs && file.is_directory()) {
+            return false;
+        }
+
+        !self.types.contains(&file.type_char())
+    }
+}
+
+
+/// The manifest of an earlier listing to compare files against, so only
+/// the ones that have been added or changed since then get displayed.
+#[derive(PartialEq, Default, Debug, Clone)]
+pub struct SinceFilter {
+    pub manifest: Option<Manifest>,
+
+    /// Whether directories should be kept whether they’ve changed or not,
+    /// because they need to be recursed into to find the files inside them.
+    pub keep_dirs: bool,
+}
+
+impl SinceFilter {
+    fn is_ignored(&self, file: &File) -> bool {
+        match self.manifest {
+            Some(ref manifest)  => !(self.keep_dirs && file.is_directory()) && manifest.is_unchanged(file),
+            None                => false,
+        }
+    }
+}
+
+
+/// The categories of file to display, judged by their names. An empty list
+/// means every file gets displayed.
+#[derive(PartialEq, Default, Debug, Clone)]
+pub struct CategoryFilter {
+    pub categories: Vec<FileCategory>,
+
+    /// Whether directories should be kept regardless of the categories,
+    /// because they need to be recursed into to find the files inside them.
+    pub keep_dirs: bool,
+}
+
+impl CategoryFilter {
+    fn is_ignored(&self, file: &File) -> bool {
+        if self.categories.is_empty() || (self.keep_dirs && file.is_directory()) {
+            return false;
+        }
+
+        !self.categories.iter().any(|c| c.includes(file))
+    }
+}
+
+
+/// Whether to display only empty files, or only non-empty ones. Nothing
+/// gets checked when neither has been asked for.
+#[derive(PartialEq, Default, Debug, Copy, Clone)]
+pub struct EmptyFilter {
+    pub emptiness: Option<Emptiness>,
+
+    /// Whether directories should be kept regardless of whether they’re
+    /// empty, because they need to be recursed into to find the empty files
+    /// inside them.
+    pub keep_dirs: bool,
+}
+
+/// Which of empty and non-empty files to display.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum Emptiness {
+
+    /// Only display zero-byte files and directories with no entries.
+    Empty,
+
+    /// Only display files that aren’t empty.
+    NonEmpty,
+}
+
+impl EmptyFilter {
+    fn is_ignored(&self, file: &File) -> bool {
+        if self.keep_dirs && file.is_directory() {
+            return false;
+        }
+
+        match self.emptiness {
+            None                      => false,
+            Some(Emptiness::Empty)    => !file.is_empty(),
+            Some(Emptiness::NonEmpty) => file.is_empty(),
+        }
+    }
+}
+
+
+/// Permission bits to filter files by. A file has to satisfy *every*
+/// predicate in order to be displayed.
+#[derive(PartialEq, Default, Debug, Clone)]
+pub struct PermFilter {
+    pub predicates: Vec<PermPredicate>,
+
+    /// Whether directories should be kept regardless of their permissions,
+    /// because they need to be recursed into to find the files inside them.
+    pub keep_dirs: bool,
+}
+
+/// A comparison against a file’s permission bits, including the setuid,
+/// setgid, and sticky bits.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum PermPredicate {
+
+    /// The file’s permission bits have to be exactly these.
+    Exactly(u32),
+
+    /// The file has to have all of these bits set.
+    AllOf(u32),
+
+    /// The file has to have at least one of these bits set.
+    AnyOf(u32),
+
+    /// The file has to have none of these bits set.
+    NoneOf(u32),
+}
+
+impl PermFilter {
+    fn is_ignored(&self, file: &File) -> bool {
+        if self.keep_dirs && file.is_directory() {
+            return false;
+        }
+
+        let mode = file.metadata.mode() & 0o7777;
+        !self.predicates.iter().all(|p| p.matches(mode))
+    }
+}
+
+impl PermPredicate {
+    fn matches(&self, mode: u32) -> bool {
+        match *self {
+            PermPredicate::Exactly(bits)  => mode == bits,
+            PermPredicate::AllOf(bits)    => mode & bits == bits,
+            PermPredicate::AnyOf(bits)    => mode & bits != 0,
+            PermPredicate::NoneOf(bits)   => mode & bits == 0,
+        }
+    }
+}
+
+
+/// Extended attributes to filter files by. A file has to have *every* one
+/// of these attributes, with the given value if there is one, in order to
+/// be displayed.
+#[derive(PartialEq, Default, Debug, Clone)]
+pub struct XattrFilter {
+    pub predicates: Vec<XattrPredicate>,
+
+    /// Whether directories should be kept regardless of their attributes,
+    /// because they need to be recursed into to find the files inside them.
+    pub keep_dirs: bool,
+}
+
+/// An extended attribute that a file has to have.
+#[derive(PartialEq, Debug, Clone)]
+pub struct XattrPredicate {
+
+    /// The attribute’s full name, including its namespace, such as
+    /// `user.tag`.
+    pub name: String,
+
+    /// The value the attribute has to have, if it matters.
+    pub value: Option<Vec<u8>>,
+}
+
+impl XattrFilter {
+    fn is_ignored(&self, file: &File) -> bool {
+        if self.predicates.is_empty() || (self.keep_dirs && file.is_directory()) {
+            return false;
+        }
+
+        !self.predicates.iter().all(|p| p.matches(file))
+    }
+}
+
+impl XattrPredicate {
+    fn matches(&self, file: &File) -> bool {
+        match file.path.attribute_value(&self.name) {
+            Ok(Some(ref value))  => self.value.as_ref().map_or(true, |v| v == value),
+            _                    => false,
+        }
+    }
+}
+
+
+/// Git statuses to filter files by. A file is only displayed if it has one
+/// of the statuses in the `only` list (when that list isn't empty), and none
+/// of the statuses in the `exclude` list.
+#[derive(PartialEq, Default, Debug, Clone)]
+pub struct GitFilter {
+    pub only: Vec<f::GitStatus>,
+    pub exclude: Vec<f::GitStatus>,
+}
+
+impl GitFilter {
+
+    /// Whether any filtering by Git status needs to be done at all, in
+    /// which case the directories being listed need to be scanned.
+    pub fn is_active(&self) -> bool {
+        !self.only.is_empty() || !self.exclude.is_empty()
+    }
+
+    fn is_ignored(&self, file: &File) -> bool {
+        if !self.is_active() {
+            return false;
+        }
+
+        let status = file.git_status();
+
+        if !self.only.is_empty() && !self.only.iter().any(|s| status.includes(*s)) {
+            return true;
+        }
+
+        self.exclude.iter().any(|s| status.includes(*s))
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::{compare_versions, Normalization};
+    use std::cmp::Ordering;
+
+    macro_rules! test_versions {
+        ($name:ident: $a:expr, $b:expr => $result:expr) => {
+            #[test]
+            fn $name() {
+                assert_eq!(compare_versions($a, $b), $result);
+            }
+        };
+    }
+
+    test_versions!(equal:     b"file",     b"file"      => Ordering::Equal);
+    test_versions!(numbers:   b"file2",    b"file10"    => Ordering::Less);
+    test_versions!(segments:  b"v1.10.0",  b"v1.9.0"    => Ordering::Greater);
+    test_versions!(zeroes:    b"img007",   b"img7"      => Ordering::Equal);
+    test_versions!(tilde:     b"1.0~rc1",  b"1.0"       => Ordering::Less);
+    test_versions!(letters:   b"1.0a",     b"1.0+"      => Ordering::Less);
+    test_versions!(prefix:    b"abc",      b"abc1"      => Ordering::Less);
+    test_versions!(invalid:   b"\xff2",    b"\xff10"    => Ordering::Less);
+
+    macro_rules! test_normalization {
+        ($name:ident: $form:ident $input:expr => $result:expr) => {
+            #[test]
+            fn $name() {
+                assert_eq!(Normalization::$form.apply($input), $result);
+            }
+        };
+    }
+
+    test_normalization!(composes:    Composed    "cafe\u{301}"  => "caf\u{e9}");
+    test_normalization!(decomposes:  Decomposed  "caf\u{e9}"    => "cafe\u{301}");
+    test_normalization!(unchanged:   Composed    "caf\u{e9}"    => "caf\u{e9}");
+    test_normalization!(ascii:       Decomposed  "file.txt"      => "file.txt");
+
+    #[test]
+    fn normalizes_around_invalid_bytes() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let name = OsStr::from_bytes(b"cafe\xcc\x81\xff-cafe\xcc\x81");
+        assert_eq!(Normalization::Composed.apply_bytes(name), OsStr::from_bytes(b"caf\xc3\xa9\xff-caf\xc3\xa9"));
+    }
+}
@@ -0,0 +1,208 @@
+//! Reading and matching the rules in `.ignore` and `.fdignore` files.
+//!
+//! These files are used by ripgrep and fd to skip files in the same way as
+//! `.gitignore` files, except that they work regardless of whether the files
+//! are in a Git repository. They use the same syntax as `.gitignore`, a
+//! reasonable subset of which is supported here: comments, negations with
+//! `!`, directory-only rules ending with `/`, and rules containing a `/`
+//! being anchored to the directory the ignore file is in.
+
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use glob::{Pattern, MatchOptions};
+
+use fs::File;
+
+
+/// The names of the ignore files that get read, in increasing order of
+/// precedence.
+static IGNORE_FILE_NAMES: &[&str] = &[ ".ignore", ".fdignore" ];
+
+
+/// All the rules that apply to the files in one directory, taken from the
+/// ignore files in that directory and every directory above it.
+#[derive(Debug)]
+pub struct IgnoreRules {
+
+    /// The canonical path of the directory whose files are being matched.
+    dir: PathBuf,
+
+    /// The rules, in increasing order of precedence: a rule later in the
+    /// list overrides an earlier one.
+    rules: Vec<Rule>,
+}
+
+impl IgnoreRules {
+
+    /// Read the rules from every ignore file in the given directory and its
+    /// ancestors. Ignore files that can’t be read are skipped.
+    pub fn for_dir(dir: &Path) -> IgnoreRules {
+        let dir = fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf());
+
+        let mut ancestors: Vec<&Path> = Vec::new();
+        let mut next = Some(dir.as_path());
+        while let Some(d) = next {
+            ancestors.push(d);
+            next = d.parent();
+        }
+
+        let mut rules = Vec::new();
+        for ancestor in ancestors.into_iter().rev() {
+            for name in IGNORE_FILE_NAMES {
+                if let Ok(file) = fs::File::open(ancestor.join(name)) {
+                    for line in BufReader::new(file).lines().filter_map(Result::ok) {
+                        rules.extend(Rule::parse(&line, ancestor));
+                    }
+                }
+            }
+        }
+
+        IgnoreRules { dir, rules }
+    }
+
+    /// Whether the given file, which has to be in this directory, is
+    /// ignored by any of the rules.
+    pub fn is_ignored(&self, file: &File) -> bool {
+        match file.path.file_name() {
+            Some(name)  => self.is_path_ignored(&self.dir.join(name), file.is_directory()),
+            None        => false,
+        }
+    }
+
+    /// Whether the file at the given canonical path is ignored. The last
+    /// rule that matches decides, so negated rules can un-ignore files.
+    fn is_path_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        self.rules.iter().rev()
+            .find(|rule| rule.matches(path, is_dir))
+            .map(|rule| !rule.negated)
+            .unwrap_or(false)
+    }
+}
+
+
+/// One line of an ignore file.
+#[derive(Debug)]
+struct Rule {
+
+    /// The directory containing the ignore file this rule came from.
+    base: PathBuf,
+
+    /// The glob pattern to match against.
+    pattern: Pattern,
+
+    /// Whether this rule un-ignores files, having started with `!`.
+    negated: bool,
+
+    /// Whether this rule only applies to directories, having ended with `/`.
+    dirs_only: bool,
+
+    /// Whether this rule gets matched against the path relative to the
+    /// base directory, rather than just the file’s name, having contained a
+    /// `/` somewhere other than the end.
+    anchored: bool,
+}
+
+impl Rule {
+
+    /// Parse a line from an ignore file, returning `None` for blank lines,
+    /// comments, and patterns that aren’t valid globs.
+    fn parse(line: &str, base: &Path) -> Option<Rule> {
+        let mut line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+
+        let negated = line.starts_with('!');
+        if negated {
+            line = &line[1..];
+        }
+
+        let dirs_only = line.ends_with('/');
+        if dirs_only {
+            line = &line[.. line.len() - 1];
+        }
+
+        let anchored = line.contains('/');
+        if line.starts_with('/') {
+            line = &line[1..];
+        }
+
+        match Pattern::new(line) {
+            Ok(pattern)  => Some(Rule { base: base.to_path_buf(), pattern, negated, dirs_only, anchored }),
+            Err(_)       => None,
+        }
+    }
+
+    /// Whether this rule matches the file at the given canonical path.
+    fn matches(&self, path: &Path, is_dir: bool) -> bool {
+        if self.dirs_only && !is_dir {
+            return false;
+        }
+
+        let relative = match path.strip_prefix(&self.base) {
+            Ok(r)   => r,
+            Err(_)  => return false,
+        };
+
+        if self.anchored {
+            let options = MatchOptions { require_literal_separator: true, .. MatchOptions::new() };
+            self.pattern.matches_path_with(relative, &options)
+        }
+        else {
+            relative.file_name().map_or(false, |name| self.pattern.matches(&name.to_string_lossy()))
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn rules(lines: &[&str]) -> IgnoreRules {
+        let base = Path::new("/project");
+        IgnoreRules {
+            dir:   base.to_path_buf(),
+            rules: lines.iter().filter_map(|line| Rule::parse(line, base)).collect(),
+        }
+    }
+
+    #[test]
+    fn names() {
+        let r = rules(&[ "*.o", "# comment", "" ]);
+        assert!(r.is_path_ignored(Path::new("/project/main.o"), false));
+        assert!(r.is_path_ignored(Path::new("/project/src/lib.o"), false));
+        assert!(!r.is_path_ignored(Path::new("/project/main.c"), false));
+    }
+
+    #[test]
+    fn negation() {
+        let r = rules(&[ "*.log", "!keep.log" ]);
+        assert!(r.is_path_ignored(Path::new("/project/debug.log"), false));
+        assert!(!r.is_path_ignored(Path::new("/project/keep.log"), false));
+    }
+
+    #[test]
+    fn dirs_only() {
+        let r = rules(&[ "target/" ]);
+        assert!(r.is_path_ignored(Path::new("/project/target"), true));
+        assert!(!r.is_path_ignored(Path::new("/project/target"), false));
+    }
+
+    #[test]
+    fn anchored() {
+        let r = rules(&[ "/build", "docs/*.html" ]);
+        assert!(r.is_path_ignored(Path::new("/project/build"), true));
+        assert!(!r.is_path_ignored(Path::new("/project/src/build"), true));
+        assert!(r.is_path_ignored(Path::new("/project/docs/index.html"), false));
+        assert!(!r.is_path_ignored(Path::new("/project/docs/api/index.html"), false));
+    }
+
+    #[test]
+    fn outside_base() {
+        let r = rules(&[ "*" ]);
+        assert!(!r.is_path_ignored(Path::new("/elsewhere/file"), false));
+    }
+}
@@ -0,0 +1,161 @@
+//! Manifests of the files in a listing, which `--snapshot` writes and
+//! `--since` reads back to list only the files that have changed since.
+//!
+//! A manifest is in the same format as `--porcelain=v1`, with one line for
+//! each file that was listed. Only the paths, sizes, and modified times in
+//! it get compared: files whose sizes and times are the same as they were
+//! count as unchanged.
+
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::fs;
+use std::os::unix::ffi::OsStrExt;
+use std::path::PathBuf;
+use std::str;
+
+use fs::File;
+
+
+/// The paths, sizes, and modified times of the files in a manifest.
+#[derive(PartialEq, Debug, Default, Clone)]
+pub struct Manifest {
+    entries: HashMap<PathBuf, Entry>,
+}
+
+/// What a file was like when the manifest was written.
+#[derive(PartialEq, Debug, Copy, Clone)]
+struct Entry {
+    size: u64,
+    modified: i64,
+}
+
+impl Manifest {
+
+    /// Reads a manifest from the contents of a file in the v1 porcelain
+    /// format. Returns a description of the first line that isn’t in that
+    /// format if there is one.
+    pub fn parse(bytes: &[u8]) -> Result<Manifest, String> {
+        let mut entries = HashMap::new();
+
+        for (index, line) in bytes.split(|&b| b == b'\n').enumerate() {
+            if line.is_empty() {
+                continue;
+            }
+
+            match parse_line(line) {
+                Some((path, entry))  => { let _ = entries.insert(path, entry); },
+                None                 => return Err(format!("line {} isn’t in the v1 porcelain format", index + 1)),
+            }
+        }
+
+        Ok(Manifest { entries })
+    }
+
+    /// Whether a file is the same size and has the same modified time as it
+    /// did when the manifest was written. Files that weren’t in it are new.
+    pub fn is_unchanged(&self, file: &File) -> bool {
+        match self.entries.get(file.relative_path()) {
+            Some(entry)  => entry.size == file.metadata.len() && entry.modified == file.modified_time().seconds,
+            None         => false,
+        }
+    }
+
+    /// The paths in the manifest of the files that don’t exist anymore, in
+    /// order. As the paths are relative, this only makes sense from the
+    /// same directory that the manifest was written in.
+    pub fn removed(&self) -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = self.entries.keys()
+                                          .filter(|path| fs::symlink_metadata(path).is_err())
+                                          .cloned()
+                                          .collect();
+        paths.sort();
+        paths
+    }
+}
+
+
+/// Reads the path, size, and modified time from one line of a manifest,
+/// which are the third, eighth, and ninth of its ten fields.
+fn parse_line(line: &[u8]) -> Option<(PathBuf, Entry)> {
+    let fields: Vec<&[u8]> = line.split(|&b| b == b'\t').collect();
+    if fields.len() != 10 {
+        return None;
+    }
+
+    let size = str::from_utf8(fields[2]).ok().and_then(|s| s.parse().ok());
+    let modified = str::from_utf8(fields[7]).ok().and_then(|s| s.parse().ok());
+    let path = unescape_field(fields[8]);
+
+    match (path, size, modified) {
+        (Some(path), Some(size), Some(modified))  => Some((PathBuf::from(OsStr::from_bytes(&path)), Entry { size, modified })),
+        _                                         => None,
+    }
+}
+
+/// Turns a field escaped for the v1 format back into the bytes it came
+/// from, or returns `None` if one of its escapes is invalid.
+fn unescape_field(field: &[u8]) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(field.len());
+    let mut rest = field.iter();
+
+    while let Some(&byte) = rest.next() {
+        if byte != b'\\' {
+            bytes.push(byte);
+            continue;
+        }
+
+        match rest.next() {
+            Some(&b'\\')  => bytes.push(b'\\'),
+            Some(&b't')   => bytes.push(b'\t'),
+            Some(&b'n')   => bytes.push(b'\n'),
+            Some(&b'x')   => {
+                let hex = rest.as_slice().get(.. 2).and_then(|h| str::from_utf8(h).ok());
+                match hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                    Some(byte)  => bytes.push(byte),
+                    None        => return None,
+                }
+
+                let _ = rest.nth(1);
+            },
+            _             => return None,
+        }
+    }
+
+    Some(bytes)
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::path::Path;
+
+    macro_rules! test {
+        ($name:ident: $input:expr => $result:expr) => {
+            #[test]
+            fn $name() {
+                assert_eq!(unescape_field($input), $result.map(|r: &[u8]| r.to_vec()));
+            }
+        };
+    }
+
+    test!(plain:      b"src/exa.rs"          => Some(&b"src/exa.rs"[..]));
+    test!(tab:        b"a\\tb"               => Some(&b"a\tb"[..]));
+    test!(newline:    b"a\\nb"               => Some(&b"a\nb"[..]));
+    test!(backslash:  b"a\\\\b"              => Some(&b"a\\b"[..]));
+    test!(hex:        b"a\\xffb\\x1b"        => Some(&b"a\xffb\x1b"[..]));
+    test!(unknown:    b"a\\qb"               => None);
+    test!(short_hex:  b"a\\xf"               => None);
+    test!(trailing:   b"a\\"                 => None);
+
+    #[test]
+    fn lines() {
+        let manifest = Manifest::parse(b"f\t0644\t12\t1\t99\t1000\t1000\t1500000000\tsrc/a\\tb.rs\t\n").unwrap();
+        assert_eq!(manifest.entries.get(Path::new("src/a\tb.rs")), Some(&Entry { size: 12, modified: 1500000000 }));
+    }
+
+    #[test]
+    fn too_few_fields() {
+        assert_eq!(Manifest::parse(b"\nf\t0644\t12\n"), Err("line 2 isn’t in the v1 porcelain format".to_string()));
+    }
+}
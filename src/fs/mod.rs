@@ -7,4 +7,8 @@ pub use self::file::{File, FileTarget};
 pub mod feature;
 pub mod fields;
 pub mod filter;
+pub mod ignore;
+pub mod manifest;
+pub mod path_list;
 pub mod dir_action;
+pub mod spill;
@@ -0,0 +1,60 @@
+//! Reading lists of paths that other programs write, such as `find` or
+//! `fd`, so the files in them can be listed as if they were arguments.
+
+use std::ffi::OsStr;
+use std::io::{Read, Result as IOResult};
+use std::os::unix::ffi::OsStrExt;
+use std::path::PathBuf;
+
+
+/// Reads a list of paths, one per line. If there’s a NUL byte anywhere in
+/// the input, as there is in the output of `find -print0`, the paths are
+/// separated by NUL bytes instead, so they can have newlines in them.
+/// Empty entries get skipped.
+pub fn read<R: Read>(mut input: R) -> IOResult<Vec<PathBuf>> {
+    let mut bytes = Vec::new();
+    let _ = input.read_to_end(&mut bytes)?;
+
+    let separator = if bytes.contains(&0) { 0 } else { b'\n' };
+    let paths = bytes.split(|&b| b == separator)
+                     .filter(|path| !path.is_empty())
+                     .map(|path| PathBuf::from(OsStr::from_bytes(path)))
+                     .collect();
+
+    Ok(paths)
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn paths(input: &[u8]) -> Vec<PathBuf> {
+        read(input).unwrap()
+    }
+
+    #[test]
+    fn lines() {
+        assert_eq!(paths(b"src/exa.rs\nREADME.md\n"), vec![ PathBuf::from("src/exa.rs"), PathBuf::from("README.md") ]);
+    }
+
+    #[test]
+    fn no_final_newline() {
+        assert_eq!(paths(b"a\nb"), vec![ PathBuf::from("a"), PathBuf::from("b") ]);
+    }
+
+    #[test]
+    fn blank_lines() {
+        assert_eq!(paths(b"\na\n\n"), vec![ PathBuf::from("a") ]);
+    }
+
+    #[test]
+    fn nul_separated() {
+        assert_eq!(paths(b"new\nline\0b\0"), vec![ PathBuf::from("new\nline"), PathBuf::from("b") ]);
+    }
+
+    #[test]
+    fn nothing() {
+        assert_eq!(paths(b""), Vec::<PathBuf>::new());
+    }
+}
@@ -0,0 +1,358 @@
+//! Sorting the files in directories that are too big to sort in memory.
+//!
+//! exa normally reads every file in a directory before sorting them, which
+//! is fine until a directory has tens of millions of entries, as maildirs
+//! and cache directories sometimes do. Once a directory has more entries
+//! than a threshold, its file names get sorted in batches, called *runs*,
+//! and each run gets written to a temporary file. The runs get merged back
+//! together as the files are printed, so only one batch of names needs to
+//! be held in memory at once.
+//!
+//! Only the names get written out, so this only works when files are being
+//! sorted by their names and nothing else.
+
+use std::cmp::Ordering;
+use std::env::temp_dir;
+use std::ffi::OsStr;
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Seek, SeekFrom, Write, Result as IOResult};
+use std::mem;
+use std::os::unix::ffi::OsStrExt;
+use std::path::PathBuf;
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+use std::vec::IntoIter as VecIntoIter;
+
+use fs::{Dir, DotFilter};
+use fs::filter::{FileFilter, SortKey, DotfilePlacement};
+
+
+/// The number of entries a directory needs to have before its names get
+/// spilled to temporary files, if the user hasn’t picked a number.
+pub const DEFAULT_THRESHOLD: usize = 1_000_000;
+
+
+/// The result of reading a directory that might be too big to sort in
+/// memory.
+pub enum Listing {
+
+    /// The directory was small enough to be read in full.
+    Read(Dir),
+
+    /// The directory had more entries than the threshold, so its names
+    /// have been sorted in runs instead.
+    Spilled(SortedNames),
+}
+
+/// Reads the directory at the given path. If it has no more entries than
+/// the threshold, they all get read into a `Dir`, along with their types,
+/// as in `Dir::read_dir_with_types`; otherwise, the names get sorted in the
+/// given order, using temporary files for all but the last batch.
+pub fn read_dir(path: PathBuf, git: bool, dots: DotFilter, order: &NameOrder, threshold: usize) -> IOResult<Listing> {
+    let mut entries = fs::read_dir(&path)?;
+    let mut contents = Vec::new();
+    let mut types = Vec::new();
+
+    while contents.len() <= threshold {
+        match entries.next() {
+            Some(entry) => {
+                let entry = entry?;
+                types.push(entry.file_type()?);
+                contents.push(entry.path());
+            },
+            None => return Ok(Listing::Read(Dir::with_contents(path, contents, types, git))),
+        }
+    }
+
+    let dotfiles = dots.shows_dotfiles();
+    let mut names = SortedNames { path, order: order.clone(), runs: Vec::new(), heads: Vec::new() };
+    let mut batch: Vec<Name> = contents.into_iter()
+                                       .filter_map(|path| path.file_name().map(Name::new))
+                                       .filter(|name| dotfiles || !name.is_dotfile())
+                                       .collect();
+
+    for entry in entries {
+        let name = Name::new(&entry?.file_name());
+        if dotfiles || !name.is_dotfile() {
+            batch.push(name);
+        }
+
+        if batch.len() >= threshold {
+            names.spill(&mut batch)?;
+        }
+    }
+
+    // The `.` and `..` entries aren’t returned by `read_dir`, but still
+    // have to be sorted with everything else if they’re being shown.
+    if dots == DotFilter::DotfilesAndDots {
+        batch.push(Name::new(OsStr::new(".")));
+        batch.push(Name::new(OsStr::new("..")));
+    }
+
+    let order = &names.order;
+    batch.sort_by(|a, b| order.compare(&a.string, &b.string));
+    names.runs.push(Run::Memory(batch.into_iter()));
+
+    names.start()?;
+    Ok(Listing::Spilled(names))
+}
+
+
+/// The order to sort file names in, for when they’re being sorted by name
+/// and nothing else.
+#[derive(Debug, Clone)]
+pub struct NameOrder {
+    keys: Vec<SortKey>,
+    reverse: bool,
+}
+
+impl NameOrder {
+
+    /// Returns the order the given filter sorts files in, if it only needs
+    /// their names to do it, or `None` if it needs anything more.
+    pub fn for_filter(filter: &FileFilter) -> Option<NameOrder> {
+        if filter.sort_keys.is_empty()
+        || !filter.sort_keys.iter().all(|k| k.field.only_needs_names())
+        || filter.list_dirs_first
+        || filter.dotfile_placement != DotfilePlacement::Mixed {
+            return None;
+        }
+
+        Some(NameOrder { keys: filter.sort_keys.clone(), reverse: filter.reverse })
+    }
+
+    /// Compares two names, in the same way the filter would compare two
+    /// files with those names.
+    pub fn compare(&self, a: &str, b: &str) -> Ordering {
+        for key in &self.keys {
+            match key.compare_names(a, b) {
+                Ordering::Equal  => continue,
+                order            => return if self.reverse { order.reverse() } else { order },
+            }
+        }
+
+        Ordering::Equal
+    }
+}
+
+
+/// One file name, both as the bytes that were read from the directory and
+/// as the (possibly lossy) string that gets compared and displayed.
+struct Name {
+    bytes: Vec<u8>,
+    string: String,
+}
+
+impl Name {
+    fn new(name: &OsStr) -> Name {
+        Name {
+            bytes:  name.as_bytes().to_vec(),
+            string: name.to_string_lossy().into_owned(),
+        }
+    }
+
+    fn is_dotfile(&self) -> bool {
+        self.string.starts_with('.')
+    }
+}
+
+
+/// A batch of names that have been put in order, ready to be merged with
+/// the other batches.
+enum Run {
+
+    /// The last batch, which never needs to leave memory.
+    Memory(VecIntoIter<Name>),
+
+    /// A batch that’s been written to a temporary file, with each name
+    /// followed by a NUL byte, which can’t appear in a file name. The file
+    /// gets deleted when the run is dropped.
+    Disk(BufReader<fs::File>, PathBuf),
+}
+
+impl Run {
+
+    /// Reads the next name from this run, if there are any left.
+    fn next(&mut self) -> IOResult<Option<Name>> {
+        match *self {
+            Run::Memory(ref mut names) => Ok(names.next()),
+            Run::Disk(ref mut reader, _) => {
+                let mut bytes = Vec::new();
+                if reader.read_until(0, &mut bytes)? == 0 {
+                    return Ok(None);
+                }
+
+                let _ = bytes.pop();
+                Ok(Some(Name::new(OsStr::from_bytes(&bytes))))
+            },
+        }
+    }
+}
+
+impl Drop for Run {
+    fn drop(&mut self) {
+        if let Run::Disk(_, ref path) = *self {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+/// A counter that keeps the temporary files’ names unique, even when more
+/// than one directory has spilled.
+static RUNS_WRITTEN: AtomicUsize = AtomicUsize::new(0);
+
+
+/// The names in a directory that was too big to sort in memory, which
+/// get merged into order as they’re iterated over.
+pub struct SortedNames {
+
+    /// The path of the directory the names were read from.
+    pub path: PathBuf,
+
+    order: NameOrder,
+    runs: Vec<Run>,
+
+    /// The next name from each run, in the same order as the runs, or
+    /// `None` for runs that have been used up.
+    heads: Vec<Option<Name>>,
+}
+
+impl SortedNames {
+
+    /// Sorts the given batch of names, then writes them to a new temporary
+    /// file, leaving the batch empty.
+    fn spill(&mut self, batch: &mut Vec<Name>) -> IOResult<()> {
+        let order = &self.order;
+        batch.sort_by(|a, b| order.compare(&a.string, &b.string));
+
+        let number = RUNS_WRITTEN.fetch_add(1, AtomicOrdering::SeqCst);
+        let path = temp_dir().join(format!("exa-sort-{}-{}", process::id(), number));
+        let mut file = OpenOptions::new().read(true).write(true).create_new(true).open(&path)?;
+
+        // The run gets created before anything is written, so the file
+        // still gets deleted if writing fails.
+        let write = {
+            let mut writer = BufWriter::new(&mut file);
+            batch.drain(..)
+                 .map(|name| writer.write_all(&name.bytes).and_then(|_| writer.write_all(b"\0")))
+                 .collect::<IOResult<()>>()
+                 .and_then(|_| writer.flush())
+        };
+
+        let seek = file.seek(SeekFrom::Start(0));
+        self.runs.push(Run::Disk(BufReader::new(file), path));
+        write?;
+        let _ = seek?;
+        Ok(())
+    }
+
+    /// Reads the first name from every run, so they can start being
+    /// merged.
+    fn start(&mut self) -> IOResult<()> {
+        for run in &mut self.runs {
+            self.heads.push(run.next()?);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the path and name of the next file in order, reading the
+    /// name that replaces it from its run.
+    fn take_next(&mut self) -> IOResult<Option<(PathBuf, String)>> {
+        let mut next: Option<usize> = None;
+
+        for (index, head) in self.heads.iter().enumerate() {
+            if let Some(ref name) = *head {
+                let is_before = match next.and_then(|n| self.heads[n].as_ref()) {
+                    Some(current)  => self.order.compare(&name.string, &current.string) == Ordering::Less,
+                    None           => true,
+                };
+
+                if is_before {
+                    next = Some(index);
+                }
+            }
+        }
+
+        let index = match next {
+            Some(index)  => index,
+            None         => return Ok(None),
+        };
+
+        let replacement = self.runs[index].next()?;
+        let name = match mem::replace(&mut self.heads[index], replacement) {
+            Some(name)  => name,
+            None        => return Ok(None),
+        };
+
+        // As with `Files`, the `.` and `..` entries are the directory
+        // itself and its parent, rather than files inside it.
+        let path = match &*name.string {
+            "."   => self.path.clone(),
+            ".."  => self.path.join(".."),
+            _     => self.path.join(OsStr::from_bytes(&name.bytes)),
+        };
+
+        Ok(Some((path, name.string)))
+    }
+}
+
+impl Iterator for SortedNames {
+    type Item = IOResult<(PathBuf, String)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.take_next() {
+            Ok(Some(next))  => Some(Ok(next)),
+            Ok(None)        => None,
+            Err(e)          => Some(Err(e)),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use fs::filter::{SortField, SortCase};
+
+    fn order(keys: Vec<SortKey>, reverse: bool) -> NameOrder {
+        NameOrder { keys, reverse }
+    }
+
+    #[test]
+    fn merges_runs_in_order() {
+        let dir = temp_dir().join(format!("exa-spill-test-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for name in &[ "b", "e", "a", "d", "c", "f10", "f9", ".hidden" ] {
+            let _ = fs::File::create(dir.join(name)).unwrap();
+        }
+
+        let order = order(vec![ SortField::Name(SortCase::Sensitive).into() ], false);
+        let names: Vec<String> = match read_dir(dir.clone(), false, DotFilter::JustFiles, &order, 2).unwrap() {
+            Listing::Spilled(names) => names.map(|n| n.unwrap().1).collect(),
+            Listing::Read(_)        => panic!("Directory should have been spilled"),
+        };
+
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(names, vec![ "a", "b", "c", "d", "e", "f9", "f10" ]);
+    }
+
+    #[test]
+    fn small_directories_get_read() {
+        let dir = temp_dir().join(format!("exa-spill-small-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let _ = fs::File::create(dir.join("file")).unwrap();
+
+        let order = order(vec![ SortField::Name(SortCase::Sensitive).into() ], false);
+        let listing = read_dir(dir.clone(), false, DotFilter::JustFiles, &order, 2).unwrap();
+
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(match listing { Listing::Read(_) => true, Listing::Spilled(_) => false });
+    }
+
+    #[test]
+    fn reversed() {
+        let order = order(vec![ SortField::Name(SortCase::Insensitive).into() ], true);
+        assert_eq!(order.compare("A", "b"), Ordering::Greater);
+    }
+}
@@ -67,6 +67,18 @@ impl FileExtensions {
         ])
     }
 
+    pub fn is_code(&self, file: &File) -> bool {
+        file.extension_is_one_of( &[
+            "c", "h", "cc", "cpp", "cxx", "hh", "hpp", "cs", "m",
+            "rs", "go", "java", "kt", "scala", "swift", "d",
+            "py", "rb", "pl", "pm", "php", "lua", "r", "jl",
+            "js", "jsx", "tsx", "coffee", "elm", "dart",
+            "hs", "ml", "mli", "fs", "ex", "exs", "erl", "clj",
+            "el", "vim", "sh", "bash", "zsh", "fish", "sql",
+            "html", "css", "scss", "sass", "less",
+        ])
+    }
+
     pub fn is_compressed(&self, file: &File) -> bool {
         file.extension_is_one_of( &[
             "zip", "tar", "Z", "gz", "bz2", "a", "ar", "7z",
@@ -92,3 +104,39 @@ impl FileExtensions {
         }
     }
 }
+
+
+/// A broad category of file, based on the tests above, that files can be
+/// filtered by.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum FileCategory {
+    Image,
+    Video,
+    Audio,
+    Document,
+    Archive,
+    Code,
+    Crypto,
+    Temp,
+    Compiled,
+}
+
+impl FileCategory {
+
+    /// Whether the given file falls into this category.
+    pub fn includes(&self, file: &File) -> bool {
+        let exts = FileExtensions;
+
+        match *self {
+            FileCategory::Image     => exts.is_image(file),
+            FileCategory::Video     => exts.is_video(file),
+            FileCategory::Audio     => exts.is_music(file) || exts.is_lossless(file),
+            FileCategory::Document  => exts.is_document(file),
+            FileCategory::Archive   => exts.is_compressed(file),
+            FileCategory::Code      => exts.is_code(file),
+            FileCategory::Crypto    => exts.is_crypto(file),
+            FileCategory::Temp      => exts.is_temp(file),
+            FileCategory::Compiled  => exts.is_compiled(file),
+        }
+    }
+}
@@ -0,0 +1,55 @@
+//! Stopping a listing part-way through when the user presses Ctrl-C.
+//!
+//! Rather than letting the default signal handler kill exa on the spot —
+//! which can leave the terminal in the middle of an escape sequence — the
+//! handler here only records that an interrupt happened. The code that
+//! walks the filesystem checks for this between directories and files, and
+//! stops going any deeper, so that whatever has already been read still
+//! gets printed before exa exits.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use libc;
+
+
+/// The exit status to use when the listing got cut short, which follows
+/// the shell convention of 128 plus the number of the signal.
+pub const EXIT_STATUS: i32 = 128 + libc::SIGINT;
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+
+/// Replaces the default handler for `SIGINT` with one that records the
+/// interrupt instead of exiting.
+///
+/// This is only meant to be called from the `exa` binary: programs using
+/// exa as a library can call `request` themselves when they want a listing
+/// to stop.
+pub fn install() {
+    let handler: extern "C" fn(libc::c_int) = handle;
+
+    unsafe {
+        let _ = libc::signal(libc::SIGINT, handler as libc::sighandler_t);
+    }
+}
+
+/// Asks for the current listing to stop as soon as possible.
+pub fn request() {
+    INTERRUPTED.store(true, Ordering::SeqCst);
+}
+
+/// Whether the listing should stop without reading any more files.
+pub fn requested() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+/// The signal handler itself. The default handler gets put back after the
+/// first interrupt, so pressing Ctrl-C a second time kills exa straight
+/// away, in case stopping takes too long.
+extern "C" fn handle(_signal: libc::c_int) {
+    request();
+
+    unsafe {
+        let _ = libc::signal(libc::SIGINT, libc::SIG_DFL);
+    }
+}
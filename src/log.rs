@@ -0,0 +1,240 @@
+//! Logging what exa is doing, and how long each part of it takes, for
+//! working out why a listing is slow.
+//!
+//! Logging is configured with the `EXA_LOG` environment variable, which
+//! holds a comma-separated list of levels: a level on its own applies to
+//! everything, and `target=level` applies to one target, so
+//! `EXA_LOG=debug,git=trace` logs everything at the debug level, and Git at
+//! the trace level too. The targets are the phases of listing a directory —
+//! `readdir`, `stat`, `git`, `sort`, and `render` — which log how long they
+//! took at the debug level, and `dirs`, which logs how many files each
+//! directory had at the info level.
+//!
+//! Messages go to standard error, or get appended to the file named by the
+//! `EXA_LOG_FILE` environment variable.
+
+use std::env::var_os;
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{stderr, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+
+/// How important a message is. Each level includes all the ones above it.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Copy, Clone)]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Level {
+    fn from_word(word: &str) -> Option<Level> {
+        match word {
+            "error"  => Some(Level::Error),
+            "warn"   => Some(Level::Warn),
+            "info"   => Some(Level::Info),
+            "debug"  => Some(Level::Debug),
+            "trace"  => Some(Level::Trace),
+            _        => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Level::Error  => "ERROR",
+            Level::Warn   => "WARN",
+            Level::Info   => "INFO",
+            Level::Debug  => "DEBUG",
+            Level::Trace  => "TRACE",
+        }
+    }
+}
+
+
+/// Which messages to log, parsed from the value of `EXA_LOG`.
+#[derive(PartialEq, Debug, Default)]
+struct Filter {
+
+    /// The level for targets that don’t have one of their own.
+    everything: Option<Level>,
+
+    /// The levels for particular targets.
+    targets: Vec<(String, Level)>,
+
+    /// The entries that couldn’t be understood.
+    invalid: Vec<String>,
+}
+
+impl Filter {
+    fn parse(spec: &str) -> Filter {
+        let mut filter = Filter::default();
+
+        for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let mut parts = entry.splitn(2, '=');
+            let first = parts.next().unwrap_or_default();
+
+            match (parts.next(), Level::from_word(first)) {
+                (None, Some(level))  => filter.everything = Some(level),
+                (Some(word), _)      => match Level::from_word(word) {
+                    Some(level)  => filter.targets.push((first.to_string(), level)),
+                    None         => filter.invalid.push(entry.to_string()),
+                },
+                (None, None)         => filter.invalid.push(entry.to_string()),
+            }
+        }
+
+        filter
+    }
+
+    /// The most detailed level to log messages for the given target at.
+    /// Later entries for a target win over earlier ones.
+    fn level_for(&self, target: &str) -> Option<Level> {
+        match self.targets.iter().rev().find(|t| t.0 == target) {
+            Some(&(_, level))  => Some(level),
+            None               => self.everything,
+        }
+    }
+}
+
+
+/// Everything needed to log messages, when logging is switched on.
+struct Logger {
+    filter: Filter,
+    file: Option<Mutex<File>>,
+    start: Instant,
+}
+
+lazy_static! {
+    static ref LOGGER: Option<Logger> = {
+        let spec = match var_os("EXA_LOG") {
+            Some(ref s) if !s.is_empty()  => s.to_string_lossy().into_owned(),
+            _                             => return None,
+        };
+
+        let filter = Filter::parse(&spec);
+        for entry in &filter.invalid {
+            let _ = writeln!(stderr(), "exa: ignoring EXA_LOG entry {:?}", entry);
+        }
+
+        let file = match var_os("EXA_LOG_FILE") {
+            Some(path) => match OpenOptions::new().create(true).append(true).open(&path) {
+                Ok(file)  => Some(Mutex::new(file)),
+                Err(e)    => {
+                    let _ = writeln!(stderr(), "exa: can't open EXA_LOG_FILE {:?}: {}", path, e);
+                    None
+                },
+            },
+            None => None,
+        };
+
+        Some(Logger { filter, file, start: Instant::now() })
+    };
+}
+
+
+/// Whether messages for the given target at the given level get logged,
+/// for when working out what to log takes time itself.
+pub fn enabled(target: &str, level: Level) -> bool {
+    match *LOGGER {
+        Some(ref logger)  => logger.filter.level_for(target).map_or(false, |l| level <= l),
+        None              => false,
+    }
+}
+
+/// Logs a message for the given target at the given level, if messages
+/// like it are being logged. Each message gets one line, prefixed with the
+/// time since logging started.
+pub fn log(target: &str, level: Level, message: fmt::Arguments) {
+    if !enabled(target, level) {
+        return;
+    }
+
+    if let Some(ref logger) = *LOGGER {
+        let line = format!("[{:>10} {:<5} {}] {}\n", millis(logger.start.elapsed()), level.name(), target, message);
+
+        // A listing shouldn’t fail because its log couldn’t be written.
+        let _ = match logger.file {
+            Some(ref file)  => file.lock().map(|mut f| f.write_all(line.as_bytes())).ok(),
+            None            => Some(stderr().write_all(line.as_bytes())),
+        };
+    }
+}
+
+/// Starts timing one phase of listing the given path, which gets logged at
+/// the debug level when the returned timer is dropped.
+pub fn time(target: &'static str, path: &Path) -> Timer {
+    let started = if enabled(target, Level::Debug) { Some((Instant::now(), path.to_path_buf())) }
+                                                  else { None };
+    Timer { target, started }
+}
+
+/// Times a phase of a listing, from when it gets created until it gets
+/// dropped.
+pub struct Timer {
+    target: &'static str,
+    started: Option<(Instant, PathBuf)>,
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        if let Some((start, ref path)) = self.started {
+            log(self.target, Level::Debug, format_args!("{} took {}", path.display(), millis(start.elapsed())));
+        }
+    }
+}
+
+fn millis(duration: Duration) -> String {
+    let millis = duration.as_secs() as f64 * 1000.0 + f64::from(duration.subsec_nanos()) / 1_000_000.0;
+    format!("{:.3}ms", millis)
+}
+
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn everything() {
+        let filter = Filter::parse("debug");
+        assert_eq!(filter.level_for("git"), Some(Level::Debug));
+    }
+
+    #[test]
+    fn one_target() {
+        let filter = Filter::parse("debug,git=trace");
+        assert_eq!(filter.level_for("git"), Some(Level::Trace));
+        assert_eq!(filter.level_for("sort"), Some(Level::Debug));
+    }
+
+    #[test]
+    fn only_a_target() {
+        let filter = Filter::parse("stat=info");
+        assert_eq!(filter.level_for("stat"), Some(Level::Info));
+        assert_eq!(filter.level_for("sort"), None);
+    }
+
+    #[test]
+    fn last_wins() {
+        let filter = Filter::parse("git=trace, git=warn");
+        assert_eq!(filter.level_for("git"), Some(Level::Warn));
+    }
+
+    #[test]
+    fn invalid() {
+        let filter = Filter::parse("loud,git=very");
+        assert_eq!(filter.invalid, vec![ "loud".to_string(), "git=very".to_string() ]);
+        assert_eq!(filter.level_for("git"), None);
+    }
+
+    #[test]
+    fn levels_include_the_ones_above() {
+        assert!(Level::Error < Level::Trace);
+        assert!(Level::Info < Level::Debug);
+    }
+}
@@ -0,0 +1,155 @@
+//! Reading default options from a config file.
+//!
+//! The config file is `exa/config` in the user’s config directory. It’s in
+//! the same TOML format as a theme, with a key for each long option:
+//!
+//! ```toml
+//! long = true
+//! group-directories-first = true
+//! sort = "modified"
+//! level = 2
+//! git = false
+//! ```
+//!
+//! Each key becomes an argument before the ones from `EXA_OPTS` and the
+//! command-line, so they all get parsed and checked in the same way. They
+//! go in a layer of their own, so an option given later replaces the one
+//! from the file, even one that takes a list, such as `--sort`. An option
+//! set to `true` gets given on its own, one set to `false` gets turned off
+//! with `--no-`, and one set to a string or a number gets that as its
+//! value. Giving `--no-config` skips the file altogether.
+
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::{Read, ErrorKind};
+
+use options::{flags, Misfire};
+use options::parser::{Strictness, LAYER_END};
+use options::theme::{config_dir, strip_comment, unquote};
+
+
+/// Puts the options from the user’s config file, if they have one, before
+/// the given arguments, with the end of a layer between them, unless they
+/// include `--no-config`. Fails if the file can’t be read, or has
+/// something wrong with it.
+pub fn add_config_options(args: Vec<OsString>) -> Result<Vec<OsString>, Misfire> {
+    if skips_config(&args) {
+        return Ok(args);
+    }
+
+    let path = match config_dir() {
+        Some(dir)  => dir.join("config"),
+        None       => return Ok(args),
+    };
+
+    let mut text = String::new();
+    if let Err(e) = File::open(&path).and_then(|mut f| f.read_to_string(&mut text)) {
+        if e.kind() == ErrorKind::NotFound {
+            return Ok(args);
+        }
+
+        return Err(Misfire::BadConfig(path, e.to_string()));
+    }
+
+    let mut options = match config_options(&text) {
+        Ok(options)  => options,
+        Err(e)       => return Err(Misfire::BadConfig(path, e)),
+    };
+
+    options.push(OsString::from(LAYER_END));
+    options.extend(args);
+    Ok(options)
+}
+
+/// Whether the given arguments include `--no-config`. They get parsed to
+/// find out, so it counts however it’s given; arguments that can’t be
+/// parsed will fail again later, after the config file has been read.
+fn skips_config(args: &[OsString]) -> bool {
+    match flags::ALL_ARGS.parse(args, Strictness::UseLastArguments) {
+        Ok(matches)  => matches.flags.has(&flags::NO_CONFIG),
+        Err(_)       => false,
+    }
+}
+
+/// Turns the text of a config file into arguments, returning a
+/// description of the first problem with it, if there is one.
+fn config_options(text: &str) -> Result<Vec<OsString>, String> {
+    let mut options = Vec::new();
+
+    for (number, line) in text.lines().enumerate() {
+        let line = strip_comment(line).trim();
+        let problem = |what: &str| format!("line {}: {}", number + 1, what);
+
+        if line.is_empty() {
+            continue;
+        }
+
+        let (key, value) = match line.find('=') {
+            Some(index)  => (unquote(line[.. index].trim(), true), line[index + 1 ..].trim()),
+            None         => return Err(problem("expected option = value")),
+        };
+
+        let key = match key {
+            Some(k)  => k,
+            None     => return Err(problem("unfinished quoted key")),
+        };
+
+        let option = match value {
+            "true"   => format!("--{}", key),
+            "false"  => {
+                if !flags::ALL_ARGS.0.iter().any(|arg| arg.long == key) {
+                    return Err(problem(&format!("there’s no option --{} to turn off", key)));
+                }
+
+                format!("--no-{}", key)
+            },
+            v if !v.is_empty() && v.bytes().all(|b| b.is_ascii_digit())  => format!("--{}={}", key, v),
+            v => match unquote(v, false) {
+                Some(string)  => format!("--{}={}", key, string),
+                None          => return Err(problem("the value should be true, false, a number, or a quoted string")),
+            },
+        };
+
+        options.push(OsString::from(option));
+    }
+
+    Ok(options)
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    macro_rules! test {
+        ($name:ident: $input:expr => Err($error:expr)) => {
+            #[test]
+            fn $name() {
+                assert_eq!(config_options($input), Err($error.to_string()));
+            }
+        };
+
+        ($name:ident: $input:expr => $results:expr) => {
+            #[test]
+            fn $name() {
+                let results: &[&str] = &$results;
+                let results: Vec<OsString> = results.iter().map(OsString::from).collect();
+                assert_eq!(config_options($input), Ok(results));
+            }
+        };
+    }
+
+    test!(empty:     ""                                 => []);
+    test!(comments:  "# just a comment\n\n"             => []);
+    test!(on:        "long = true"                      => [ "--long" ]);
+    test!(off:       "git = false  # not here"          => [ "--no-git" ]);
+    test!(string:    "sort = \"modified\""              => [ "--sort=modified" ]);
+    test!(single:    "ignore-glob = '*.o|*.a'"          => [ "--ignore-glob=*.o|*.a" ]);
+    test!(number:    "level = 2"                        => [ "--level=2" ]);
+    test!(in_order:  "long = true\nsort = 'size'"       => [ "--long", "--sort=size" ]);
+    test!(no_value:  "long"                             => Err("line 1: expected option = value"));
+    test!(bare:      "\nsort = modified"                => Err("line 2: the value should be true, false, a number, or a quoted string"));
+    test!(off_list:  "sort = false"                     => [ "--no-sort" ]);
+    test!(off_alias: "colour = false"                   => [ "--no-colour" ]);
+    test!(off_none:  "long = true\ngits = false"        => Err("line 2: there’s no option --gits to turn off"));
+}
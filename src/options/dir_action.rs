@@ -13,10 +13,23 @@ impl DirAction {
         let tree    = matches.has(&flags::TREE);
 
         // Early check for --level when it wouldn’t do anything
-        if !recurse && !tree && matches.get(&flags::LEVEL).is_some() {
+        if !recurse && !tree && matches.get(&flags::LEVEL)?.is_some() {
             return Err(Misfire::Useless2(&flags::LEVEL, &flags::RECURSE, &flags::TREE));
         }
 
+        if !recurse && !tree && matches.get(&flags::MIN_DEPTH)?.is_some() {
+            return Err(Misfire::Useless2(&flags::MIN_DEPTH, &flags::RECURSE, &flags::TREE));
+        }
+
+        // --tree already recurses, so giving --recurse as well is only a
+        // problem when being strict, and both are on the command-line.
+        if matches.is_strict() {
+            let given = matches.command_line();
+            if given.has(&flags::RECURSE) && given.has(&flags::TREE) {
+                return Err(Misfire::Conflict(&flags::RECURSE, &flags::TREE));
+            }
+        }
+
         match (recurse, list, tree) {
 
             // You can't --list-dirs along with --recurse or --tree because
@@ -37,7 +50,17 @@ impl RecurseOptions {
 
     /// Determine which files should be recursed into.
     pub fn deduce(matches: &MatchedFlags, tree: bool) -> Result<RecurseOptions, Misfire> {
-        let max_depth = if let Some(level) = matches.get(&flags::LEVEL) {
+        let max_depth = if let Some(level) = matches.get(&flags::LEVEL)? {
+            match level.to_string_lossy().parse() {
+                Ok(l)   => Some(l),
+                Err(e)  => return Err(Misfire::FailedParse(e)),
+            }
+        }
+        else {
+            None
+        };
+
+        let min_depth = if let Some(level) = matches.get(&flags::MIN_DEPTH)? {
             match level.to_string_lossy().parse() {
                 Ok(l)   => Some(l),
                 Err(e)  => return Err(Misfire::FailedParse(e)),
@@ -47,7 +70,7 @@ impl RecurseOptions {
             None
         };
 
-        Ok(RecurseOptions { tree, max_depth })
+        Ok(RecurseOptions { tree, max_depth, min_depth })
     }
 }
 
@@ -57,6 +80,7 @@ mod test {
     use super::*;
     use std::ffi::OsString;
     use options::flags;
+    use options::parser::{Flag, LAYER_END};
 
     pub fn os(input: &'static str) -> OsString {
         let mut os = OsString::new();
@@ -66,15 +90,19 @@ mod test {
 
     macro_rules! test {
         ($name:ident: $type:ident <- $inputs:expr => $result:expr) => {
+            test!($name: $type <- $inputs; UseLastArguments => $result);
+        };
+
+        ($name:ident: $type:ident <- $inputs:expr; $strictness:ident => $result:expr) => {
             #[test]
             fn $name() {
-                use options::parser::{Args, Arg};
+                use options::parser::{Args, Arg, Strictness};
                 use std::ffi::OsString;
 
-                static TEST_ARGS: &[&Arg] = &[ &flags::RECURSE, &flags::LIST_DIRS, &flags::TREE, &flags::LEVEL ];
+                static TEST_ARGS: &[&Arg] = &[ &flags::RECURSE, &flags::LIST_DIRS, &flags::TREE, &flags::LEVEL, &flags::MIN_DEPTH ];
 
                 let bits = $inputs.as_ref().into_iter().map(|&o| os(o)).collect::<Vec<OsString>>();
-                let results = Args(TEST_ARGS).parse(bits.iter());
+                let results = Args(TEST_ARGS).parse(bits.iter(), Strictness::$strictness);
                 assert_eq!($type::deduce(&results.unwrap().flags), $result);
             }
         };
@@ -89,17 +117,29 @@ mod test {
     test!(dirs_long:       DirAction <- ["--list-dirs"]  => Ok(DirAction::AsFile));
 
     // Recursing
-    test!(rec_short:       DirAction <- ["-R"]                           => Ok(DirAction::Recurse(RecurseOptions { tree: false, max_depth: None })));
-    test!(rec_long:        DirAction <- ["--recurse"]                    => Ok(DirAction::Recurse(RecurseOptions { tree: false, max_depth: None })));
-    test!(rec_lim_short:   DirAction <- ["-RL4"]                         => Ok(DirAction::Recurse(RecurseOptions { tree: false, max_depth: Some(4) })));
-    test!(rec_lim_short_2: DirAction <- ["-RL=5"]                        => Ok(DirAction::Recurse(RecurseOptions { tree: false, max_depth: Some(5) })));
-    test!(rec_lim_long:    DirAction <- ["--recurse", "--level", "666"]  => Ok(DirAction::Recurse(RecurseOptions { tree: false, max_depth: Some(666) })));
-    test!(rec_lim_long_2:  DirAction <- ["--recurse", "--level=0118"]    => Ok(DirAction::Recurse(RecurseOptions { tree: false, max_depth: Some(118) })));
-    test!(rec_tree:        DirAction <- ["--recurse", "--tree"]          => Ok(DirAction::Recurse(RecurseOptions { tree: true,  max_depth: None })));
-    test!(rec_short_tree:  DirAction <- ["--tree", "--recurse"]          => Ok(DirAction::Recurse(RecurseOptions { tree: true,  max_depth: None })));
+    test!(rec_short:       DirAction <- ["-R"]                           => Ok(DirAction::Recurse(RecurseOptions { tree: false, max_depth: None, min_depth: None })));
+    test!(rec_long:        DirAction <- ["--recurse"]                    => Ok(DirAction::Recurse(RecurseOptions { tree: false, max_depth: None, min_depth: None })));
+    test!(rec_lim_short:   DirAction <- ["-RL4"]                         => Ok(DirAction::Recurse(RecurseOptions { tree: false, max_depth: Some(4), min_depth: None })));
+    test!(rec_lim_short_2: DirAction <- ["-RL=5"]                        => Ok(DirAction::Recurse(RecurseOptions { tree: false, max_depth: Some(5), min_depth: None })));
+    test!(rec_lim_long:    DirAction <- ["--recurse", "--level", "666"]  => Ok(DirAction::Recurse(RecurseOptions { tree: false, max_depth: Some(666), min_depth: None })));
+    test!(rec_lim_long_2:  DirAction <- ["--recurse", "--level=0118"]    => Ok(DirAction::Recurse(RecurseOptions { tree: false, max_depth: Some(118), min_depth: None })));
+    test!(rec_tree:        DirAction <- ["--recurse", "--tree"]          => Ok(DirAction::Recurse(RecurseOptions { tree: true,  max_depth: None, min_depth: None })));
+    test!(rec_short_tree:  DirAction <- ["--tree", "--recurse"]          => Ok(DirAction::Recurse(RecurseOptions { tree: true,  max_depth: None, min_depth: None })));
+
+    test!(rec_min_depth:   DirAction <- ["--recurse", "--min-depth=2"]   => Ok(DirAction::Recurse(RecurseOptions { tree: false, max_depth: None, min_depth: Some(2) })));
+    test!(rec_min_max:     DirAction <- ["--tree", "-L3", "--min-depth", "2"]  => Ok(DirAction::Recurse(RecurseOptions { tree: true, max_depth: Some(3), min_depth: Some(2) })));
 
     // Errors
     test!(error:           DirAction <- ["--list-dirs", "--recurse"]  => Err(Misfire::Conflict(&flags::RECURSE, &flags::LIST_DIRS)));
     test!(error_2:         DirAction <- ["--list-dirs", "--tree"]     => Err(Misfire::Conflict(&flags::TREE,    &flags::LIST_DIRS)));
     test!(underwaterlevel: DirAction <- ["--level=4"]                 => Err(Misfire::Useless2(&flags::LEVEL, &flags::RECURSE, &flags::TREE)));
+    test!(shallow_alone:   DirAction <- ["--min-depth=2"]             => Err(Misfire::Useless2(&flags::MIN_DEPTH, &flags::RECURSE, &flags::TREE)));
+
+    // Strict mode
+    test!(strict_tree:     DirAction <- ["--tree"]; ComplainAboutRedundantArguments               => Ok(DirAction::Recurse(RecurseOptions { tree: true, max_depth: None, min_depth: None })));
+    test!(strict_rec_tree: DirAction <- ["--recurse", "--tree"]; ComplainAboutRedundantArguments  => Err(Misfire::Conflict(&flags::RECURSE, &flags::TREE)));
+    test!(strict_levels:   DirAction <- ["-T", "-L2", "-L3"]; ComplainAboutRedundantArguments     => Err(Misfire::Duplicate(Flag::Short(b'L'), Flag::Short(b'L'))));
+    test!(strict_same:     DirAction <- ["-T", "-L2", "--level=2"]; ComplainAboutRedundantArguments => Ok(DirAction::Recurse(RecurseOptions { tree: true, max_depth: Some(2), min_depth: None })));
+    test!(strict_layers:   DirAction <- ["--recurse", LAYER_END, "--tree"]; ComplainAboutRedundantArguments => Ok(DirAction::Recurse(RecurseOptions { tree: true, max_depth: None, min_depth: None })));
+    test!(strict_layer_levels: DirAction <- ["-T", "-L2", LAYER_END, "-L3"]; ComplainAboutRedundantArguments => Ok(DirAction::Recurse(RecurseOptions { tree: true, max_depth: Some(3), min_depth: None })));
 }
@@ -0,0 +1,120 @@
+//! Reading default options from the `EXA_OPTS` environment variable.
+//!
+//! The variable holds options the way they’d be typed into a shell, which
+//! get split into arguments and put before the ones exa was run with, in a
+//! layer of their own. Any option given on the command-line replaces them,
+//! even one that takes a list, such as `--sort`, which would otherwise add
+//! to the list from the variable.
+//!
+//! Splitting works like a shell without any of its expansions: arguments
+//! are separated by whitespace, which can be kept in one by quoting it.
+//! Inside single quotes, everything is kept as it is; inside double quotes,
+//! and outside of quotes, a backslash keeps the character after it.
+
+use std::env::var_os;
+use std::ffi::OsString;
+use std::os::unix::ffi::{OsStrExt, OsStringExt};
+
+use options::Misfire;
+use options::parser::LAYER_END;
+
+
+/// Puts the options from the `EXA_OPTS` environment variable, if it’s set,
+/// before the given arguments, with the end of a layer between them. Fails
+/// if the variable can’t be split into arguments.
+pub fn add_env_options(args: Vec<OsString>) -> Result<Vec<OsString>, Misfire> {
+    let env = match var_os("EXA_OPTS") {
+        Some(env)  => env,
+        None       => return Ok(args),
+    };
+
+    let mut options = split(env.as_bytes()).map_err(|e| Misfire::BadEnvOptions(e.to_string()))?;
+    options.push(OsString::from(LAYER_END));
+    options.extend(args);
+    Ok(options)
+}
+
+
+/// Splits a string into arguments, taking quotes and backslashes into
+/// account. Returns a description of the problem if a quote is never
+/// closed, or the string ends with a backslash.
+fn split(input: &[u8]) -> Result<Vec<OsString>, &'static str> {
+    let mut args = Vec::new();
+    let mut current: Option<Vec<u8>> = None;
+    let mut bytes = input.iter();
+
+    while let Some(&byte) = bytes.next() {
+        if byte == b' ' || byte == b'\t' || byte == b'\n' {
+            args.extend(current.take().map(OsString::from_vec));
+            continue;
+        }
+
+        let arg = current.get_or_insert_with(Vec::new);
+        match byte {
+            b'\''  => loop {
+                match bytes.next() {
+                    Some(&b'\'')  => break,
+                    Some(&b)      => arg.push(b),
+                    None          => return Err("a single quote isn’t closed"),
+                }
+            },
+            b'"'   => loop {
+                match bytes.next() {
+                    Some(&b'"')   => break,
+                    Some(&b'\\')  => match bytes.next() {
+                        Some(&b)  => arg.push(b),
+                        None      => return Err("a double quote isn’t closed"),
+                    },
+                    Some(&b)      => arg.push(b),
+                    None          => return Err("a double quote isn’t closed"),
+                }
+            },
+            b'\\'  => match bytes.next() {
+                Some(&b)  => arg.push(b),
+                None      => return Err("it ends with a backslash"),
+            },
+            _      => arg.push(byte),
+        }
+    }
+
+    args.extend(current.map(OsString::from_vec));
+    Ok(args)
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    macro_rules! test {
+        ($name:ident: $input:expr => Err($error:expr)) => {
+            #[test]
+            fn $name() {
+                assert_eq!(split($input.as_bytes()), Err($error));
+            }
+        };
+
+        ($name:ident: $input:expr => $results:expr) => {
+            #[test]
+            fn $name() {
+                let results: &[&str] = &$results;
+                let results: Vec<OsString> = results.iter().map(OsString::from).collect();
+                assert_eq!(split($input.as_bytes()), Ok(results));
+            }
+        };
+    }
+
+    test!(empty:         ""                                => []);
+    test!(spaces:        "  \t "                           => []);
+    test!(two:           "--group-directories-first -l"    => [ "--group-directories-first", "-l" ]);
+    test!(extra_spaces:  "  -l   --git "                   => [ "-l", "--git" ]);
+    test!(single:        "--ignore-glob='*.o *.a'"         => [ "--ignore-glob=*.o *.a" ]);
+    test!(single_slash:  "'a\\b'"                          => [ "a\\b" ]);
+    test!(double:        "--time-style \"long-iso\""       => [ "--time-style", "long-iso" ]);
+    test!(double_quote:  "\"say \\\"hi\\\"\""              => [ "say \"hi\"" ]);
+    test!(backslash:     "a\\ b c"                         => [ "a b", "c" ]);
+    test!(empty_quotes:  "'' x"                            => [ "", "x" ]);
+    test!(open_single:   "'abc"                            => Err("a single quote isn’t closed"));
+    test!(open_double:   "\"abc\\\""                       => Err("a double quote isn’t closed"));
+    test!(last_slash:    "abc\\"                           => Err("it ends with a backslash"));
+}
@@ -1,10 +1,29 @@
+use std::env::var_os;
+use std::ffi::OsStr;
+use std::fs::File;
+use std::io::Read;
+use std::mem;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
 use glob;
+use libc;
+use regex::bytes::Regex;
+use users::{get_user_by_name, get_group_by_name};
 
 use fs::DotFilter;
-use fs::filter::{FileFilter, SortField, SortCase, IgnorePatterns};
+use fs::fields::{GitStatus, Type};
+use fs::filter::{FileFilter, SortKey, SortField, SortDirection, SortCase, IgnorePatterns, RegexFilter, GitFilter};
+use fs::filter::{PrunePatterns, XattrFilter, XattrPredicate, CategoryFilter, EmptyFilter, Emptiness, SinceFilter};
+use info::filetype::FileCategory;
+use fs::filter::{DotfilePlacement, Normalization};
+use fs::manifest::Manifest;
+use fs::filter::{SizeFilter, SizePredicate, TimeFilter, TimeField};
+use fs::filter::{OwnerFilter, IdFilter, TypeFilter, PermFilter, PermPredicate};
+use output::table::TimeTypes;
 
 use options::{flags, Misfire};
-use options::parser::MatchedFlags;
+use options::parser::{MatchedFlags, Arg};
 
 
 impl FileFilter {
@@ -13,44 +32,137 @@ impl FileFilter {
     /// command-line arguments.
     pub fn deduce(matches: &MatchedFlags) -> Result<FileFilter, Misfire> {
         Ok(FileFilter {
-            list_dirs_first: matches.has(&flags::DIRS_FIRST),
-            reverse:         matches.has(&flags::REVERSE),
-            sort_field:      SortField::deduce(matches)?,
-            dot_filter:      DotFilter::deduce(matches)?,
-            ignore_patterns: IgnorePatterns::deduce(matches)?,
+            list_dirs_first:   matches.has(&flags::DIRS_FIRST),
+            reverse:           matches.has(&flags::REVERSE),
+            sort_keys:         SortKey::deduce(matches)?,
+            ties_by_name:      matches.has(&flags::DETERMINISTIC),
+            dot_filter:        DotFilter::deduce(matches)?,
+            dotfile_placement: DotfilePlacement::deduce(matches)?,
+            ignore_patterns:   IgnorePatterns::deduce(matches)?,
+            prune_patterns:    PrunePatterns::deduce(matches)?,
+            regexes:           RegexFilter::deduce(matches)?,
+            size_filter:       SizeFilter::deduce(matches)?,
+            time_filter:       TimeFilter::deduce(matches)?,
+            owner_filter:      OwnerFilter::deduce(matches)?,
+            type_filter:       TypeFilter::deduce(matches)?,
+            perm_filter:       PermFilter::deduce(matches)?,
+            xattr_filter:      XattrFilter::deduce(matches)?,
+            category_filter:   CategoryFilter::deduce(matches)?,
+            empty_filter:      EmptyFilter::deduce(matches)?,
+            use_ignore_files:  matches.has(&flags::DOT_IGNORE),
+            git_filter:        GitFilter::deduce(matches)?,
+            since_filter:      SinceFilter::deduce(matches)?,
+            dedupe:            matches.has(&flags::DEDUPE),
+            normalization:     Normalization::deduce(matches)?,
+            max_results:       deduce_max_results(matches)?,
         })
     }
 }
 
 
+/// Determine the maximum number of files to list across the whole run, if
+/// one was given. Returns an error if it doesn’t parse to an integer.
+fn deduce_max_results(matches: &MatchedFlags) -> Result<Option<usize>, Misfire> {
+    match matches.get(&flags::MAX_RESULTS)? {
+        Some(count) => match count.to_string_lossy().parse() {
+            Ok(c)   => Ok(Some(c)),
+            Err(e)  => Err(Misfire::FailedParse(e)),
+        },
+        None => Ok(None),
+    }
+}
+
+
 
 impl Default for SortField {
     fn default() -> SortField {
-        SortField::Name(SortCase::Sensitive)
+        SortField::for_name()
     }
 }
 
-const SORTS: &[&str] = &[ "name", "Name", "size", "extension",
-                          "Extension", "modified", "accessed",
-                          "created", "inode", "type", "none" ];
-
 impl SortField {
 
-    /// Determine the sort field to use, based on the presence of a “sort”
-    /// argument. This will return `Err` if the option is there, but does not
-    /// correspond to a valid field.
-    fn deduce(matches: &MatchedFlags) -> Result<SortField, Misfire> {
-        let word = match matches.get(&flags::SORT) {
-            Some(w)  => w,
-            None     => return Ok(SortField::default()),
-        };
+    /// Determine what the plain `name` field, which is also the default,
+    /// should sort by. It compares names as versions if the `EXA_SORT_NAME`
+    /// environment variable is set to `version`, and uses natural ordering
+    /// otherwise.
+    fn for_name() -> SortField {
+        match var_os("EXA_SORT_NAME") {
+            Some(ref name) if name == "version"  => SortField::NameVersion,
+            _                                    => SortField::Name(SortCase::for_name()),
+        }
+    }
+}
+
+impl SortCase {
+
+    /// Determine whether the plain `name` field, which is also the default,
+    /// should be case-sensitive. It is unless the `EXA_SORT_CASE` environment
+    /// variable is set to `insensitive`.
+    fn for_name() -> SortCase {
+        match var_os("EXA_SORT_CASE") {
+            Some(ref case) if case == "insensitive"  => SortCase::Insensitive,
+            _                                        => SortCase::Sensitive,
+        }
+    }
+}
+
+impl SortKey {
+
+    /// Determine the sort keys to use, based on the presence of “sort”
+    /// arguments. More than one field can be given, either separated by
+    /// commas or with the argument being repeated, with each one breaking
+    /// ties between files according to the ones before it. Each field can
+    /// have a direction after a colon, such as `size:desc`. This will return
+    /// `Err` if any field or direction given is not a valid one.
+    fn deduce(matches: &MatchedFlags) -> Result<Vec<SortKey>, Misfire> {
+        let mut keys = Vec::new();
+
+        for word in matches.get_all(&flags::SORT) {
+            let word = word.to_string_lossy();
+            let mut parts = word.splitn(2, ':');
+            let field = SortField::from_word(OsStr::new(parts.next().unwrap_or("")))?;
+
+            let direction = match parts.next() {
+                None          => SortDirection::Ascending,
+                Some("asc")   => SortDirection::Ascending,
+                Some("desc")  => SortDirection::Descending,
+                Some(other)   => return Err(Misfire::bad_argument(&flags::SORT, OsStr::new(other), flags::SORT_DIRECTIONS)),
+            };
+
+            keys.push(SortKey { field, direction });
+        }
+
+        if keys.is_empty() {
+            keys.push(SortKey::from(SortField::default()));
+        }
+
+        Ok(keys)
+    }
+}
 
+impl SortField {
+
+    /// Determine the sort field named by a single word.
+    fn from_word(word: &OsStr) -> Result<SortField, Misfire> {
         if word == "name" || word == "filename" {
+            Ok(SortField::for_name())
+        }
+        else if word == "name-case" {
             Ok(SortField::Name(SortCase::Sensitive))
         }
+        else if word == "name-nocase" {
+            Ok(SortField::Name(SortCase::Insensitive))
+        }
         else if word == "Name" || word == "Filename" {
             Ok(SortField::Name(SortCase::Insensitive))
         }
+        else if word == "name-version" || word == "version" {
+            Ok(SortField::NameVersion)
+        }
+        else if word == "name-locale" || word == "locale" {
+            Ok(SortField::NameLocale)
+        }
         else if word == "size" || word == "filesize" {
             Ok(SortField::Size)
         }
@@ -72,6 +184,15 @@ impl SortField {
         else if word == "inode" {
             Ok(SortField::FileInode)
         }
+        else if word == "blocks" {
+            Ok(SortField::Blocks)
+        }
+        else if word == "links" {
+            Ok(SortField::Links)
+        }
+        else if word == "git" {
+            Ok(SortField::GitStatus)
+        }
         else if word == "type" {
             Ok(SortField::FileType)
         }
@@ -79,7 +200,7 @@ impl SortField {
             Ok(SortField::Unsorted)
         }
         else {
-            Err(Misfire::bad_argument(&flags::SORT, word, SORTS))
+            Err(Misfire::bad_argument(&flags::SORT, word, flags::SORTS))
         }
     }
 }
@@ -87,6 +208,19 @@ impl SortField {
 
 impl DotFilter {
     pub fn deduce(matches: &MatchedFlags) -> Result<DotFilter, Misfire> {
+        // --almost-all never shows `.` and `..`, no matter how many times
+        // --all is also given.
+        if matches.has(&flags::ALMOST_ALL) {
+            if matches.is_strict() {
+                let given = matches.command_line();
+                if given.has(&flags::ALL) && given.has(&flags::ALMOST_ALL) {
+                    return Err(Misfire::Useless(&flags::ALL, true, &flags::ALMOST_ALL));
+                }
+            }
+
+            return Ok(DotFilter::Dotfiles);
+        }
+
         match matches.count(&flags::ALL) {
             0 => Ok(DotFilter::JustFiles),
             1 => Ok(DotFilter::Dotfiles),
@@ -97,23 +231,648 @@ impl DotFilter {
 }
 
 
+
+impl Normalization {
+
+    /// Determines which Unicode normalization form to put files’ names
+    /// into, if any, based on the user’s command-line arguments.
+    fn deduce(matches: &MatchedFlags) -> Result<Option<Normalization>, Misfire> {
+        let word = match matches.get(&flags::NORMALIZE)? {
+            Some(w)  => w,
+            None     => return Ok(None),
+        };
+
+        if word == "nfc" {
+            Ok(Some(Normalization::Composed))
+        }
+        else if word == "nfd" {
+            Ok(Some(Normalization::Decomposed))
+        }
+        else {
+            Err(Misfire::bad_argument(&flags::NORMALIZE, word, flags::NORMALIZATIONS))
+        }
+    }
+}
+
+
+impl DotfilePlacement {
+
+    /// Determines where to place dotfiles when sorting, based on the
+    /// user’s command-line arguments.
+    fn deduce(matches: &MatchedFlags) -> Result<DotfilePlacement, Misfire> {
+        let word = match matches.get(&flags::DOTFILES)? {
+            Some(w)  => w,
+            None     => return Ok(DotfilePlacement::default()),
+        };
+
+        if word == "first" {
+            Ok(DotfilePlacement::First)
+        }
+        else if word == "last" {
+            Ok(DotfilePlacement::Last)
+        }
+        else if word == "mixed" {
+            Ok(DotfilePlacement::Mixed)
+        }
+        else {
+            Err(Misfire::bad_argument(&flags::DOTFILES, word, flags::DOTFILE_PLACEMENTS))
+        }
+    }
+}
+
+
 impl IgnorePatterns {
 
-    /// Determines the set of file filter options to use, based on the user’s
-    /// command-line arguments.
+    /// Determines the set of glob patterns to ignore, based on the user’s
+    /// command-line arguments. The argument can be given more than once,
+    /// and each occurrence can hold several pipe-separated patterns.
     pub fn deduce(matches: &MatchedFlags) -> Result<IgnorePatterns, Misfire> {
-        let patterns = match matches.get(&flags::IGNORE_GLOB) {
-            None => Ok(Vec::new()),
-            Some(is) => is.to_string_lossy().split('|').map(|a| glob::Pattern::new(a)).collect(),
-        }?;
+        Ok(IgnorePatterns { patterns: compile_globs(matches.get_all(&flags::IGNORE_GLOB))? })
+    }
+}
+
+
+impl PrunePatterns {
+
+    /// Determines the set of glob patterns of directories to prune, which
+    /// get given in the same way as the patterns to ignore.
+    pub fn deduce(matches: &MatchedFlags) -> Result<PrunePatterns, Misfire> {
+        Ok(PrunePatterns { patterns: compile_globs(matches.get_all(&flags::PRUNE_GLOB))? })
+    }
+}
+
+
+/// Compiles the glob patterns in each of the given words, which can each
+/// hold several pipe-separated patterns.
+fn compile_globs(words: Vec<&OsStr>) -> Result<Vec<glob::Pattern>, Misfire> {
+    let mut patterns = Vec::new();
+
+    for globs in words {
+        for glob in globs.to_string_lossy().split('|') {
+            patterns.push(glob::Pattern::new(glob)?);
+        }
+    }
+
+    // TODO: is to_string_lossy really the best way to handle
+    // invalid UTF-8 there?
+
+    Ok(patterns)
+}
+
+
+impl RegexFilter {
+
+    /// Determines the regexes to filter files by, based on the user’s
+    /// command-line arguments. Both arguments can be given more than once.
+    pub fn deduce(matches: &MatchedFlags) -> Result<RegexFilter, Misfire> {
+        Ok(RegexFilter {
+            include: compile_regexes(matches.get_all(&flags::REGEX))?,
+            exclude: compile_regexes(matches.get_all(&flags::REGEX_EXCLUDE))?,
+        })
+    }
+}
+
+fn compile_regexes(words: Vec<&OsStr>) -> Result<Vec<Regex>, Misfire> {
+    words.into_iter()
+         .map(|word| Regex::new(&word.to_string_lossy()).map_err(Misfire::from))
+         .collect()
+}
+
+
+impl SizeFilter {
+
+    /// Determines the sizes to filter files by, based on the user’s
+    /// command-line arguments. The argument can be given more than once,
+    /// with files having to satisfy every one of them.
+    pub fn deduce(matches: &MatchedFlags) -> Result<SizeFilter, Misfire> {
+        let predicates = matches.get_all(&flags::SIZE_FILTER).into_iter()
+                                .map(SizePredicate::deduce)
+                                .collect::<Result<_, _>>()?;
+
+        Ok(SizeFilter { predicates })
+    }
+}
+
+impl SizePredicate {
+
+    /// Parses a size predicate in the style of `find -size`: a number of
+    /// bytes with an optional unit suffix, prefixed with `+` to match files
+    /// larger than that, `-` to match files smaller than that, or nothing
+    /// to match files of exactly that size.
+    fn deduce(word: &OsStr) -> Result<SizePredicate, Misfire> {
+        let bad_value = || Misfire::BadValue(&flags::SIZE_FILTER, word.to_os_string(), "a size such as +10M or -4k");
+
+        let string = word.to_string_lossy();
+        let (constructor, rest): (fn(u64) -> SizePredicate, &str) = match string.chars().next() {
+            Some('+')  => (SizePredicate::LargerThan,  &string[1..]),
+            Some('-')  => (SizePredicate::SmallerThan, &string[1..]),
+            _          => (SizePredicate::Exactly,     &string[..]),
+        };
+
+        let split = rest.find(|c: char| !c.is_digit(10)).unwrap_or(rest.len());
+        let (number, unit) = rest.split_at(split);
+
+        let number: u64 = number.parse().map_err(|_| bad_value())?;
+        let multiplier: u64 = match &*unit.to_lowercase() {
+            "" | "b"      => 1,
+            "k" | "kb"    => 1000,
+            "m" | "mb"    => 1000 * 1000,
+            "g" | "gb"    => 1000 * 1000 * 1000,
+            "t" | "tb"    => 1000 * 1000 * 1000 * 1000,
+            "ki" | "kib"  => 1 << 10,
+            "mi" | "mib"  => 1 << 20,
+            "gi" | "gib"  => 1 << 30,
+            "ti" | "tib"  => 1 << 40,
+            _             => return Err(bad_value()),
+        };
+
+        match number.checked_mul(multiplier) {
+            Some(size)  => Ok(constructor(size)),
+            None        => Err(Misfire::BadValue(&flags::SIZE_FILTER, word.to_os_string(), "a size that fits in 64 bits")),
+        }
+    }
+}
+
+
+impl TimeFilter {
+
+    /// Determines the times to filter files by, based on the user’s
+    /// command-line arguments. The timestamp that gets compared is the one
+    /// picked with `--time` or its shorthands, falling back to the modified
+    /// time; if more than one is picked, the first listed here is used.
+    ///
+    /// Durations get counted back from the time this is run, so every file
+    /// gets compared against the same times.
+    pub fn deduce(matches: &MatchedFlags) -> Result<TimeFilter, Misfire> {
+        let now = now();
+
+        let newer_than = match (matches.get(&flags::NEWER_THAN)?, matches.get(&flags::CHANGED_WITHIN)?) {
+            (Some(_),    Some(_))  => return Err(Misfire::Conflict(&flags::CHANGED_WITHIN, &flags::NEWER_THAN)),
+            (Some(word), None)     => Some(deduce_timestamp(&flags::NEWER_THAN, word, now)?),
+            (None,       Some(word)) => Some(deduce_timestamp(&flags::CHANGED_WITHIN, word, now)?),
+            (None,       None)     => None,
+        };
+
+        let older_than = match matches.get(&flags::OLDER_THAN)? {
+            Some(word)  => Some(deduce_timestamp(&flags::OLDER_THAN, word, now)?),
+            None        => None,
+        };
+
+        if newer_than.is_none() && older_than.is_none() {
+            return Ok(TimeFilter::default());
+        }
+
+        let types = TimeTypes::deduce(matches)?;
+        let field = if types.modified      { TimeField::Modified }
+                    else if types.accessed { TimeField::Accessed }
+                    else                   { TimeField::Created };
+
+        Ok(TimeFilter { field, newer_than, older_than })
+    }
+}
+
+/// Parses either a duration, which counts backwards from the given time,
+/// or an absolute date in the local time zone, into a Unix timestamp.
+fn deduce_timestamp(arg: &'static Arg, word: &OsStr, now: i64) -> Result<i64, Misfire> {
+    let string = word.to_string_lossy();
+
+    let too_long = || Misfire::BadValue(arg, word.to_os_string(), "a duration that isn’t so long");
+
+    match parse_duration(&string) {
+        Duration::Seconds(seconds)  => now.checked_sub(seconds).ok_or_else(too_long),
+        Duration::TooLong           => Err(too_long()),
+        Duration::NotOne            => match parse_date(&string) {
+            Some(timestamp)  => Ok(timestamp),
+            None             => Err(Misfire::BadValue(arg, word.to_os_string(), "a duration such as 2d or 30min, or a date such as 2017-01-01")),
+        },
+    }
+}
+
+/// The current time as a Unix timestamp.
+fn now() -> i64 {
+    match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(duration)  => duration.as_secs() as i64,
+        Err(_)        => 0,
+    }
+}
+
+/// What an argument turned out to be when read as a duration.
+#[derive(PartialEq, Debug)]
+enum Duration {
+
+    /// A duration, as a number of seconds.
+    Seconds(i64),
+
+    /// A duration with too many seconds to count.
+    TooLong,
+
+    /// Something that isn’t a duration at all, which might be a date.
+    NotOne,
+}
+
+/// Parses a humanised duration, such as `2d` or `30min`, into a number of
+/// seconds.
+fn parse_duration(input: &str) -> Duration {
+    let split = input.find(|c: char| !c.is_digit(10)).unwrap_or(input.len());
+    let (number, unit) = input.split_at(split);
+
+    if number.is_empty() {
+        return Duration::NotOne;
+    }
+
+    let multiplier: i64 = match unit {
+        "s" | "sec" | "secs" | "second" | "seconds"  => 1,
+        "m" | "min" | "mins" | "minute" | "minutes"  => 60,
+        "h" | "hour" | "hours"                       => 60 * 60,
+        "d" | "day" | "days"                         => 60 * 60 * 24,
+        "w" | "week" | "weeks"                       => 60 * 60 * 24 * 7,
+        "y" | "year" | "years"                       => 60 * 60 * 24 * 365,
+        _                                            => return Duration::NotOne,
+    };
+
+    // The number is all digits, so it only fails to parse if it’s too big.
+    match number.parse::<i64>().ok().and_then(|n| n.checked_mul(multiplier)) {
+        Some(seconds)  => Duration::Seconds(seconds),
+        None           => Duration::TooLong,
+    }
+}
+
+/// Parses a date such as `2017-01-01`, optionally followed by a time such
+/// as `12:30` or `12:30:45`, into a Unix timestamp.
+fn parse_date(input: &str) -> Option<i64> {
+    fn numbers(input: &str, separator: char) -> Option<Vec<i32>> {
+        input.split(separator).map(|n| n.parse().ok()).collect()
+    }
+
+    let (date, time) = match input.find(|c| c == ' ' || c == 'T') {
+        Some(index)  => (&input[.. index], &input[index + 1 ..]),
+        None         => (input, "0:0"),
+    };
+
+    match (numbers(date, '-'), numbers(time, ':')) {
+        (Some(ref d), Some(ref t)) if d.len() == 3 && (t.len() == 2 || t.len() == 3) => {
+            let seconds = if t.len() == 3 { t[2] } else { 0 };
+            local_timestamp(d[0], d[1], d[2], t[0], t[1], seconds)
+        },
+        _ => None,
+    }
+}
+
+/// Converts a date and time in the local time zone into a Unix timestamp,
+/// returning `None` if any of the fields are out of range.
+fn local_timestamp(year: i32, month: i32, day: i32, hour: i32, minute: i32, second: i32) -> Option<i64> {
+    if month < 1 || month > 12 || day < 1 || day > 31
+    || hour < 0 || hour > 23 || minute < 0 || minute > 59 || second < 0 || second > 60 {
+        return None;
+    }
+
+    let mut tm: libc::tm = unsafe { mem::zeroed() };
+    tm.tm_year  = year - 1900;
+    tm.tm_mon   = month - 1;
+    tm.tm_mday  = day;
+    tm.tm_hour  = hour;
+    tm.tm_min   = minute;
+    tm.tm_sec   = second;
+    tm.tm_isdst = -1;  // let mktime work out whether DST applies
+
+    match unsafe { libc::mktime(&mut tm) } {
+        -1         => None,
+        timestamp  => Some(i64::from(timestamp)),
+    }
+}
+
+
+impl OwnerFilter {
+
+    /// Determines the users and groups to filter files by, based on the
+    /// user’s command-line arguments. Each argument can be given more than
+    /// once, and takes either a name or a numeric ID, which can be prefixed
+    /// with a `!` to hide files with that owner instead.
+    pub fn deduce(matches: &MatchedFlags) -> Result<OwnerFilter, Misfire> {
+        let users = IdFilter::deduce(matches, &flags::OWNER, "a user name or ID",
+                                     |name| get_user_by_name(name).map(|u| u.uid()))?;
+
+        let groups = IdFilter::deduce(matches, &flags::OWNER_GROUP, "a group name or ID",
+                                      |name| get_group_by_name(name).map(|g| g.gid()))?;
+
+        Ok(OwnerFilter { users, groups })
+    }
+}
+
+impl IdFilter {
+    fn deduce<F>(matches: &MatchedFlags, arg: &'static Arg, expected: &'static str, lookup: F) -> Result<IdFilter, Misfire>
+    where F: Fn(&str) -> Option<u32> {
+        let mut filter = IdFilter::default();
+
+        for word in matches.get_all(arg) {
+            let string = word.to_string_lossy();
+            let (negated, name) = if string.starts_with('!') { (true, &string[1..]) }
+                                                        else { (false, &string[..]) };
+
+            let id = match name.parse() {
+                Ok(id)  => id,
+                Err(_)  => match lookup(name) {
+                    Some(id)  => id,
+                    None      => return Err(Misfire::BadValue(arg, word.to_os_string(), expected)),
+                },
+            };
+
+            if negated { filter.except.push(id) }
+                  else { filter.only.push(id) }
+        }
+
+        Ok(filter)
+    }
+}
+
+
+const TYPES: &[&str] = &[ "dirs", "files", "links", "sockets", "pipes", "devices" ];
+
+impl TypeFilter {
+
+    /// Determines the types of file to display, based on the user’s
+    /// command-line arguments. The `--only-dirs`, `--only-files`, and
+    /// `--only-links` arguments are shorthands for `--only-types`, and all
+    /// of them add to the same list.
+    ///
+    /// When recursing, directories always get kept, so the files inside
+    /// them can still be found.
+    pub fn deduce(matches: &MatchedFlags) -> Result<TypeFilter, Misfire> {
+        let mut types = Vec::new();
+
+        if matches.has(&flags::ONLY_DIRS)   { types.push(Type::Directory) }
+        if matches.has(&flags::ONLY_FILES)  { types.push(Type::File) }
+        if matches.has(&flags::ONLY_LINKS)  { types.push(Type::Link) }
+
+        for word in matches.get_all(&flags::ONLY_TYPES) {
+            match &*word.to_string_lossy() {
+                "dir"    | "dirs"    | "directories"  => types.push(Type::Directory),
+                "file"   | "files"                    => types.push(Type::File),
+                "link"   | "links"   | "symlinks"     => types.push(Type::Link),
+                "socket" | "sockets"                  => types.push(Type::Socket),
+                "pipe"   | "pipes"   | "fifos"        => types.push(Type::Pipe),
+                "device" | "devices"                  => { types.push(Type::BlockDevice); types.push(Type::CharDevice) },
+                _ => return Err(Misfire::bad_argument(&flags::ONLY_TYPES, word, TYPES)),
+            }
+        }
+
+        let keep_dirs = matches.has(&flags::RECURSE) || matches.has(&flags::TREE);
+        Ok(TypeFilter { types, keep_dirs })
+    }
+}
+
+
+impl SinceFilter {
+
+    /// Reads the manifest of an earlier listing from the file given to
+    /// `--since`, if there is one. Returns an error if it can’t be read, or
+    /// isn’t in the v1 porcelain format.
+    pub fn deduce(matches: &MatchedFlags) -> Result<SinceFilter, Misfire> {
+        let path = match matches.get(&flags::SINCE)? {
+            Some(path)  => PathBuf::from(path),
+            None        => return Ok(SinceFilter::default()),
+        };
+
+        let mut bytes = Vec::new();
+        if let Err(e) = File::open(&path).and_then(|mut f| f.read_to_end(&mut bytes)) {
+            return Err(Misfire::BadManifest(path, e.to_string()));
+        }
+
+        let manifest = match Manifest::parse(&bytes) {
+            Ok(manifest)  => manifest,
+            Err(e)        => return Err(Misfire::BadManifest(path, e)),
+        };
+
+        let keep_dirs = matches.has(&flags::RECURSE) || matches.has(&flags::TREE);
+        Ok(SinceFilter { manifest: Some(manifest), keep_dirs })
+    }
+}
+
+
+const CATEGORIES: &[&str] = &[ "image", "video", "audio", "document", "archive",
+                               "code", "crypto", "temp", "compiled" ];
+
+impl CategoryFilter {
+
+    /// Determines the categories of file to display, based on the user’s
+    /// command-line arguments. The categories are separated by commas, and
+    /// the argument can be given more than once, with each one adding to
+    /// the same list.
+    ///
+    /// When recursing, directories always get kept, so the files inside
+    /// them can still be found.
+    pub fn deduce(matches: &MatchedFlags) -> Result<CategoryFilter, Misfire> {
+        let mut categories = Vec::new();
+
+        for word in matches.get_all(&flags::CATEGORY) {
+            match &*word.to_string_lossy() {
+                "image"    | "images"                    => categories.push(FileCategory::Image),
+                "video"    | "videos"                    => categories.push(FileCategory::Video),
+                "audio"    | "music"                     => categories.push(FileCategory::Audio),
+                "document" | "documents" | "docs"        => categories.push(FileCategory::Document),
+                "archive"  | "archives"  | "compressed"  => categories.push(FileCategory::Archive),
+                "code"     | "source"                    => categories.push(FileCategory::Code),
+                "crypto"                                 => categories.push(FileCategory::Crypto),
+                "temp"     | "temporary"                 => categories.push(FileCategory::Temp),
+                "compiled"                               => categories.push(FileCategory::Compiled),
+                _ => return Err(Misfire::bad_argument(&flags::CATEGORY, word, CATEGORIES)),
+            }
+        }
+
+        let keep_dirs = matches.has(&flags::RECURSE) || matches.has(&flags::TREE);
+        Ok(CategoryFilter { categories, keep_dirs })
+    }
+}
+
+
+impl EmptyFilter {
+
+    /// Determines whether to only display empty files, or only non-empty
+    /// ones, based on the user’s command-line arguments. Asking for both at
+    /// once is an error.
+    ///
+    /// When only listing empty files while recursing, directories always
+    /// get kept, so the files inside them can still be found.
+    pub fn deduce(matches: &MatchedFlags) -> Result<EmptyFilter, Misfire> {
+        let emptiness = match (matches.has(&flags::EMPTY), matches.has(&flags::NON_EMPTY)) {
+            (true,  true)   => return Err(Misfire::Conflict(&flags::NON_EMPTY, &flags::EMPTY)),
+            (true,  false)  => Some(Emptiness::Empty),
+            (false, true)   => Some(Emptiness::NonEmpty),
+            (false, false)  => None,
+        };
+
+        let keep_dirs = emptiness == Some(Emptiness::Empty)
+                     && (matches.has(&flags::RECURSE) || matches.has(&flags::TREE));
+        Ok(EmptyFilter { emptiness, keep_dirs })
+    }
+}
+
+
+impl PermFilter {
+
+    /// Determines the permission bits to filter files by, based on the
+    /// user’s command-line arguments. The argument can be given more than
+    /// once, with files having to satisfy every one of them.
+    pub fn deduce(matches: &MatchedFlags) -> Result<PermFilter, Misfire> {
+        let mut predicates = Vec::new();
+
+        for word in matches.get_all(&flags::PERM) {
+            match PermPredicate::parse(&word.to_string_lossy()) {
+                Some(mut ps)  => predicates.append(&mut ps),
+                None          => return Err(Misfire::BadValue(&flags::PERM, word.to_os_string(), "a mode such as 0755, /111, -4000, or u+s")),
+            }
+        }
+
+        let keep_dirs = matches.has(&flags::RECURSE) || matches.has(&flags::TREE);
+        Ok(PermFilter { predicates, keep_dirs })
+    }
+}
+
+impl XattrFilter {
+
+    /// Determines the extended attributes to filter files by, based on the
+    /// user’s command-line arguments. Each one is either just the name of an
+    /// attribute, or a name and the value it has to have, separated by `=`.
+    /// The argument can be given more than once, with files having to have
+    /// every one of the attributes.
+    pub fn deduce(matches: &MatchedFlags) -> Result<XattrFilter, Misfire> {
+        use std::os::unix::ffi::OsStrExt;
+
+        let mut predicates = Vec::new();
+
+        for word in matches.get_all(&flags::XATTR_FILTER) {
+            let bytes = word.as_bytes();
+            let (name, value) = match bytes.iter().position(|&b| b == b'=') {
+                Some(index)  => (&bytes[.. index], Some(bytes[index + 1 ..].to_vec())),
+                None         => (bytes, None),
+            };
+
+            if name.is_empty() {
+                return Err(Misfire::BadValue(&flags::XATTR_FILTER, word.to_os_string(), "an attribute such as user.tag or user.tag=value"));
+            }
+
+            let name = String::from_utf8_lossy(name).into_owned();
+            predicates.push(XattrPredicate { name, value });
+        }
+
+        let keep_dirs = matches.has(&flags::RECURSE) || matches.has(&flags::TREE);
+        Ok(XattrFilter { predicates, keep_dirs })
+    }
+}
+
+
+impl PermPredicate {
+
+    /// Parses a permission spec in the style of `find -perm`. Octal modes
+    /// have to match exactly, or have all of their bits set when prefixed
+    /// with `-`, or any of them set when prefixed with `/`. Symbolic modes
+    /// in the style of `chmod`, such as `u+s` or `go-w,a+x`, give the bits
+    /// that have to be set or unset, with a `/` prefix meaning any of the
+    /// bits being set is enough.
+    fn parse(input: &str) -> Option<Vec<PermPredicate>> {
+        let (constructor, rest): (fn(u32) -> PermPredicate, &str) =
+            if input.starts_with('/')       { (PermPredicate::AnyOf, &input[1..]) }
+            else if input.starts_with('-')  { (PermPredicate::AllOf, &input[1..]) }
+            else                            { (PermPredicate::Exactly, input) };
+
+        if !rest.is_empty() && rest.chars().all(|c| c.is_digit(8)) {
+            return match u32::from_str_radix(rest, 8) {
+                Ok(bits) if bits <= 0o7777  => Some(vec![ constructor(bits) ]),
+                _                           => None,
+            };
+        }
+
+        rest.split(',').map(|clause| parse_symbolic(clause).map(|(bits, adding)| {
+            if !adding                      { PermPredicate::NoneOf(bits) }
+            else if input.starts_with('/')  { PermPredicate::AnyOf(bits) }
+            else                            { PermPredicate::AllOf(bits) }
+        })).collect()
+    }
+}
+
+/// Parses a single `chmod`-style clause, such as `u+s` or `go-w`, into the
+/// bits it refers to and whether they’re being added or removed.
+fn parse_symbolic(clause: &str) -> Option<(u32, bool)> {
+    let split = match clause.find(|c| c == '+' || c == '-') {
+        Some(index)  => index,
+        None         => return None,
+    };
+
+    let (who, rest) = clause.split_at(split);
+    let adding = rest.starts_with('+');
+
+    let mut classes = 0;
+    for c in who.chars() {
+        classes |= match c {
+            'u'  => 0o4700,
+            'g'  => 0o2070,
+            'o'  => 0o0007,
+            'a'  => 0o6777,
+            _    => return None,
+        };
+    }
+
+    if classes == 0 {
+        classes = 0o6777;
+    }
+
+    let mut perms = 0;
+    for c in rest[1..].chars() {
+        perms |= match c {
+            'r'  => 0o0444,
+            'w'  => 0o0222,
+            'x'  => 0o0111,
+            's'  => 0o6000,
+            't'  => 0o1000,
+            _    => return None,
+        };
+    }
+
+    if perms == 0 {
+        return None;
+    }
+
+    // The sticky bit doesn’t belong to any class, so it gets let through
+    // no matter which ones were given.
+    Some((perms & (classes | 0o1000), adding))
+}
+
 
-        // TODO: is to_string_lossy really the best way to handle
-        // invalid UTF-8 there?
+const GIT_STATUSES: &[&str] = &[ "new", "untracked", "modified", "deleted",
+                                 "renamed", "typechange", "ignored", "clean" ];
 
-        Ok(IgnorePatterns { patterns })
+impl GitFilter {
+
+    /// Determines which Git statuses to filter files by, based on the
+    /// comma-separated lists of statuses given to the “git-only” and
+    /// “git-exclude” arguments, which each add to their list when given
+    /// more than once. These get ignored if exa has been built
+    /// without Git support, in the same way as the “git” argument.
+    pub fn deduce(matches: &MatchedFlags) -> Result<GitFilter, Misfire> {
+        if !cfg!(feature="git") {
+            return Ok(GitFilter::default());
+        }
+
+        let only = parse_git_statuses(&flags::GIT_ONLY, matches.get_all(&flags::GIT_ONLY))?;
+        let exclude = parse_git_statuses(&flags::GIT_EXCLUDE, matches.get_all(&flags::GIT_EXCLUDE))?;
+
+        Ok(GitFilter { only, exclude })
     }
 }
 
+fn parse_git_statuses(arg: &'static Arg, words: Vec<&OsStr>) -> Result<Vec<GitStatus>, Misfire> {
+    words.into_iter().map(|word| match &*word.to_string_lossy() {
+        "new" | "untracked"  => Ok(GitStatus::New),
+        "modified"           => Ok(GitStatus::Modified),
+        "deleted"            => Ok(GitStatus::Deleted),
+        "renamed"            => Ok(GitStatus::Renamed),
+        "typechange"         => Ok(GitStatus::TypeChange),
+        "ignored"            => Ok(GitStatus::Ignored),
+        "clean"              => Ok(GitStatus::NotModified),
+        _                    => Err(Misfire::bad_argument(arg, word, GIT_STATUSES)),
+    }).collect()
+}
+
 
 
 #[cfg(test)]
@@ -130,44 +889,83 @@ mod test {
 
     macro_rules! test {
         ($name:ident: $type:ident <- $inputs:expr => $result:expr) => {
+            test!($name: $type <- $inputs; UseLastArguments => $result);
+        };
+
+        ($name:ident: $type:ident <- $inputs:expr; $strictness:ident => $result:expr) => {
+            test!(@deduce $name, $type::deduce, $inputs; $strictness => $result);
+        };
+
+        ($name:ident: $deduce:ident() <- $inputs:expr => $result:expr) => {
+            test!(@deduce $name, $deduce, $inputs; UseLastArguments => $result);
+        };
+
+        (@deduce $name:ident, $deduce:path, $inputs:expr; $strictness:ident => $result:expr) => {
             #[test]
             fn $name() {
-                use options::parser::{Args, Arg};
+                use options::parser::{Args, Arg, Strictness};
                 use std::ffi::OsString;
 
-                static TEST_ARGS: &[&Arg] = &[ &flags::SORT, &flags::ALL, &flags::TREE, &flags::IGNORE_GLOB ];
+                static TEST_ARGS: &[&Arg] = &[ &flags::SORT, &flags::ALL, &flags::ALMOST_ALL, &flags::DOTFILES, &flags::TREE, &flags::IGNORE_GLOB, &flags::PRUNE_GLOB,
+                                                &flags::REGEX, &flags::REGEX_EXCLUDE, &flags::SIZE_FILTER,
+                                                &flags::NEWER_THAN, &flags::OLDER_THAN, &flags::CHANGED_WITHIN,
+                                                &flags::TIME, &flags::ACCESSED, &flags::OWNER, &flags::OWNER_GROUP,
+                                                &flags::ONLY_DIRS, &flags::ONLY_FILES, &flags::ONLY_LINKS, &flags::ONLY_TYPES,
+                                                &flags::RECURSE, &flags::PERM, &flags::XATTR_FILTER, &flags::CATEGORY, &flags::EMPTY, &flags::NON_EMPTY,
+                                                &flags::GIT_ONLY, &flags::GIT_EXCLUDE, &flags::SINCE, &flags::MAX_RESULTS, &flags::NORMALIZE ];
 
                 let bits = $inputs.as_ref().into_iter().map(|&o| os(o)).collect::<Vec<OsString>>();
-                let results = Args(TEST_ARGS).parse(bits.iter());
-                assert_eq!($type::deduce(&results.unwrap().flags), $result);
+                let results = Args(TEST_ARGS).parse(bits.iter(), Strictness::$strictness).map_err(Misfire::InvalidOptions);
+                assert_eq!(results.and_then(|r| $deduce(&r.flags)), $result);
             }
         };
     }
 
     mod sort_fields {
         use super::*;
+        use options::parser::{Flag, ParseError};
 
         // Default behaviour
-        test!(empty:         SortField <- []                  => Ok(SortField::default()));
+        test!(empty:         SortKey <- []                  => Ok(vec![ SortKey::from(SortField::default()) ]));
 
         // Sort field arguments
-        test!(one_arg:       SortField <- ["--sort=cr"]       => Ok(SortField::CreatedDate));
-        test!(one_long:      SortField <- ["--sort=size"]     => Ok(SortField::Size));
-        test!(one_short:     SortField <- ["-saccessed"]      => Ok(SortField::AccessedDate));
-        test!(lowercase:     SortField <- ["--sort", "name"]  => Ok(SortField::Name(SortCase::Sensitive)));
-        test!(uppercase:     SortField <- ["--sort", "Name"]  => Ok(SortField::Name(SortCase::Insensitive)));
+        test!(one_arg:       SortKey <- ["--sort=cr"]       => Ok(vec![ SortKey::from(SortField::CreatedDate) ]));
+        test!(one_long:      SortKey <- ["--sort=size"]     => Ok(vec![ SortKey::from(SortField::Size) ]));
+        test!(one_short:     SortKey <- ["-saccessed"]      => Ok(vec![ SortKey::from(SortField::AccessedDate) ]));
+        test!(lowercase:     SortKey <- ["--sort", "name"]  => Ok(vec![ SortKey::from(SortField::Name(SortCase::Sensitive)) ]));
+        test!(uppercase:     SortKey <- ["--sort", "Name"]  => Ok(vec![ SortKey::from(SortField::Name(SortCase::Insensitive)) ]));
+        test!(name_case:     SortKey <- ["--sort=name-case"]      => Ok(vec![ SortKey::from(SortField::Name(SortCase::Sensitive)) ]));
+        test!(name_nocase:   SortKey <- ["--sort=name-nocase"]    => Ok(vec![ SortKey::from(SortField::Name(SortCase::Insensitive)) ]));
+        test!(version:       SortKey <- ["--sort=name-version"]  => Ok(vec![ SortKey::from(SortField::NameVersion) ]));
+        test!(locale:        SortKey <- ["--sort=name-locale"]   => Ok(vec![ SortKey::from(SortField::NameLocale) ]));
+        test!(extension:     SortKey <- ["--sort=ext"]      => Ok(vec![ SortKey::from(SortField::Extension(SortCase::Sensitive)) ]));
+        test!(blocks:        SortKey <- ["--sort=blocks"]   => Ok(vec![ SortKey::from(SortField::Blocks) ]));
+        test!(links:         SortKey <- ["--sort=links"]    => Ok(vec![ SortKey::from(SortField::Links) ]));
+        test!(git:           SortKey <- ["--sort=git"]      => Ok(vec![ SortKey::from(SortField::GitStatus) ]));
+        test!(extension_2:   SortKey <- ["--sort=Ext"]      => Ok(vec![ SortKey::from(SortField::Extension(SortCase::Insensitive)) ]));
 
         // Errors
-        test!(error:         SortField <- ["--sort=colour"]   => Err(Misfire::bad_argument(&flags::SORT, &os("colour"), super::SORTS)));
+        test!(error:         SortKey <- ["--sort=colour"]       => Err(Misfire::InvalidOptions(ParseError::BadValue { flag: Flag::Long("sort"), value: os("colour"), values: flags::SORTS })));
+        test!(error_2nd:     SortKey <- ["--sort=size,colour"]  => Err(Misfire::InvalidOptions(ParseError::BadValue { flag: Flag::Long("sort"), value: os("colour"), values: flags::SORTS })));
+        test!(error_typo:    SortKey <- ["-ssizze"]             => Err(Misfire::InvalidOptions(ParseError::BadValue { flag: Flag::Short(b's'), value: os("sizze"), values: flags::SORTS })));
 
-        // Overriding
-        test!(overridden:    SortField <- ["--sort=cr",       "--sort", "mod"]     => Ok(SortField::ModifiedDate));
-        test!(overridden_2:  SortField <- ["--sort", "none",  "--sort=Extension"]  => Ok(SortField::Extension(SortCase::Insensitive)));
+        // Multiple fields
+        test!(commas:        SortKey <- ["--sort=size,name"]    => Ok(vec![ SortKey::from(SortField::Size), SortKey::from(SortField::Name(SortCase::Sensitive)) ]));
+        test!(repeated:      SortKey <- ["--sort=cr",       "--sort", "mod"]     => Ok(vec![ SortKey::from(SortField::CreatedDate), SortKey::from(SortField::ModifiedDate) ]));
+        test!(repeated_2:    SortKey <- ["--sort", "none",  "--sort=Extension"]  => Ok(vec![ SortKey::from(SortField::Unsorted), SortKey::from(SortField::Extension(SortCase::Insensitive)) ]));
+
+        // Directions
+        test!(desc:          SortKey <- ["--sort=size:desc"]          => Ok(vec![ SortKey { field: SortField::Size, direction: SortDirection::Descending } ]));
+        test!(asc:           SortKey <- ["--sort=size:asc"]           => Ok(vec![ SortKey::from(SortField::Size) ]));
+        test!(both:          SortKey <- ["--sort=size:desc,name:asc"] => Ok(vec![ SortKey { field: SortField::Size, direction: SortDirection::Descending }, SortKey::from(SortField::Name(SortCase::Sensitive)) ]));
+        test!(bad_dir:       SortKey <- ["--sort=size:down"]          => Err(Misfire::InvalidOptions(ParseError::BadValue { flag: Flag::Long("sort"), value: os("down"), values: flags::SORT_DIRECTIONS })));
+        test!(bad_dir_field: SortKey <- ["--sort=sizze:desc"]         => Err(Misfire::InvalidOptions(ParseError::BadValue { flag: Flag::Long("sort"), value: os("sizze"), values: flags::SORTS })));
     }
 
 
     mod dot_filters {
         use super::*;
+        use options::parser::LAYER_END;
 
         // Default behaviour
         test!(empty:      DotFilter <- []               => Ok(DotFilter::JustFiles));
@@ -180,6 +978,49 @@ mod test {
         // --all and --tree
         test!(tree_a:     DotFilter <- ["-Ta"]          => Ok(DotFilter::Dotfiles));
         test!(tree_aa:    DotFilter <- ["-Taa"]         => Err(Misfire::TreeAllAll));
+
+        // --almost-all
+        test!(almost:     DotFilter <- ["--almost-all"] => Ok(DotFilter::Dotfiles));
+        test!(almost_aa:  DotFilter <- ["-Aaa"]         => Ok(DotFilter::Dotfiles));
+        test!(tree_aaa:   DotFilter <- ["-TaaA"]        => Ok(DotFilter::Dotfiles));
+
+        // Strict mode
+        test!(strict_almost:     DotFilter <- ["-A"]; ComplainAboutRedundantArguments   => Ok(DotFilter::Dotfiles));
+        test!(strict_almost_aa:  DotFilter <- ["-Aa"]; ComplainAboutRedundantArguments  => Err(Misfire::Useless(&flags::ALL, true, &flags::ALMOST_ALL)));
+        test!(strict_layers:     DotFilter <- ["-a", LAYER_END, "-A"]; ComplainAboutRedundantArguments  => Ok(DotFilter::Dotfiles));
+    }
+
+
+    mod dotfile_placements {
+        use super::*;
+        use options::parser::{Flag, ParseError};
+
+        // Default behaviour
+        test!(empty:      DotfilePlacement <- []                     => Ok(DotfilePlacement::Mixed));
+
+        // Placements
+        test!(first:      DotfilePlacement <- ["--dotfiles=first"]   => Ok(DotfilePlacement::First));
+        test!(last:       DotfilePlacement <- ["--dotfiles", "last"] => Ok(DotfilePlacement::Last));
+        test!(overridden: DotfilePlacement <- ["--dotfiles=first", "--dotfiles=mixed"]  => Ok(DotfilePlacement::Mixed));
+
+        // Errors
+        test!(error:      DotfilePlacement <- ["--dotfiles=middle"]  => Err(Misfire::InvalidOptions(ParseError::BadValue { flag: Flag::Long("dotfiles"), value: os("middle"), values: flags::DOTFILE_PLACEMENTS })));
+    }
+
+
+    mod normalizations {
+        use super::*;
+        use options::parser::{Flag, ParseError};
+
+        // Default behaviour
+        test!(empty:      Normalization <- []                      => Ok(None));
+
+        // Forms
+        test!(nfc:        Normalization <- ["--normalize=nfc"]     => Ok(Some(Normalization::Composed)));
+        test!(nfd:        Normalization <- ["--normalize", "nfd"]  => Ok(Some(Normalization::Decomposed)));
+
+        // Errors
+        test!(error:      Normalization <- ["--normalize=nfkc"]    => Err(Misfire::InvalidOptions(ParseError::BadValue { flag: Flag::Long("normalize"), value: os("nfkc"), values: flags::NORMALIZATIONS })));
     }
 
 
@@ -196,5 +1037,323 @@ mod test {
         test!(one:    IgnorePatterns <- ["--ignore-glob", "*.ogg"]     => Ok(IgnorePatterns { patterns: vec![ pat("*.ogg") ] }));
         test!(two:    IgnorePatterns <- ["--ignore-glob=*.ogg|*.MP3"]  => Ok(IgnorePatterns { patterns: vec![ pat("*.ogg"), pat("*.MP3") ] }));
         test!(loads:  IgnorePatterns <- ["-I*|?|.|*"]  => Ok(IgnorePatterns { patterns: vec![ pat("*"), pat("?"), pat("."), pat("*") ] }));
+
+        // Repeated arguments
+        test!(again:  IgnorePatterns <- ["-I", "target", "--ignore-glob=*.o"]        => Ok(IgnorePatterns { patterns: vec![ pat("target"), pat("*.o") ] }));
+        test!(mixed:  IgnorePatterns <- ["-Itarget|*.o", "--ignore-glob", "*.rlib"]  => Ok(IgnorePatterns { patterns: vec![ pat("target"), pat("*.o"), pat("*.rlib") ] }));
+    }
+
+
+    mod prune_patterns {
+        use super::*;
+        use glob;
+
+        fn pat(string: &'static str) -> glob::Pattern {
+            glob::Pattern::new(string).unwrap()
+        }
+
+        test!(none:   PrunePatterns <- []                                   => Ok(PrunePatterns { patterns: vec![] }));
+        test!(two:    PrunePatterns <- ["--prune-glob=node_modules|.git"]   => Ok(PrunePatterns { patterns: vec![ pat("node_modules"), pat(".git") ] }));
+        test!(again:  PrunePatterns <- ["--prune-glob", "target", "--prune-glob=build*"]  => Ok(PrunePatterns { patterns: vec![ pat("target"), pat("build*") ] }));
+        test!(apart:  PrunePatterns <- ["-I", "target"]                     => Ok(PrunePatterns { patterns: vec![] }));
+    }
+
+
+    mod regex_filters {
+        use super::*;
+
+        fn re(string: &'static str) -> Regex {
+            Regex::new(string).unwrap()
+        }
+
+        // Various numbers of regexes
+        test!(none:     RegexFilter <- []                                 => Ok(RegexFilter { include: vec![], exclude: vec![] }));
+        test!(include:  RegexFilter <- ["--regex", "^src"]               => Ok(RegexFilter { include: vec![ re("^src") ], exclude: vec![] }));
+        test!(exclude:  RegexFilter <- ["--regex-exclude=\\.o$"]          => Ok(RegexFilter { include: vec![], exclude: vec![ re("\\.o$") ] }));
+        test!(both:     RegexFilter <- ["--regex=a", "--regex-exclude=b", "--regex=c"]  => Ok(RegexFilter { include: vec![ re("a"), re("c") ], exclude: vec![ re("b") ] }));
+
+        // Errors
+        test!(error:    RegexFilter <- ["--regex=("]                      => Err(Misfire::from(Regex::new("(").unwrap_err())));
+    }
+
+
+    mod size_filters {
+        use super::*;
+        use fs::filter::SizePredicate::*;
+
+        // Default behaviour
+        test!(none:      SizeFilter <- []                          => Ok(SizeFilter { predicates: vec![] }));
+
+        // Predicates and units
+        test!(bytes:     SizeFilter <- ["--size-filter=512"]       => Ok(SizeFilter { predicates: vec![ Exactly(512) ] }));
+        test!(larger:    SizeFilter <- ["--size-filter=+10M"]      => Ok(SizeFilter { predicates: vec![ LargerThan(10_000_000) ] }));
+        test!(smaller:   SizeFilter <- ["--size-filter", "-4k"]    => Ok(SizeFilter { predicates: vec![ SmallerThan(4_000) ] }));
+        test!(binary:    SizeFilter <- ["--size-filter=+1GiB"]     => Ok(SizeFilter { predicates: vec![ LargerThan(1 << 30) ] }));
+        test!(range:     SizeFilter <- ["--size-filter=+1k", "--size-filter=-1m"]  => Ok(SizeFilter { predicates: vec![ LargerThan(1_000), SmallerThan(1_000_000) ] }));
+
+        // Errors
+        test!(no_number: SizeFilter <- ["--size-filter=+M"]        => Err(Misfire::BadValue(&flags::SIZE_FILTER, os("+M"), "a size such as +10M or -4k")));
+        test!(bad_unit:  SizeFilter <- ["--size-filter=10parsecs"] => Err(Misfire::BadValue(&flags::SIZE_FILTER, os("10parsecs"), "a size such as +10M or -4k")));
+        test!(overflow:  SizeFilter <- ["--size-filter=+99999999999T"]  => Err(Misfire::BadValue(&flags::SIZE_FILTER, os("+99999999999T"), "a size that fits in 64 bits")));
+    }
+
+
+    mod time_filters {
+        use super::*;
+
+        fn filter(field: TimeField, newer_than: Option<i64>, older_than: Option<i64>) -> TimeFilter {
+            TimeFilter { field, newer_than, older_than }
+        }
+
+        // Default behaviour
+        test!(none:       TimeFilter <- []                                => Ok(TimeFilter::default()));
+
+        // Dates
+        test!(date:       TimeFilter <- ["--older-than=2017-01-01"]       => Ok(filter(TimeField::Modified, None, local_timestamp(2017, 1, 1, 0, 0, 0))));
+        test!(date_time:  TimeFilter <- ["--newer-than=2017-06-30T12:30"] => Ok(filter(TimeField::Modified, local_timestamp(2017, 6, 30, 12, 30, 0), None)));
+        test!(within:     TimeFilter <- ["--changed-within", "2017-06-30"] => Ok(filter(TimeField::Modified, local_timestamp(2017, 6, 30, 0, 0, 0), None)));
+
+        // Timestamp fields
+        test!(accessed:   TimeFilter <- ["--newer-than=2017-01-01", "-u"]         => Ok(filter(TimeField::Accessed, local_timestamp(2017, 1, 1, 0, 0, 0), None)));
+        test!(created:    TimeFilter <- ["--newer-than=2017-01-01", "--time=cr"]  => Ok(filter(TimeField::Created, local_timestamp(2017, 1, 1, 0, 0, 0), None)));
+
+        // Errors
+        test!(conflict:   TimeFilter <- ["--newer-than=1d", "--changed-within=2d"]  => Err(Misfire::Conflict(&flags::CHANGED_WITHIN, &flags::NEWER_THAN)));
+        test!(bad_unit:   TimeFilter <- ["--newer-than=2fortnights"]     => Err(Misfire::BadValue(&flags::NEWER_THAN, os("2fortnights"), "a duration such as 2d or 30min, or a date such as 2017-01-01")));
+        test!(bad_date:   TimeFilter <- ["--older-than=2017-13-01"]       => Err(Misfire::BadValue(&flags::OLDER_THAN, os("2017-13-01"), "a duration such as 2d or 30min, or a date such as 2017-01-01")));
+        test!(too_long:   TimeFilter <- ["--changed-within=9999999999999y"]  => Err(Misfire::BadValue(&flags::CHANGED_WITHIN, os("9999999999999y"), "a duration that isn’t so long")));
+    }
+
+
+    mod timestamps {
+        use super::*;
+        use super::super::deduce_timestamp;
+
+        const NOW: i64 = 1_500_000_000;
+
+        macro_rules! test {
+            ($name:ident: $input:expr => $result:expr) => {
+                #[test]
+                fn $name() {
+                    assert_eq!(deduce_timestamp(&flags::NEWER_THAN, &os($input), NOW), $result);
+                }
+            };
+        }
+
+        test!(seconds:    "90s"            => Ok(NOW - 90));
+        test!(minutes:    "30min"          => Ok(NOW - 30 * 60));
+        test!(days:       "2d"             => Ok(NOW - 2 * 24 * 60 * 60));
+        test!(weeks:      "1w"             => Ok(NOW - 7 * 24 * 60 * 60));
+        test!(date:       "2017-01-01"     => Ok(local_timestamp(2017, 1, 1, 0, 0, 0).unwrap()));
+        test!(overflow:   "9999999999999y" => Err(Misfire::BadValue(&flags::NEWER_THAN, os("9999999999999y"), "a duration that isn’t so long")));
+        test!(huge:       "99999999999999999999s" => Err(Misfire::BadValue(&flags::NEWER_THAN, os("99999999999999999999s"), "a duration that isn’t so long")));
+        test!(no_number:  "d"              => Err(Misfire::BadValue(&flags::NEWER_THAN, os("d"), "a duration such as 2d or 30min, or a date such as 2017-01-01")));
+    }
+
+
+    mod owner_filters {
+        use super::*;
+
+        fn ids(only: Vec<u32>, except: Vec<u32>) -> IdFilter {
+            IdFilter { only, except }
+        }
+
+        // Default behaviour
+        test!(none:      OwnerFilter <- []                                => Ok(OwnerFilter::default()));
+
+        // Names and IDs
+        test!(root:      OwnerFilter <- ["--owner=root"]                  => Ok(OwnerFilter { users: ids(vec![ 0 ], vec![]), groups: IdFilter::default() }));
+        test!(numeric:   OwnerFilter <- ["--owner", "1000", "--owner=33"] => Ok(OwnerFilter { users: ids(vec![ 1000, 33 ], vec![]), groups: IdFilter::default() }));
+        test!(negated:   OwnerFilter <- ["--owner=!root", "--owner=1000"] => Ok(OwnerFilter { users: ids(vec![ 1000 ], vec![ 0 ]), groups: IdFilter::default() }));
+        test!(group:     OwnerFilter <- ["--owner-group=!100"]            => Ok(OwnerFilter { users: IdFilter::default(), groups: ids(vec![], vec![ 100 ]) }));
+
+        // Errors
+        test!(unknown:   OwnerFilter <- ["--owner=nobody-at-all"]         => Err(Misfire::BadValue(&flags::OWNER, os("nobody-at-all"), "a user name or ID")));
+    }
+
+
+    mod type_filters {
+        use super::*;
+        use fs::fields::Type;
+
+        // Default behaviour
+        test!(none:      TypeFilter <- []                                   => Ok(TypeFilter { types: vec![], keep_dirs: false }));
+
+        // Shorthands
+        test!(dirs:      TypeFilter <- ["--only-dirs"]                      => Ok(TypeFilter { types: vec![ Type::Directory ], keep_dirs: false }));
+        test!(two:       TypeFilter <- ["--only-links", "--only-files"]     => Ok(TypeFilter { types: vec![ Type::File, Type::Link ], keep_dirs: false }));
+
+        // Lists of types
+        test!(list:      TypeFilter <- ["--only-types=sockets,pipes"]       => Ok(TypeFilter { types: vec![ Type::Socket, Type::Pipe ], keep_dirs: false }));
+        test!(devices:   TypeFilter <- ["--only-types", "devices"]          => Ok(TypeFilter { types: vec![ Type::BlockDevice, Type::CharDevice ], keep_dirs: false }));
+        test!(mixed:     TypeFilter <- ["--only-dirs", "--only-types=file"] => Ok(TypeFilter { types: vec![ Type::Directory, Type::File ], keep_dirs: false }));
+        test!(repeated:  TypeFilter <- ["--only-types=dirs", "--only-types", "links,pipes"]  => Ok(TypeFilter { types: vec![ Type::Directory, Type::Link, Type::Pipe ], keep_dirs: false }));
+
+        // Recursing
+        test!(tree:      TypeFilter <- ["--only-files", "--tree"]           => Ok(TypeFilter { types: vec![ Type::File ], keep_dirs: true }));
+        test!(recurse:   TypeFilter <- ["--only-links", "-R"]               => Ok(TypeFilter { types: vec![ Type::Link ], keep_dirs: true }));
+
+        // Errors
+        test!(error:     TypeFilter <- ["--only-types=files,doors"]         => Err(Misfire::bad_argument(&flags::ONLY_TYPES, &os("doors"), super::TYPES)));
+    }
+
+
+    mod perm_filters {
+        use super::*;
+        use fs::filter::PermPredicate::*;
+
+        fn perms(predicates: Vec<PermPredicate>) -> PermFilter {
+            PermFilter { predicates, keep_dirs: false }
+        }
+
+        // Default behaviour
+        test!(none:       PermFilter <- []                         => Ok(perms(vec![])));
+
+        // Octal modes
+        test!(exact:      PermFilter <- ["--perm=0755"]            => Ok(perms(vec![ Exactly(0o755) ])));
+        test!(all_of:     PermFilter <- ["--perm", "-4000"]        => Ok(perms(vec![ AllOf(0o4000) ])));
+        test!(any_of:     PermFilter <- ["--perm=/222"]            => Ok(perms(vec![ AnyOf(0o222) ])));
+
+        // Symbolic modes
+        test!(setuid:     PermFilter <- ["--perm=u+s"]             => Ok(perms(vec![ AllOf(0o4000) ])));
+        test!(all_exec:   PermFilter <- ["--perm=a+x"]             => Ok(perms(vec![ AllOf(0o111) ])));
+        test!(any_exec:   PermFilter <- ["--perm=/+x"]             => Ok(perms(vec![ AnyOf(0o111) ])));
+        test!(clauses:    PermFilter <- ["--perm=o+w,g-r"]         => Ok(perms(vec![ AllOf(0o002), NoneOf(0o040) ])));
+        test!(sticky:     PermFilter <- ["--perm=o+t"]             => Ok(perms(vec![ AllOf(0o1000) ])));
+        test!(repeated:   PermFilter <- ["--perm=u+x", "--perm=o-w"]  => Ok(perms(vec![ AllOf(0o100), NoneOf(0o002) ])));
+
+        // Errors
+        test!(too_big:    PermFilter <- ["--perm=17777"]           => Err(Misfire::BadValue(&flags::PERM, os("17777"), "a mode such as 0755, /111, -4000, or u+s")));
+        test!(bad_class:  PermFilter <- ["--perm=z+x"]             => Err(Misfire::BadValue(&flags::PERM, os("z+x"), "a mode such as 0755, /111, -4000, or u+s")));
+        test!(no_perms:   PermFilter <- ["--perm=u+"]              => Err(Misfire::BadValue(&flags::PERM, os("u+"), "a mode such as 0755, /111, -4000, or u+s")));
+    }
+
+
+    mod category_filters {
+        use super::*;
+        use info::filetype::FileCategory::*;
+
+        // Default behaviour
+        test!(none:      CategoryFilter <- []                                => Ok(CategoryFilter { categories: vec![], keep_dirs: false }));
+
+        // Lists of categories
+        test!(one:       CategoryFilter <- ["--category=image"]              => Ok(CategoryFilter { categories: vec![ Image ], keep_dirs: false }));
+        test!(list:      CategoryFilter <- ["--category", "video,code"]      => Ok(CategoryFilter { categories: vec![ Video, Code ], keep_dirs: false }));
+        test!(aliases:   CategoryFilter <- ["--category=music,compressed"]   => Ok(CategoryFilter { categories: vec![ Audio, Archive ], keep_dirs: false }));
+        test!(repeated:  CategoryFilter <- ["--category=temp", "--category=compiled"]  => Ok(CategoryFilter { categories: vec![ Temp, Compiled ], keep_dirs: false }));
+        test!(tree:      CategoryFilter <- ["--category=image", "--tree"]    => Ok(CategoryFilter { categories: vec![ Image ], keep_dirs: true }));
+
+        // Errors
+        test!(error:     CategoryFilter <- ["--category=image,spreadsheet"]  => Err(Misfire::bad_argument(&flags::CATEGORY, &os("spreadsheet"), super::CATEGORIES)));
+    }
+
+
+    mod empty_filters {
+        use super::*;
+        use fs::filter::Emptiness::*;
+
+        // Default behaviour
+        test!(none:       EmptyFilter <- []                         => Ok(EmptyFilter { emptiness: None,           keep_dirs: false }));
+
+        // Either one or the other
+        test!(empty:      EmptyFilter <- ["--empty"]                => Ok(EmptyFilter { emptiness: Some(Empty),    keep_dirs: false }));
+        test!(non_empty:  EmptyFilter <- ["--non-empty"]            => Ok(EmptyFilter { emptiness: Some(NonEmpty), keep_dirs: false }));
+
+        // Recursing
+        test!(recurse:    EmptyFilter <- ["--empty", "--recurse"]   => Ok(EmptyFilter { emptiness: Some(Empty),    keep_dirs: true }));
+        test!(tree:       EmptyFilter <- ["--non-empty", "--tree"]  => Ok(EmptyFilter { emptiness: Some(NonEmpty), keep_dirs: false }));
+
+        // Errors
+        test!(both:       EmptyFilter <- ["--empty", "--non-empty"] => Err(Misfire::Conflict(&flags::NON_EMPTY, &flags::EMPTY)));
+    }
+
+
+    mod xattr_filters {
+        use super::*;
+
+        fn xattr(name: &str, value: Option<&str>) -> XattrPredicate {
+            XattrPredicate { name: name.to_string(), value: value.map(|v| v.as_bytes().to_vec()) }
+        }
+
+        // Default behaviour
+        test!(none:       XattrFilter <- []                                    => Ok(XattrFilter::default()));
+
+        // Names and values
+        test!(name:       XattrFilter <- ["--xattr-filter=user.tag"]           => Ok(XattrFilter { predicates: vec![ xattr("user.tag", None) ], keep_dirs: false }));
+        test!(value:      XattrFilter <- ["--xattr-filter", "user.tag=a=b"]    => Ok(XattrFilter { predicates: vec![ xattr("user.tag", Some("a=b")) ], keep_dirs: false }));
+        test!(empty:      XattrFilter <- ["--xattr-filter=user.tag="]          => Ok(XattrFilter { predicates: vec![ xattr("user.tag", Some("")) ], keep_dirs: false }));
+        test!(repeated:   XattrFilter <- ["--xattr-filter=user.a", "--xattr-filter=user.b=c"]  => Ok(XattrFilter { predicates: vec![ xattr("user.a", None), xattr("user.b", Some("c")) ], keep_dirs: false }));
+        test!(recursing:  XattrFilter <- ["--xattr-filter=user.a", "--recurse"]  => Ok(XattrFilter { predicates: vec![ xattr("user.a", None) ], keep_dirs: true }));
+
+        // Errors
+        test!(no_name:    XattrFilter <- ["--xattr-filter==x"]                 => Err(Misfire::BadValue(&flags::XATTR_FILTER, os("=x"), "an attribute such as user.tag or user.tag=value")));
+    }
+
+
+    #[cfg(feature="git")]
+    mod git_filters {
+        use super::*;
+        use fs::fields::GitStatus;
+
+        // Default behaviour
+        test!(empty:      GitFilter <- []                                  => Ok(GitFilter::default()));
+
+        // Status lists
+        test!(only:       GitFilter <- ["--git-only=modified,untracked"]   => Ok(GitFilter { only: vec![ GitStatus::Modified, GitStatus::New ], exclude: vec![] }));
+        test!(exclude:    GitFilter <- ["--git-exclude", "ignored,clean"]  => Ok(GitFilter { only: vec![], exclude: vec![ GitStatus::Ignored, GitStatus::NotModified ] }));
+        test!(both:       GitFilter <- ["--git-only=new", "--git-exclude=deleted"]  => Ok(GitFilter { only: vec![ GitStatus::New ], exclude: vec![ GitStatus::Deleted ] }));
+
+        // Repeating
+        test!(repeated:   GitFilter <- ["--git-only=new", "--git-only=renamed"]     => Ok(GitFilter { only: vec![ GitStatus::New, GitStatus::Renamed ], exclude: vec![] }));
+
+        // Errors
+        test!(error:      GitFilter <- ["--git-only=modified,dirty"]       => Err(Misfire::bad_argument(&flags::GIT_ONLY, &os("dirty"), super::GIT_STATUSES)));
+    }
+
+
+    mod max_results {
+        use super::*;
+        use super::super::deduce_max_results;
+
+        // Default behaviour
+        test!(empty:       deduce_max_results() <- []                                      => Ok(None));
+
+        // Counts
+        test!(count:       deduce_max_results() <- ["--max-results", "20"]                 => Ok(Some(20)));
+        test!(overridden:  deduce_max_results() <- ["--max-results=20", "--max-results=5"] => Ok(Some(5)));
+
+        // Errors
+        test!(not_a_number: deduce_max_results() <- ["--max-results=many"]                 => Err(Misfire::FailedParse("many".parse::<usize>().unwrap_err())));
+    }
+
+
+    mod since_filters {
+        use super::*;
+        use options::parser::Strictness;
+
+        // Default behaviour
+        test!(empty:  SinceFilter <- []  => Ok(SinceFilter::default()));
+
+        #[test]
+        fn missing() {
+            let bits = [ os("--since=/proc/nonexistent/manifest") ];
+            let results = flags::ALL_ARGS.parse(bits.iter(), Strictness::UseLastArguments).unwrap();
+
+            match SinceFilter::deduce(&results.flags) {
+                Err(Misfire::BadManifest(path, _))  => assert_eq!(path, PathBuf::from("/proc/nonexistent/manifest")),
+                otherwise                           => panic!("unexpected result {:?}", otherwise),
+            }
+        }
+
+        #[test]
+        fn not_a_manifest() {
+            let bits = [ os("--since=Cargo.toml") ];
+            let results = flags::ALL_ARGS.parse(bits.iter(), Strictness::UseLastArguments).unwrap();
+
+            match SinceFilter::deduce(&results.flags) {
+                Err(Misfire::BadManifest(_, e))  => assert_eq!(e, "line 1 isn’t in the v1 porcelain format"),
+                otherwise                        => panic!("unexpected result {:?}", otherwise),
+            }
+        }
     }
 }
@@ -1,9 +1,34 @@
-use options::parser::{Arg, Args, TakesValue};
+use options::parser::{Arg, Args, TakesValue, Values};
+use output::PRESETS;
+
+
+// the values of options that only accept a fixed set of them
+pub const FORMATS:            Values = &[ "fzf", "v1" ];
+pub const COLOURS:            Values = &[ "always", "auto", "automatic", "never" ];
+pub const DEPTHS:             Values = &[ "truecolor", "truecolour", "24bit", "256", "16", "8", "none" ];
+pub const DOTFILE_PLACEMENTS: Values = &[ "first", "last", "mixed" ];
+pub const NORMALIZATIONS:     Values = &[ "nfc", "nfd" ];
+pub const SCALES:             Values = &[ "size", "age", "age-names", "blocks", "links" ];
+pub const SORTS:              Values = &[ "name", "filename", "Name", "Filename", "name-case", "name-nocase",
+                                          "name-version", "version", "name-locale", "locale", "size", "filesize",
+                                          "extension", "ext", "Extension", "Ext", "modified", "mod", "accessed", "acc",
+                                          "created", "cr", "inode", "blocks", "links", "git", "type", "none" ];
+pub const SORT_DIRECTIONS:    Values = &[ "asc", "desc" ];
+pub const TIMES:              Values = &[ "modified", "mod", "accessed", "acc", "created", "cr" ];
+pub const TIME_STYLES:        Values = &[ "default", "long-iso", "full-iso", "iso" ];
 
 
 // exa options
 pub static VERSION: Arg = Arg { short: Some(b'v'), long: "version",  takes_value: TakesValue::Forbidden };
 pub static HELP:    Arg = Arg { short: Some(b'?'), long: "help",     takes_value: TakesValue::Forbidden };
+pub static THREADS: Arg = Arg { short: None,       long: "threads",  takes_value: TakesValue::Necessary(None) };
+pub static STDIN:   Arg = Arg { short: None,       long: "stdin",    takes_value: TakesValue::Forbidden };
+pub static QUIET:   Arg = Arg { short: None,       long: "quiet",    takes_value: TakesValue::Forbidden };
+pub static DETERMINISTIC: Arg = Arg { short: None, long: "deterministic", takes_value: TakesValue::Forbidden };
+pub static NO_CONFIG: Arg = Arg { short: None, long: "no-config", takes_value: TakesValue::Forbidden };
+
+// hidden options, which don’t get listed in the help text
+pub static BENCH:   Arg = Arg { short: None,       long: "bench",    takes_value: TakesValue::Forbidden };
 
 // display options
 pub static ONE_LINE: Arg = Arg { short: Some(b'1'), long: "oneline",  takes_value: TakesValue::Forbidden };
@@ -14,21 +39,56 @@ pub static RECURSE:  Arg = Arg { short: Some(b'R'), long: "recurse",  takes_valu
 pub static TREE:     Arg = Arg { short: Some(b'T'), long: "tree",     takes_value: TakesValue::Forbidden };
 pub static CLASSIFY: Arg = Arg { short: Some(b'F'), long: "classify", takes_value: TakesValue::Forbidden };
 
-pub static COLOR:  Arg = Arg { short: None, long: "color",  takes_value: TakesValue::Necessary };
-pub static COLOUR: Arg = Arg { short: None, long: "colour", takes_value: TakesValue::Necessary };
+pub static PORCELAIN: Arg = Arg { short: None, long: "porcelain", takes_value: TakesValue::Necessary(Some(FORMATS)) };
+pub static SNAPSHOT:  Arg = Arg { short: None, long: "snapshot",  takes_value: TakesValue::Necessary(None) };
 
-pub static COLOR_SCALE:  Arg = Arg { short: None, long: "color-scale",  takes_value: TakesValue::Forbidden };
-pub static COLOUR_SCALE: Arg = Arg { short: None, long: "colour-scale", takes_value: TakesValue::Forbidden };
+pub static COLOR:  Arg = Arg { short: None, long: "color",  takes_value: TakesValue::Optional(Some(COLOURS), Some("always")) };
+pub static COLOUR: Arg = Arg { short: None, long: "colour", takes_value: TakesValue::Optional(Some(COLOURS), Some("always")) };
+
+pub static COLOR_SCALE:  Arg = Arg { short: None, long: "color-scale",  takes_value: TakesValue::OptionalList(SCALES) };
+pub static COLOUR_SCALE: Arg = Arg { short: None, long: "colour-scale", takes_value: TakesValue::OptionalList(SCALES) };
+pub static COLOR_DEPTH:  Arg = Arg { short: None, long: "color-depth",  takes_value: TakesValue::Necessary(Some(DEPTHS)) };
+pub static COLOUR_DEPTH: Arg = Arg { short: None, long: "colour-depth", takes_value: TakesValue::Necessary(Some(DEPTHS)) };
+pub static THEME:        Arg = Arg { short: None, long: "theme",        takes_value: TakesValue::Necessary(Some(PRESETS)) };
+pub static HIGHLIGHT_OWNERS: Arg = Arg { short: None, long: "highlight-owners", takes_value: TakesValue::Forbidden };
+pub static PLAIN_COLUMNS:    Arg = Arg { short: None, long: "plain-columns",    takes_value: TakesValue::Necessary(None) };
 
 // filtering and sorting options
 pub static ALL:         Arg = Arg { short: Some(b'a'), long: "all",         takes_value: TakesValue::Forbidden };
+pub static ALMOST_ALL:  Arg = Arg { short: Some(b'A'), long: "almost-all",  takes_value: TakesValue::Forbidden };
+pub static DOTFILES:    Arg = Arg { short: None,       long: "dotfiles",    takes_value: TakesValue::Necessary(Some(DOTFILE_PLACEMENTS)) };
 pub static LIST_DIRS:   Arg = Arg { short: Some(b'd'), long: "list-dirs",   takes_value: TakesValue::Forbidden };
-pub static LEVEL:       Arg = Arg { short: Some(b'L'), long: "level",       takes_value: TakesValue::Necessary };
+pub static LEVEL:       Arg = Arg { short: Some(b'L'), long: "level",       takes_value: TakesValue::Necessary(None) };
+pub static MIN_DEPTH:   Arg = Arg { short: None,       long: "min-depth",   takes_value: TakesValue::Necessary(None) };
 pub static REVERSE:     Arg = Arg { short: Some(b'r'), long: "reverse",     takes_value: TakesValue::Forbidden };
-pub static SORT:        Arg = Arg { short: Some(b's'), long: "sort",        takes_value: TakesValue::Necessary };
-pub static IGNORE_GLOB: Arg = Arg { short: Some(b'I'), long: "ignore-glob", takes_value: TakesValue::Necessary };
+pub static SORT:        Arg = Arg { short: Some(b's'), long: "sort",        takes_value: TakesValue::SuffixedList(SORTS, SORT_DIRECTIONS) };
+pub static IGNORE_GLOB: Arg = Arg { short: Some(b'I'), long: "ignore-glob", takes_value: TakesValue::Necessary(None) };
+pub static PRUNE_GLOB:  Arg = Arg { short: None,       long: "prune-glob",  takes_value: TakesValue::Necessary(None) };
 pub static DIRS_FIRST:  Arg = Arg { short: None, long: "group-directories-first",  takes_value: TakesValue::Forbidden };
 
+pub static REGEX:          Arg = Arg { short: None, long: "regex",          takes_value: TakesValue::Necessary(None) };
+pub static REGEX_EXCLUDE:  Arg = Arg { short: None, long: "regex-exclude",  takes_value: TakesValue::Necessary(None) };
+pub static SIZE_FILTER:    Arg = Arg { short: None, long: "size-filter",    takes_value: TakesValue::Necessary(None) };
+pub static NEWER_THAN:     Arg = Arg { short: None, long: "newer-than",     takes_value: TakesValue::Necessary(None) };
+pub static OLDER_THAN:     Arg = Arg { short: None, long: "older-than",     takes_value: TakesValue::Necessary(None) };
+pub static CHANGED_WITHIN: Arg = Arg { short: None, long: "changed-within", takes_value: TakesValue::Necessary(None) };
+pub static OWNER:          Arg = Arg { short: None, long: "owner",          takes_value: TakesValue::Necessary(None) };
+pub static OWNER_GROUP:    Arg = Arg { short: None, long: "owner-group",    takes_value: TakesValue::Necessary(None) };
+pub static ONLY_DIRS:      Arg = Arg { short: None, long: "only-dirs",      takes_value: TakesValue::Forbidden };
+pub static ONLY_FILES:     Arg = Arg { short: None, long: "only-files",     takes_value: TakesValue::Forbidden };
+pub static ONLY_LINKS:     Arg = Arg { short: None, long: "only-links",     takes_value: TakesValue::Forbidden };
+pub static ONLY_TYPES:     Arg = Arg { short: None, long: "only-types",     takes_value: TakesValue::List(None) };
+pub static PERM:           Arg = Arg { short: None, long: "perm",           takes_value: TakesValue::Necessary(None) };
+pub static CATEGORY:       Arg = Arg { short: None, long: "category",       takes_value: TakesValue::List(None) };
+pub static XATTR_FILTER:   Arg = Arg { short: None, long: "xattr-filter",   takes_value: TakesValue::Necessary(None) };
+pub static EMPTY:          Arg = Arg { short: None, long: "empty",          takes_value: TakesValue::Forbidden };
+pub static NON_EMPTY:      Arg = Arg { short: None, long: "non-empty",      takes_value: TakesValue::Forbidden };
+pub static DOT_IGNORE:     Arg = Arg { short: None, long: "dot-ignore",     takes_value: TakesValue::Forbidden };
+pub static DEDUPE:         Arg = Arg { short: None, long: "dedupe",         takes_value: TakesValue::Forbidden };
+pub static NORMALIZE:      Arg = Arg { short: None, long: "normalize",      takes_value: TakesValue::Necessary(Some(NORMALIZATIONS)) };
+pub static MAX_RESULTS:    Arg = Arg { short: None, long: "max-results",    takes_value: TakesValue::Necessary(None) };
+pub static SINCE:          Arg = Arg { short: None, long: "since",          takes_value: TakesValue::Necessary(None) };
+
 // display options
 pub static BINARY:     Arg = Arg { short: Some(b'b'), long: "binary",     takes_value: TakesValue::Forbidden };
 pub static BYTES:      Arg = Arg { short: Some(b'B'), long: "bytes",      takes_value: TakesValue::Forbidden };
@@ -38,27 +98,35 @@ pub static INODE:      Arg = Arg { short: Some(b'i'), long: "inode",      takes_
 pub static LINKS:      Arg = Arg { short: Some(b'H'), long: "links",      takes_value: TakesValue::Forbidden };
 pub static MODIFIED:   Arg = Arg { short: Some(b'm'), long: "modified",   takes_value: TakesValue::Forbidden };
 pub static BLOCKS:     Arg = Arg { short: Some(b'S'), long: "blocks",     takes_value: TakesValue::Forbidden };
-pub static TIME:       Arg = Arg { short: Some(b't'), long: "time",       takes_value: TakesValue::Necessary };
+pub static TIME:       Arg = Arg { short: Some(b't'), long: "time",       takes_value: TakesValue::List(Some(TIMES)) };
 pub static ACCESSED:   Arg = Arg { short: Some(b'u'), long: "accessed",   takes_value: TakesValue::Forbidden };
 pub static CREATED:    Arg = Arg { short: Some(b'U'), long: "created",    takes_value: TakesValue::Forbidden };
-pub static TIME_STYLE: Arg = Arg { short: None,       long: "time-style", takes_value: TakesValue::Necessary };
+pub static TIME_STYLE: Arg = Arg { short: None,       long: "time-style", takes_value: TakesValue::Necessary(Some(TIME_STYLES)) };
 
 // optional feature options
-pub static GIT:       Arg = Arg { short: None,       long: "git",      takes_value: TakesValue::Forbidden };
-pub static EXTENDED:  Arg = Arg { short: Some(b'@'), long: "extended", takes_value: TakesValue::Forbidden };
+pub static GIT:          Arg = Arg { short: None,       long: "git",          takes_value: TakesValue::Forbidden };
+pub static GIT_ONLY:     Arg = Arg { short: None,       long: "git-only",     takes_value: TakesValue::List(None) };
+pub static GIT_EXCLUDE:  Arg = Arg { short: None,       long: "git-exclude",  takes_value: TakesValue::List(None) };
+pub static EXTENDED:     Arg = Arg { short: Some(b'@'), long: "extended",     takes_value: TakesValue::Forbidden };
+pub static XATTR_COLUMN: Arg = Arg { short: None,       long: "xattr-column", takes_value: TakesValue::Necessary(None) };
 
 
 pub static ALL_ARGS: Args = Args(&[
-    &VERSION, &HELP,
+    &VERSION, &HELP, &THREADS, &STDIN, &QUIET, &DETERMINISTIC, &NO_CONFIG, &BENCH,
 
-    &ONE_LINE, &LONG, &GRID, &ACROSS, &RECURSE, &TREE, &CLASSIFY,
-    &COLOR, &COLOUR, &COLOR_SCALE, &COLOUR_SCALE,
+    &ONE_LINE, &LONG, &GRID, &ACROSS, &RECURSE, &TREE, &CLASSIFY, &PORCELAIN, &SNAPSHOT,
+    &COLOR, &COLOUR, &COLOR_SCALE, &COLOUR_SCALE, &COLOR_DEPTH, &COLOUR_DEPTH, &THEME, &HIGHLIGHT_OWNERS, &PLAIN_COLUMNS,
 
-    &ALL, &LIST_DIRS, &LEVEL, &REVERSE, &SORT, &IGNORE_GLOB, &DIRS_FIRST,
+    &ALL, &ALMOST_ALL, &DOTFILES, &LIST_DIRS, &LEVEL, &MIN_DEPTH, &REVERSE, &SORT, &IGNORE_GLOB, &PRUNE_GLOB, &DIRS_FIRST,
+    &REGEX, &REGEX_EXCLUDE, &SIZE_FILTER,
+    &NEWER_THAN, &OLDER_THAN, &CHANGED_WITHIN, &OWNER, &OWNER_GROUP,
+    &ONLY_DIRS, &ONLY_FILES, &ONLY_LINKS, &ONLY_TYPES, &PERM, &XATTR_FILTER, &CATEGORY,
+    &EMPTY, &NON_EMPTY, &DOT_IGNORE,
+    &DEDUPE, &NORMALIZE, &MAX_RESULTS, &SINCE,
 
     &BINARY, &BYTES, &GROUP, &HEADER, &INODE, &LINKS, &MODIFIED, &BLOCKS,
     &TIME, &ACCESSED, &CREATED, &TIME_STYLE,
 
-    &GIT, &EXTENDED,
+    &GIT, &GIT_ONLY, &GIT_EXCLUDE, &EXTENDED, &XATTR_COLUMN,
 ]);
 
@@ -8,6 +8,11 @@ use fs::feature::xattr;
 static OPTIONS: &str = r##"
   -?, --help         show list of command-line options
   -v, --version      show version of exa
+  --threads N        how many threads to read files with (0 for one per CPU)
+  --stdin            also list the paths read from standard input
+  --quiet            don't warn about unreadable files (twice for none at all)
+  --deterministic    list files the same way everywhere, for comparing output
+  --no-config        don't read options from the config file
 
 DISPLAY OPTIONS
   -1, --oneline      display one entry per line
@@ -17,18 +22,67 @@ DISPLAY OPTIONS
   -R, --recurse      recurse into directories
   -T, --tree         recurse into directories as a tree
   -F, --classify     display type indicator by file names
-  --colo[u]r=WHEN    when to use terminal colours (always, auto, never)
-  --colo[u]r-scale   highlight levels of file sizes distinctly
+  --porcelain=FORMAT print one line per file for other programs (fzf, v1)
+  --snapshot FILE    write a manifest of the listed files for --since
+  --colo[u]r[=WHEN]  when to use terminal colours (always, auto, never)
+  --colo[u]r-scale[=WHAT]  colour numbers or ages on a scale (size, age, age-names, blocks, links)
+  --colo[u]r-depth=DEPTH  how many colours the terminal has (truecolor, 256, 16, none)
+  --highlight-owners highlight files owned by someone else, or by root
+  --plain-columns=COLUMNS  columns to show without colours (size, date, name...)
+  --theme NAME       which built-in colour theme to use
 
 FILTERING AND SORTING OPTIONS
   -a, --all                  show hidden and 'dot' files
+  -A, --almost-all           show hidden files, but never '.' and '..'
+  --dotfiles PLACEMENT       where to list dot files (first, last, mixed)
   -d, --list-dirs            list directories like regular files
   -r, --reverse              reverse the sort order
-  -s, --sort SORT_FIELD      which field to sort by:
+  -s, --sort SORT_FIELDS     which fields to sort by (comma-separated):
   --group-directories-first  list directories before other files
   -I, --ignore-glob GLOBS    glob patterns (pipe-separated) of files to ignore
-  Valid sort fields:         name, Name, extension, Extension, size, type,
-                             modified, accessed, created, inode, none
+  --prune-glob GLOBS         glob patterns of directories to skip entirely
+  --regex PATTERN            only list files whose names match this regex
+  --regex-exclude PATTERN    don't list files whose names match this regex
+  --size-filter SIZE         only list files of this size (+10M, -4k, 512)
+  --newer-than TIME          only list files changed after this time
+  --older-than TIME          only list files changed before this time
+  --changed-within TIME      the same as --newer-than (2d, 30min, 2017-01-01)
+  --owner USER               only list files owned by this user (or !user)
+  --owner-group GROUP        only list files owned by this group (or !group;
+                             not --group, which is -g's group column)
+  --only-dirs                only list directories
+  --only-files               only list regular files
+  --only-links               only list symbolic links
+  --only-types TYPES         only list these types of file (dirs, files,
+                             links, sockets, pipes, devices)
+  --perm MODE                only list files with these permission bits
+                             (0755, -4000, /111, u+s, go-w)
+  --xattr-filter ATTR        only list files with this extended attribute
+                             (user.tag, or user.tag=value)
+  --category CATEGORIES      only list these kinds of file (image, video,
+                             audio, document, archive, code, crypto, temp,
+                             compiled)
+  --empty                    only list empty files and directories
+  --non-empty                only list files and directories that aren't empty
+  --dot-ignore               skip files listed in .ignore and .fdignore files
+  --dedupe                   only list the first of several files that are
+                             the same file, through hard links or symlinks
+  --normalize FORM           put file names in this Unicode normalization
+                             form before using them (nfc, nfd)
+  --max-results COUNT        only list this many files in total
+  --since FILE               only list files added or changed since a snapshot"##;
+
+static GIT_FILTER_HELP: &str = r##"  --git-only STATES          only list files with these Git statuses
+  --git-exclude STATES       don't list files with these Git statuses
+  Valid Git statuses:        new, untracked, modified, deleted, renamed,
+                             typechange, ignored, clean"##;
+
+static SORT_FIELDS: &str = r##"
+  Valid sort fields:         name, Name, name-case, name-nocase, name-version,
+                             name-locale, extension, Extension, size, type,
+                             modified, accessed, created, inode, blocks,
+                             links, git, none
+  Valid sort directions:     field:asc, field:desc (such as size:desc)
 "##;
 
 static LONG_OPTIONS: &str = r##"
@@ -40,15 +94,28 @@ LONG VIEW OPTIONS
   -H, --links        list each file's number of hard links
   -i, --inode        list each file's inode number
   -L, --level DEPTH  limit the depth of recursion
+  --min-depth DEPTH  only list files at least this deep when recursing
   -m, --modified     use the modified timestamp field
   -S, --blocks       show number of file system blocks
-  -t, --time FIELD   which timestamp field to list (modified, accessed, created)
+  -t, --time FIELDS  which timestamp fields to list (modified, accessed, created)
   -u, --accessed     use the accessed timestamp field
   -U, --created      use the created timestamp field
   --time-style       how to format timestamps (default, iso, long-iso, full-iso)"##;
 
 static GIT_HELP:      &str = r##"  --git              list each file's Git status, if tracked"##;
-static EXTENDED_HELP: &str = r##"  -@, --extended     list each file's extended attributes and sizes"##;
+static EXTENDED_HELP: &str = r##"  -@, --extended     list each file's extended attributes and sizes
+  --xattr-column ATTR
+                     list each file's value for this extended attribute"##;
+
+static EXIT_STATUSES: &str = r##"
+EXIT STATUS
+  0    everything was listed
+  1    some files couldn't be read, but the rest were listed
+  2    the options were wrong
+  3    files were being filtered, and none of them matched
+  4    an error stopped the listing part of the way through
+  130  the listing was interrupted with Ctrl-C
+  141  the output was piped into a program that stopped reading it"##;
 
 
 /// All the information needed to display the help text, which depends
@@ -63,7 +130,8 @@ pub struct HelpString {
     /// Whether the --git option should be included in the help.
     git: bool,
 
-    /// Whether the --extended option should be included in the help.
+    /// Whether the --extended and --xattr-column options should be
+    /// included in the help.
     xattrs: bool,
 }
 
@@ -94,6 +162,12 @@ impl fmt::Display for HelpString {
 
         if !self.only_long {
             try!(write!(f, "{}", OPTIONS));
+
+            if self.git {
+                try!(write!(f, "\n{}", GIT_FILTER_HELP));
+            }
+
+            try!(write!(f, "{}", SORT_FIELDS));
         }
 
         try!(write!(f, "{}", LONG_OPTIONS));
@@ -106,6 +180,10 @@ impl fmt::Display for HelpString {
             try!(write!(f, "\n{}", EXTENDED_HELP));
         }
 
+        if !self.only_long {
+            try!(write!(f, "\n{}", EXIT_STATUSES));
+        }
+
         Ok(())
     }
 }
@@ -0,0 +1,180 @@
+//! Translating the flags of GNU and BSD `ls` that exa doesn’t have, or that
+//! mean something else in exa, into exa’s own, so exa can stand in for `ls`
+//! in scripts and aliases.
+//!
+//! This happens to the raw arguments, before they get parsed: `-t` takes a
+//! value in exa but not in `ls`, so the parser would otherwise swallow the
+//! argument after it.
+
+use std::env::var_os;
+use std::ffi::OsString;
+use std::os::unix::ffi::{OsStrExt, OsStringExt};
+
+use options::flags;
+use options::parser::{Arg, TakesValue, LAYER_END};
+
+
+/// Translates the `ls` flags in the given arguments, if the
+/// `EXA_LS_COMPAT` environment variable is set to anything. Otherwise,
+/// they get returned as they are.
+pub fn translate_ls_flags(args: Vec<OsString>) -> Vec<OsString> {
+    match var_os("EXA_LS_COMPAT") {
+        Some(ref v) if !v.is_empty()  => translate(args),
+        _                             => args,
+    }
+}
+
+/// Translates these `ls` flags:
+///
+/// - `-t` and `-S` sort by time and by size, newest or largest first;
+/// - `-c` uses the changed time, which exa calls created, like `-U`;
+/// - `-p` marks directories, which exa does along with other types, as `-F`;
+/// - `--full-time` is the long view with full ISO timestamps.
+///
+/// `-A` and `-u` are left alone, as exa’s mean the same as `ls`’s.
+fn translate(args: Vec<OsString>) -> Vec<OsString> {
+    let time = sort_time(&args);
+    let mut translated = Vec::new();
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        let bytes = arg.as_bytes().to_vec();
+
+        // Nothing after a “--” gets translated, until the end of the
+        // layer it’s in.
+        if bytes == b"--" {
+            translated.push(arg);
+            while let Some(arg) = args.next() {
+                let end = arg == LAYER_END;
+                translated.push(arg);
+
+                if end {
+                    break;
+                }
+            }
+        }
+        else if bytes == b"--full-time" {
+            translated.push(OsString::from("--long"));
+            translated.push(OsString::from("--time-style=full-iso"));
+        }
+        else if bytes.starts_with(b"--") {
+            let takes_value = !bytes.contains(&b'=') && takes_value(|a| a.long.as_bytes() == &bytes[2..]);
+            translated.push(arg);
+
+            if takes_value {
+                translated.extend(args.next());
+            }
+        }
+        else if bytes.starts_with(b"-") && bytes.len() > 1 {
+            let mut shorts = vec![ b'-' ];
+            let mut needs_value = false;
+
+            for (i, &short) in bytes.iter().enumerate().skip(1) {
+                match short {
+                    b't'  => translated.push(OsString::from(format!("--sort={}:desc", time))),
+                    b'S'  => translated.push(OsString::from("--sort=size:desc")),
+                    b'c'  => shorts.push(b'U'),
+                    b'p'  => shorts.push(b'F'),
+                    _     => {
+                        shorts.push(short);
+
+                        // The rest of the argument is this flag’s value,
+                        // or if there is no rest, the next argument is.
+                        if takes_value(|a| a.short == Some(short)) {
+                            shorts.extend_from_slice(&bytes[i + 1 ..]);
+                            needs_value = i + 1 == bytes.len();
+                            break;
+                        }
+                    },
+                }
+            }
+
+            if shorts.len() > 1 {
+                translated.push(OsString::from_vec(shorts));
+            }
+
+            if needs_value {
+                translated.extend(args.next());
+            }
+        }
+        else {
+            translated.push(arg);
+        }
+    }
+
+    translated
+}
+
+/// Which timestamp `-t` sorts by: the changed time after `-c`, the
+/// accessed time after `-u`, and otherwise the modified time. The last of
+/// them to be given wins, as in `ls`.
+fn sort_time(args: &[OsString]) -> &'static str {
+    let mut time = "modified";
+
+    for arg in args {
+        let bytes = arg.as_bytes();
+
+        if bytes == b"--" {
+            break;
+        }
+        else if bytes.starts_with(b"-") && !bytes.starts_with(b"--") {
+            for &short in &bytes[1..] {
+                match short {
+                    b'c'  => time = "created",
+                    b'u'  => time = "accessed",
+                    _ if takes_value(|a| a.short == Some(short))  => break,
+                    _     => {},
+                }
+            }
+        }
+    }
+
+    time
+}
+
+/// Whether the exa flag that matches the given predicate has to be
+/// followed by a value, which can’t be translated. exa’s `-t` isn’t
+/// counted, as it means something else here.
+fn takes_value<F>(predicate: F) -> bool
+where F: Fn(&Arg) -> bool {
+    flags::ALL_ARGS.0.iter()
+        .filter(|arg| arg.short != Some(b't'))
+        .any(|arg| predicate(arg) && match arg.takes_value {
+            TakesValue::Necessary(_) | TakesValue::List(_) | TakesValue::SuffixedList(..)  => true,
+            _                                                                              => false,
+        })
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    macro_rules! test {
+        ($name:ident: $inputs:expr => $results:expr) => {
+            #[test]
+            fn $name() {
+                let inputs = $inputs.iter().map(OsString::from).collect();
+                let results: Vec<OsString> = $results.iter().map(OsString::from).collect();
+                assert_eq!(translate(inputs), results);
+            }
+        };
+    }
+
+    test!(nothing:      [ "-l", "src" ]                  => [ "-l", "src" ]);
+    test!(time:         [ "-lt" ]                        => [ "--sort=modified:desc", "-l" ]);
+    test!(just_time:    [ "-t", "src" ]                  => [ "--sort=modified:desc", "src" ]);
+    test!(size:         [ "-S" ]                         => [ "--sort=size:desc" ]);
+    test!(accessed:     [ "-ltu" ]                       => [ "--sort=accessed:desc", "-lu" ]);
+    test!(changed:      [ "-c", "-lt" ]                  => [ "-U", "--sort=created:desc", "-l" ]);
+    test!(last_time:    [ "-cu", "-t" ]                  => [ "-Uu", "--sort=accessed:desc" ]);
+    test!(slashes:      [ "-1p" ]                        => [ "-1F" ]);
+    test!(full_time:    [ "--full-time" ]                => [ "--long", "--time-style=full-iso" ]);
+    test!(almost_all:   [ "-A" ]                         => [ "-A" ]);
+    test!(short_value:  [ "-L", "-t", "-I-p" ]           => [ "-L", "-t", "-I-p" ]);
+    test!(long_value:   [ "--ignore-glob", "-t" ]        => [ "--ignore-glob", "-t" ]);
+    test!(equals:       [ "--ignore-glob=x", "-t" ]      => [ "--ignore-glob=x", "--sort=modified:desc" ]);
+    test!(dashes:       [ "--", "-t" ]                   => [ "--", "-t" ]);
+    test!(dashes_layer: [ "--", "-t", LAYER_END, "-t" ]  => [ "--", "-t", LAYER_END, "--sort=modified:desc" ]);
+    test!(stdin:        [ "-" ]                          => [ "-" ]);
+}
@@ -1,11 +1,13 @@
 use std::ffi::{OsStr, OsString};
 use std::fmt;
 use std::num::ParseIntError;
+use std::path::PathBuf;
 
 use glob;
+use regex;
 
 use options::{HelpString, VersionString};
-use options::parser::{Arg, ParseError};
+use options::parser::{Arg, Flag, ParseError};
 
 
 /// A list of legal choices for an argument-taking option
@@ -39,6 +41,9 @@ pub enum Misfire {
     /// Two options were given that conflict with one another.
     Conflict(&'static Arg, &'static Arg),
 
+    /// An option was given twice with different values, in strict mode.
+    Duplicate(Flag, Flag),
+
     /// An option was given that does nothing when another one either is or
     /// isn't present.
     Useless(&'static Arg, bool, &'static Arg),
@@ -55,6 +60,27 @@ pub enum Misfire {
 
     /// A glob ignore was given that failed to be parsed as a pattern.
     FailedGlobPattern(String),
+
+    /// A regular expression was given that failed to be parsed.
+    FailedRegex(String),
+
+    /// An option was given a value that couldn’t be understood, along with
+    /// a description of what was expected instead.
+    BadValue(&'static Arg, OsString, &'static str),
+
+    /// The theme file couldn’t be read, or had something wrong with it.
+    BadTheme(PathBuf, String),
+
+    /// The manifest given to `--since` couldn’t be read, or wasn’t a
+    /// manifest.
+    BadManifest(PathBuf, String),
+
+    /// The `EXA_OPTS` environment variable couldn’t be split into
+    /// arguments.
+    BadEnvOptions(String),
+
+    /// The config file couldn’t be read, or had something wrong with it.
+    BadConfig(PathBuf, String),
 }
 
 impl Misfire {
@@ -83,22 +109,53 @@ impl From<glob::PatternError> for Misfire {
     }
 }
 
+impl From<regex::Error> for Misfire {
+    fn from(error: regex::Error) -> Misfire {
+        Misfire::FailedRegex(error.to_string())
+    }
+}
+
 impl fmt::Display for Misfire {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         use self::Misfire::*;
 
         match *self {
-            BadArgument(ref a, ref b, ref c) => write!(f, "Option {} has no value {:?} (Choices: {})", a, b, c),
+            BadArgument(ref a, ref b, ref c) => write!(f, "Option {} has no value {:?} {}", a, b, c),
+            InvalidOptions(ParseError::UnknownArgument { ref attempt, ref suggestions }) => {
+                write!(f, "Unknown argument --{}", attempt.to_string_lossy())?;
+
+                if !suggestions.is_empty() {
+                    let names: Vec<String> = suggestions.iter().map(|long| format!("--{}", long)).collect();
+                    write!(f, " (did you mean {}?)", names.join(" or "))?;
+                }
+
+                Ok(())
+            },
+            InvalidOptions(ParseError::AmbiguousArgument { ref attempt, ref candidates }) => {
+                let names: Vec<String> = candidates.iter().map(|long| format!("--{}", long)).collect();
+                write!(f, "Ambiguous argument --{} (could be {})", attempt.to_string_lossy(), names.join(", "))
+            },
+            InvalidOptions(ParseError::BadValue { ref flag, ref value, values }) => {
+                write!(f, "Flag {} has no value {:?} {}", flag, value, Choices(values))
+            },
             InvalidOptions(ref e)            => write!(f, "{:?}", e),
             Help(ref text)                   => write!(f, "{}", text),
             Version(ref version)             => write!(f, "{}", version),
             Conflict(ref a, ref b)           => write!(f, "Option {} conflicts with option {}.", a, b),
+            Duplicate(ref a, ref b) if a == b => write!(f, "Flag {} was given twice with different values.", a),
+            Duplicate(ref a, ref b)          => write!(f, "Flag {} conflicts with flag {}.", a, b),
             Useless(ref a, false, ref b)     => write!(f, "Option {} is useless without option {}.", a, b),
             Useless(ref a, true, ref b)      => write!(f, "Option {} is useless given option {}.", a, b),
             Useless2(ref a, ref b1, ref b2)  => write!(f, "Option {} is useless without options {} or {}.", a, b1, b2),
             TreeAllAll                       => write!(f, "Option --tree is useless given --all --all."),
             FailedParse(ref e)               => write!(f, "Failed to parse number: {}", e),
             FailedGlobPattern(ref e)         => write!(f, "Failed to parse glob pattern: {}", e),
+            FailedRegex(ref e)               => write!(f, "Failed to parse regex: {}", e),
+            BadValue(ref a, ref b, ref c)    => write!(f, "Option {} has an invalid value {:?} (expected {})", a, b, c),
+            BadTheme(ref path, ref e)        => write!(f, "Failed to load theme {}: {}", path.display(), e),
+            BadManifest(ref path, ref e)     => write!(f, "Failed to load manifest {}: {}", path.display(), e),
+            BadEnvOptions(ref e)             => write!(f, "Failed to read EXA_OPTS: {}", e),
+            BadConfig(ref path, ref e)       => write!(f, "Failed to load config {}: {}", path.display(), e),
         }
     }
 }
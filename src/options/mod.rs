@@ -69,15 +69,22 @@
 //! it’s clear what the user wants.
 
 
+use std::env::var_os;
 use std::ffi::{OsStr, OsString};
+use std::path::PathBuf;
+
+use num_cpus;
 
 use fs::dir_action::DirAction;
 use fs::filter::FileFilter;
+use fs::spill;
 use output::{View, Mode};
 use output::details;
 
 mod dir_action;
 mod filter;
+mod terminfo;
+mod theme;
 mod view;
 
 mod help;
@@ -91,6 +98,19 @@ pub use self::misfire::Misfire;
 
 mod parser;
 mod flags;
+mod suggest;
+
+mod ls_flags;
+pub use self::ls_flags::translate_ls_flags;
+
+mod env_options;
+pub use self::env_options::add_env_options;
+
+mod config;
+pub use self::config::add_config_options;
+
+mod path_args;
+pub use self::path_args::PathArg;
 use self::parser::MatchedFlags;
 
 
@@ -108,17 +128,59 @@ pub struct Options {
 
     /// The type of output to use (lines, grid, or details).
     pub view: View,
+
+    /// How many threads to use when reading files’ metadata and building
+    /// the rows of a table.
+    pub threads: usize,
+
+    /// How many entries a directory can have before sorting its files’
+    /// names spills them to temporary files, rather than keeping them all
+    /// in memory.
+    pub spill_threshold: usize,
+
+    /// Whether to read the paths of the files to list from standard input,
+    /// as well as taking them from the arguments.
+    pub stdin: bool,
+
+    /// Which warnings about files that couldn’t be read to print.
+    pub warnings: Warnings,
+
+    /// Whether to leave out everything that depends on the terminal, the
+    /// locale, or the time zone, so the same files always get listed in
+    /// the same way.
+    pub deterministic: bool,
+
+    /// The file to write a manifest of the listed files to, so a later
+    /// listing can show what’s changed since, if there is one.
+    pub snapshot: Option<PathBuf>,
+
+    /// Whether to time each phase of listing the given directories and
+    /// print how long they took, instead of listing them. This is a hidden
+    /// option, used to get numbers for performance reports.
+    pub bench: bool,
 }
 
 impl Options {
 
-    /// Call getopts on the given slice of command-line strings.
+    /// Call getopts on the given slice of command-line strings, returning
+    /// the options along with the free arguments, with any `@file`
+    /// arguments expanded into the paths in their files.
+    ///
+    /// Setting the `EXA_STRICT` environment variable to anything makes
+    /// options that are redundant, or that conflict, into errors, rather
+    /// than using the last one or ignoring them. Only the options on the
+    /// command-line itself get complained about.
     #[allow(unused_results)]
-    pub fn getopts<'args, I>(args: I) -> Result<(Options, Vec<&'args OsStr>), Misfire>
+    pub fn getopts<'args, I>(args: I) -> Result<(Options, Vec<PathArg<'args>>), Misfire>
     where I: IntoIterator<Item=&'args OsString> {
-        use options::parser::Matches;
+        use options::parser::{Matches, Strictness};
 
-        let Matches { flags, frees } = match flags::ALL_ARGS.parse(args) {
+        let strictness = match var_os("EXA_STRICT") {
+            Some(ref s) if !s.is_empty()  => Strictness::ComplainAboutRedundantArguments,
+            _                             => Strictness::UseLastArguments,
+        };
+
+        let Matches { flags, frees } = match flags::ALL_ARGS.parse(args, strictness) {
             Ok(m)   => m,
             Err(e)  => return Err(Misfire::InvalidOptions(e)),
         };
@@ -127,13 +189,18 @@ impl Options {
         VersionString::deduce(&flags).map_err(Misfire::Version)?;
 
         let options = Options::deduce(&flags)?;
-        Ok((options, frees))
+        Ok((options, path_args::expand(frees)))
     }
 
     /// Whether the View specified in this set of options includes a Git
-    /// status column. It’s only worth trying to discover a repository if the
-    /// results will end up being displayed.
+    /// status column, or files are being filtered by their Git statuses.
+    /// It’s only worth trying to discover a repository if the results will
+    /// end up being used.
     pub fn should_scan_for_git(&self) -> bool {
+        if self.filter.needs_git() {
+            return true;
+        }
+
         match self.view.mode {
             Mode::Details(details::Options { table: Some(ref table), .. }) |
             Mode::GridDetails(_, details::Options { table: Some(ref table), .. }) => table.should_scan_for_git(),
@@ -147,8 +214,105 @@ impl Options {
         let dir_action = DirAction::deduce(matches)?;
         let filter = FileFilter::deduce(matches)?;
         let view = View::deduce(matches)?;
+        let threads = Options::deduce_threads(matches)?;
+        let spill_threshold = Options::deduce_spill_threshold()?;
+        let stdin = matches.has(&flags::STDIN);
+        let warnings = Warnings::deduce(matches);
+        let deterministic = matches.has(&flags::DETERMINISTIC);
+        let snapshot = Options::deduce_snapshot(matches)?;
+        let bench = matches.has(&flags::BENCH);
+
+        Ok(Options { dir_action, view, filter, threads, spill_threshold, stdin, warnings, deterministic, snapshot, bench })
+    }
+
+    /// Determines which file to write a manifest to, if any.
+    ///
+    /// The tree view lists the files in the directories below the first
+    /// as it draws them, so they can’t get written to a manifest. Nor can a
+    /// listing filtered by another manifest, as only the changed files
+    /// would end up in the new one.
+    fn deduce_snapshot(matches: &MatchedFlags) -> Result<Option<PathBuf>, Misfire> {
+        let path = match matches.get(&flags::SNAPSHOT)? {
+            Some(path)  => PathBuf::from(path),
+            None        => return Ok(None),
+        };
+
+        if matches.has(&flags::TREE) {
+            Err(Misfire::Conflict(&flags::SNAPSHOT, &flags::TREE))
+        }
+        else if matches.get(&flags::SINCE)?.is_some() {
+            Err(Misfire::Conflict(&flags::SNAPSHOT, &flags::SINCE))
+        }
+        else {
+            Ok(Some(path))
+        }
+    }
+
+    /// Determines how many threads to use, from the `--threads` argument or
+    /// the `EXA_THREADS` environment variable, in that order. A count of
+    /// zero, or no count at all, means one thread for each CPU.
+    ///
+    /// Returns an error if the count doesn’t parse to an integer.
+    fn deduce_threads(matches: &MatchedFlags) -> Result<usize, Misfire> {
+        let count = if let Some(count) = matches.get(&flags::THREADS)? {
+            Some(count.to_os_string())
+        }
+        else {
+            var_os("EXA_THREADS")
+        };
+
+        match count.map(|c| c.to_string_lossy().parse()) {
+            Some(Ok(0)) | None  => Ok(num_cpus::get()),
+            Some(Ok(threads))   => Ok(threads),
+            Some(Err(e))        => Err(Misfire::FailedParse(e)),
+        }
+    }
+
+    /// Determines how many entries a directory can have before it gets
+    /// sorted using temporary files, from the `EXA_SORT_SPILL` environment
+    /// variable. Setting it to zero means directories never get spilled.
+    ///
+    /// Returns an error if the count doesn’t parse to an integer.
+    fn deduce_spill_threshold() -> Result<usize, Misfire> {
+        match var_os("EXA_SORT_SPILL").map(|c| c.to_string_lossy().parse()) {
+            None              => Ok(spill::DEFAULT_THRESHOLD),
+            Some(Ok(0))       => Ok(usize::max_value()),
+            Some(Ok(count))   => Ok(count),
+            Some(Err(e))      => Err(Misfire::FailedParse(e)),
+        }
+    }
+}
+
+
+
+/// Which warnings to print when files can’t be read. Files that couldn’t
+/// be read still change the exit status, whether they get warned about or
+/// not.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum Warnings {
+
+    /// Warn about every file that couldn’t be read.
+    Everything,
+
+    /// Only warn about the files given as arguments, not the ones found in
+    /// the directories being listed.
+    ArgumentsOnly,
 
-        Ok(Options { dir_action, view, filter })
+    /// Don’t print any warnings.
+    Nothing,
+}
+
+impl Warnings {
+
+    /// Determines which warnings to print from the number of times
+    /// `--quiet` was given: once hides the ones about files inside
+    /// directories, and twice hides them all.
+    fn deduce(matches: &MatchedFlags) -> Warnings {
+        match matches.count(&flags::QUIET) {
+            0 => Warnings::Everything,
+            1 => Warnings::ArgumentsOnly,
+            _ => Warnings::Nothing,
+        }
     }
 }
 
@@ -156,9 +320,9 @@ impl Options {
 
 #[cfg(test)]
 mod test {
-    use super::{Options, Misfire, flags};
-    use std::ffi::OsString;
-    use fs::filter::{SortField, SortCase};
+    use super::{Options, Misfire, Warnings, PathArg, flags};
+    use std::ffi::{OsStr, OsString};
+    use fs::filter::{SortKey, SortField, SortCase};
 
     /// Creates an `OSStr` (used in tests)
     #[cfg(test)]
@@ -172,7 +336,7 @@ mod test {
     fn files() {
         let args = [ os("this file"), os("that file") ];
         let outs = Options::getopts(&args).unwrap().1;
-        assert_eq!(outs, vec![ &os("this file"), &os("that file") ])
+        assert_eq!(outs, vec![ PathArg::Given(OsStr::new("this file")), PathArg::Given(OsStr::new("that file")) ])
     }
 
     #[test]
@@ -182,6 +346,50 @@ mod test {
         assert!(outs.is_empty());  // Listing the `.` directory is done in main.rs
     }
 
+    #[test]
+    fn bench() {
+        let args = [ os("--bench"), os("target") ];
+        let (opts, outs) = Options::getopts(&args).unwrap();
+        assert!(opts.bench);
+        assert_eq!(outs, vec![ PathArg::Given(OsStr::new("target")) ])
+    }
+
+    #[test]
+    fn threads() {
+        let args = [ os("--threads"), os("3") ];
+        let opts = Options::getopts(&args).unwrap().0;
+        assert_eq!(opts.threads, 3);
+    }
+
+    #[test]
+    fn threads_not_a_number() {
+        let args = [ os("--threads=lots") ];
+        let opts = Options::getopts(&args);
+        assert!(opts.is_err());
+    }
+
+    #[test]
+    fn quiet() {
+        let args = [ os("--quiet") ];
+        let opts = Options::getopts(&args).unwrap().0;
+        assert_eq!(opts.warnings, Warnings::ArgumentsOnly);
+    }
+
+    #[test]
+    fn quieter() {
+        let args = [ os("--quiet"), os("--quiet") ];
+        let opts = Options::getopts(&args).unwrap().0;
+        assert_eq!(opts.warnings, Warnings::Nothing);
+    }
+
+    #[test]
+    fn deterministic() {
+        let args = [ os("--deterministic") ];
+        let opts = Options::getopts(&args).unwrap().0;
+        assert!(opts.deterministic);
+        assert!(opts.filter.ties_by_name);
+    }
+
     #[test]
     fn just_binary() {
         let args = [ os("--binary") ];
@@ -210,6 +418,20 @@ mod test {
         assert_eq!(opts.unwrap_err(), Misfire::Useless(&flags::ACROSS, true, &flags::ONE_LINE))
     }
 
+    #[test]
+    fn porcelain_long() {
+        let args = [ os("--porcelain=fzf"), os("--long") ];
+        let opts = Options::getopts(&args);
+        assert_eq!(opts.unwrap_err(), Misfire::Conflict(&flags::PORCELAIN, &flags::LONG))
+    }
+
+    #[test]
+    fn snapshot_tree() {
+        let args = [ os("--snapshot=manifest"), os("--tree") ];
+        let opts = Options::getopts(&args);
+        assert_eq!(opts.unwrap_err(), Misfire::Conflict(&flags::SNAPSHOT, &flags::TREE))
+    }
+
     #[test]
     fn just_header() {
         let args = [ os("--header") ];
@@ -217,6 +439,13 @@ mod test {
         assert_eq!(opts.unwrap_err(), Misfire::Useless(&flags::HEADER, false, &flags::LONG))
     }
 
+    #[test]
+    fn just_xattr_column() {
+        let args = [ os("--xattr-column"), os("user.tag") ];
+        let opts = Options::getopts(&args);
+        assert_eq!(opts.unwrap_err(), Misfire::Useless(&flags::XATTR_COLUMN, false, &flags::LONG))
+    }
+
     #[test]
     fn just_group() {
         let args = [ os("--group") ];
@@ -249,21 +478,28 @@ mod test {
     fn test_sort_size() {
         let args = [ os("--sort=size") ];
         let opts = Options::getopts(&args);
-        assert_eq!(opts.unwrap().0.filter.sort_field, SortField::Size);
+        assert_eq!(opts.unwrap().0.filter.sort_keys, vec![ SortKey::from(SortField::Size) ]);
     }
 
     #[test]
     fn test_sort_name() {
         let args = [ os("--sort=name") ];
         let opts = Options::getopts(&args);
-        assert_eq!(opts.unwrap().0.filter.sort_field, SortField::Name(SortCase::Sensitive));
+        assert_eq!(opts.unwrap().0.filter.sort_keys, vec![ SortKey::from(SortField::Name(SortCase::Sensitive)) ]);
     }
 
     #[test]
     fn test_sort_name_lowercase() {
         let args = [ os("--sort=Name") ];
         let opts = Options::getopts(&args);
-        assert_eq!(opts.unwrap().0.filter.sort_field, SortField::Name(SortCase::Insensitive));
+        assert_eq!(opts.unwrap().0.filter.sort_keys, vec![ SortKey::from(SortField::Name(SortCase::Insensitive)) ]);
+    }
+
+    #[test]
+    fn test_sort_multiple() {
+        let args = [ os("--sort=size,name"), os("--sort=inode") ];
+        let opts = Options::getopts(&args);
+        assert_eq!(opts.unwrap().0.filter.sort_keys, vec![ SortKey::from(SortField::Size), SortKey::from(SortField::Name(SortCase::Sensitive)), SortKey::from(SortField::FileInode) ]);
     }
 
     #[test]
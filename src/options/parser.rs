@@ -7,10 +7,28 @@
 //! - Long options with values: `--sort size`, `--level=4`
 //! - Short options: `-i`, `-G`
 //! - Short options with values: `-ssize`, `-L=4`
+//! - Negated long options: `--no-git`, `--no-header`
+//! - Unambiguous prefixes of long options: `--rev` for `--reverse`
 //!
 //! These values can be mixed and matched: `exa -lssize --grid`. If you’ve used
 //! other command-line programs, then hopefully it’ll work much like them.
 //!
+//! Every long option can be negated by putting `no-` in front of its name.
+//! This doesn’t get stored as a flag of its own: it removes every earlier
+//! occurrence of the option, long or short, along with any values given to
+//! it, so the options parsed after it act as though it had never been given
+//! and it goes back to its default. This lets an alias turn an option on,
+//! and the command it’s used in turn it back off again. Both spellings of
+//! the colour options count as the same option, so `--no-colour` also
+//! undoes an earlier `--color`.
+//!
+//! Options can also come from the config file and the `EXA_OPTS`
+//! environment variable, which go before the ones on the command-line.
+//! Each set of arguments is a **layer**, ended by `LAYER_END`, and an
+//! option given in one layer replaces every use of it in the layers
+//! before, so options that take lists get their lists replaced rather
+//! than added to.
+//!
 //! Because exa already has its own files for the help text, shell completions,
 //! man page, and readme, so it can get away with having the options parser do
 //! very little: all it really needs to do is parse a slice of strings.
@@ -31,6 +49,9 @@
 use std::ffi::{OsStr, OsString};
 use std::fmt;
 
+use options::Misfire;
+use options::suggest::suggestions;
+
 
 /// A **short argument** is a single ASCII character.
 pub type ShortArg = u8;
@@ -50,7 +71,7 @@ pub enum Flag {
 }
 
 impl Flag {
-    fn matches(&self, arg: &Arg) -> bool {
+    pub fn matches(&self, arg: &Arg) -> bool {
         match *self {
             Flag::Short(short)  => arg.short == Some(short),
             Flag::Long(long)    => arg.long == long,
@@ -58,10 +79,18 @@ impl Flag {
     }
 }
 
+impl fmt::Display for Flag {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Flag::Short(short)  => write!(f, "-{}", short as char),
+            Flag::Long(long)    => write!(f, "--{}", long),
+        }
+    }
+}
+
 
 /// Whether redundant arguments should be considered a problem.
-#[derive(PartialEq, Debug)]
-#[allow(dead_code)] // until strict mode is actually implemented
+#[derive(PartialEq, Debug, Copy, Clone)]
 pub enum Strictness {
 
     /// Throw an error when an argument doesn’t do anything, either because
@@ -73,16 +102,59 @@ pub enum Strictness {
     UseLastArguments,
 }
 
+/// The values that a flag can be given, for flags that only accept a fixed
+/// set of them.
+pub type Values = &'static [&'static str];
+
 /// Whether a flag takes a value. This is applicable to both long and short
 /// arguments.
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub enum TakesValue {
 
-    /// This flag has to be followed by a value.
-    Necessary,
+    /// This flag has to be followed by a value. If a list of values is
+    /// given, the value has to be one of them.
+    Necessary(Option<Values>),
+
+    /// This flag has to be followed by a comma-separated list of values,
+    /// such as `--sort=size,name`, each of which gets matched as though the
+    /// flag had been given once for each. Flags whose values can contain
+    /// commas of their own shouldn’t take this.
+    List(Option<Values>),
+
+    /// This flag takes a list in the same way, where each value has to be
+    /// one of the first list given, optionally followed by a colon and one
+    /// of the second, such as `--sort=size:desc,name`.
+    SuffixedList(Values, Values),
 
     /// This flag will throw an error if there’s a value after it.
     Forbidden,
+
+    /// This flag can have a value, but only if it’s given straight after
+    /// an equals sign, as in `--scale=age`. On its own, it has the default
+    /// value given second, or no value at all if there isn’t one. Any
+    /// value has to be one of the list given first, if there is one.
+    Optional(Option<Values>, Option<&'static str>),
+
+    /// This flag can have a value in the same way, with no default, where
+    /// the value is a comma-separated list of the values given, such as
+    /// `--scale=age,size`. Unlike with `List`, the whole list is kept as
+    /// one value.
+    OptionalList(Values),
+}
+
+impl TakesValue {
+
+    /// The values that this flag accepts, if they’re limited to a list.
+    pub fn values(&self) -> Option<Values> {
+        match *self {
+            TakesValue::Necessary(values)        => values,
+            TakesValue::List(values)             => values,
+            TakesValue::SuffixedList(values, _)  => Some(values),
+            TakesValue::Optional(values, _)      => values,
+            TakesValue::OptionalList(values)     => Some(values),
+            TakesValue::Forbidden                => None,
+        }
+    }
 }
 
 
@@ -101,6 +173,31 @@ pub struct Arg {
     pub takes_value: TakesValue,
 }
 
+impl Arg {
+
+    /// Whether this argument is the same as the given one under another
+    /// name. The only arguments with two names are the colour ones, which
+    /// exa accepts with either spelling of “colour”.
+    pub fn is_alias_of(&self, other: &Arg) -> bool {
+        self.long.replace("colour", "color") == other.long.replace("colour", "color")
+    }
+
+    /// Whether this argument is left out of the help text. Hidden arguments
+    /// only match when their whole name is given, and never get suggested,
+    /// so they can’t be stumbled upon. The only one is `--bench`, which is
+    /// there for exa’s own developers.
+    pub fn is_hidden(&self) -> bool {
+        self.long == "bench"
+    }
+
+    /// Whether this argument can be given as the start of its name.
+    /// Names that start with `no-` can’t, as that’s where the negations
+    /// of the other arguments are.
+    fn takes_prefixes(&self) -> bool {
+        !self.is_hidden() && !self.long.starts_with("no-")
+    }
+}
+
 impl fmt::Display for Arg {
     fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
         write!(f, "--{}", self.long)?;
@@ -114,6 +211,12 @@ impl fmt::Display for Arg {
 }
 
 
+/// The argument that goes between one layer of arguments and the next,
+/// such as between the ones from `EXA_OPTS` and the command-line. Nobody
+/// can type it by accident, as arguments can’t contain NUL bytes.
+pub static LAYER_END: &str = "\0";
+
+
 /// Literally just several args.
 #[derive(PartialEq, Debug)]
 pub struct Args(pub &'static [&'static Arg]);
@@ -121,8 +224,9 @@ pub struct Args(pub &'static [&'static Arg]);
 impl Args {
 
     /// Iterates over the given list of command-line arguments and parses
-    /// them into a list of matched flags and free strings.
-    pub fn parse<'args, I>(&self, inputs: I) -> Result<Matches<'args>, ParseError>
+    /// them into a list of matched flags and free strings. The strictness
+    /// gets kept with the flags, for when their values are looked up.
+    pub fn parse<'args, I>(&self, inputs: I, strictness: Strictness) -> Result<Matches<'args>, ParseError>
     where I: IntoIterator<Item=&'args OsString> {
         use std::os::unix::ffi::OsStrExt;
         use self::TakesValue::*;
@@ -133,6 +237,9 @@ impl Args {
         let mut result_flags = Vec::new();
         let mut frees: Vec<&OsStr> = Vec::new();
 
+        // Where the flags from the current layer of arguments start.
+        let mut layer_start = 0;
+
         // Iterate over the inputs with “while let” because we need to advance
         // the iterator manually whenever an argument that takes a value
         // doesn’t have one in its string so it needs the next one.
@@ -140,11 +247,19 @@ impl Args {
         while let Some(arg) = inputs.next() {
             let bytes = arg.as_bytes();
 
+            // The end of a layer replaces earlier uses of the options in
+            // it, and starts parsing again if it was stopped.
+            if arg == LAYER_END {
+                self.replace_earlier(&mut result_flags, layer_start);
+                layer_start = result_flags.len();
+                parsing = true;
+            }
+
             // Stop parsing if one of the arguments is the literal string “--”.
             // This allows a file named “--arg” to be specified by passing in
             // the pair “-- --arg”, without it getting matched as a flag that
             // doesn’t exist.
-            if !parsing {
+            else if !parsing {
                 frees.push(arg)
             }
             else if arg == "--" {
@@ -162,24 +277,36 @@ impl Args {
                     let arg = self.lookup_long(before)?;
                     let flag = Flag::Long(arg.long);
                     match arg.takes_value {
-                        Necessary  => result_flags.push((flag, Some(after))),
-                        Forbidden  => return Err(ParseError::ForbiddenValue { flag })
+                        Forbidden  => return Err(ParseError::ForbiddenValue { flag }),
+                        _          => push_value(&mut result_flags, flag, arg.takes_value, after),
                     }
                 }
 
                 // If there’s no equals, then the entire string (apart from
                 // the dashes) is the argument name.
                 else {
-                    let arg = self.lookup_long(long_arg_name)?;
+                    let arg = match self.lookup_long(long_arg_name) {
+                        Ok(arg)  => arg,
+                        Err(e)   => match self.lookup_negation(long_arg_name) {
+                            Some(arg)  => {
+                                // The flags removed from earlier layers move
+                                // the start of this one back.
+                                layer_start -= result_flags[.. layer_start].iter().filter(|tuple| self.is_for(&tuple.0, arg)).count();
+                                result_flags.retain(|tuple| !self.is_for(&tuple.0, arg));
+                                continue;
+                            },
+                            None       => return Err(e),
+                        },
+                    };
+
                     let flag = Flag::Long(arg.long);
                     match arg.takes_value {
-                        Forbidden  => result_flags.push((flag, None)),
-                        Necessary  => {
-                            if let Some(next_arg) = inputs.next() {
-                                result_flags.push((flag, Some(next_arg)));
-                            }
-                            else {
-                                return Err(ParseError::NeedsValue { flag })
+                        Forbidden | OptionalList(_)                 => result_flags.push((flag, None)),
+                        Optional(_, default)                        => result_flags.push((flag, default.map(OsStr::new))),
+                        Necessary(_) | List(_) | SuffixedList(..)   => {
+                            match inputs.next() {
+                                Some(next_arg) if next_arg != LAYER_END  => push_value(&mut result_flags, flag, arg.takes_value, next_arg),
+                                _                                        => return Err(ParseError::NeedsValue { flag }),
                             }
                         }
                     }
@@ -211,8 +338,9 @@ impl Args {
                         let arg = self.lookup_short(*byte)?;
                         let flag = Flag::Short(*byte);
                         match arg.takes_value {
-                            Forbidden  => result_flags.push((flag, None)),
-                            Necessary  => return Err(ParseError::NeedsValue { flag })
+                            Forbidden | OptionalList(_)                 => result_flags.push((flag, None)),
+                            Optional(_, default)                        => result_flags.push((flag, default.map(OsStr::new))),
+                            Necessary(_) | List(_) | SuffixedList(..)   => return Err(ParseError::NeedsValue { flag })
                         }
                     }
 
@@ -220,8 +348,8 @@ impl Args {
                     let arg = self.lookup_short(*arg_with_value)?;
                     let flag = Flag::Short(arg.short.unwrap());
                     match arg.takes_value {
-                        Necessary  => result_flags.push((flag, Some(after))),
-                        Forbidden  => return Err(ParseError::ForbiddenValue { flag })
+                        Forbidden  => return Err(ParseError::ForbiddenValue { flag }),
+                        _          => push_value(&mut result_flags, flag, arg.takes_value, after),
                     }
                 }
 
@@ -242,18 +370,19 @@ impl Args {
                         let arg = self.lookup_short(*byte)?;
                         let flag = Flag::Short(*byte);
                         match arg.takes_value {
-                            Forbidden  => result_flags.push((flag, None)),
-                            Necessary  => {
+                            Forbidden | OptionalList(_)                 => result_flags.push((flag, None)),
+                            Optional(_, default)                        => result_flags.push((flag, default.map(OsStr::new))),
+                            Necessary(_) | List(_) | SuffixedList(..)   => {
                                 if index < bytes.len() - 1 {
                                     let remnants = &bytes[index+1 ..];
-                                    result_flags.push((flag, Some(OsStr::from_bytes(remnants))));
+                                    push_value(&mut result_flags, flag, arg.takes_value, OsStr::from_bytes(remnants));
                                     break;
                                 }
-                                else if let Some(next_arg) = inputs.next() {
-                                    result_flags.push((flag, Some(next_arg)));
-                                }
                                 else {
-                                    return Err(ParseError::NeedsValue { flag })
+                                    match inputs.next() {
+                                        Some(next_arg) if next_arg != LAYER_END  => push_value(&mut result_flags, flag, arg.takes_value, next_arg),
+                                        _                                        => return Err(ParseError::NeedsValue { flag }),
+                                    }
                                 }
                             }
                         }
@@ -267,7 +396,55 @@ impl Args {
             }
         }
 
-        Ok(Matches { frees, flags: MatchedFlags { flags: result_flags } })
+        let last_layer = result_flags.len() - layer_start;
+        self.replace_earlier(&mut result_flags, layer_start);
+        let command_line = result_flags.len() - last_layer;
+
+        self.check_values(&result_flags)?;
+        Ok(Matches { frees, flags: MatchedFlags { flags: result_flags, strictness, command_line } })
+    }
+
+    /// Checks that every flag given a value from a limited list was given
+    /// one of them, returning an error for the first one that wasn’t.
+    /// Suffixes get checked against their own list, and each value in an
+    /// optional list gets checked on its own.
+    fn check_values(&self, flags: &[(Flag, Option<&OsStr>)]) -> Result<(), ParseError> {
+        use std::os::unix::ffi::OsStrExt;
+
+        for &(ref flag, value) in flags {
+            let value = match value {
+                Some(v)  => v,
+                None     => continue,
+            };
+
+            let takes_value = match self.0.into_iter().find(|arg| flag.matches(arg)) {
+                Some(arg)  => arg.takes_value,
+                None       => continue,
+            };
+
+            match takes_value {
+                TakesValue::SuffixedList(values, suffixes) => {
+                    let mut parts = value.as_bytes().splitn(2, |&b| b == b':');
+                    check_value(flag, OsStr::from_bytes(parts.next().unwrap_or(b"")), values)?;
+
+                    if let Some(suffix) = parts.next() {
+                        check_value(flag, OsStr::from_bytes(suffix), suffixes)?;
+                    }
+                },
+                TakesValue::OptionalList(values) => {
+                    for part in value.as_bytes().split(|&b| b == b',') {
+                        check_value(flag, OsStr::from_bytes(part), values)?;
+                    }
+                },
+                other => {
+                    if let Some(values) = other.values() {
+                        check_value(flag, value, values)?;
+                    }
+                },
+            }
+        }
+
+        Ok(())
     }
 
     fn lookup_short<'a>(&self, short: ShortArg) -> Result<&Arg, ParseError> {
@@ -277,11 +454,72 @@ impl Args {
         }
     }
 
+    /// Looks up a long argument by its name, or by the start of its name
+    /// if that’s the start of only one argument’s name. An exact match
+    /// always wins, so one argument’s name can be the start of another’s.
+    /// Aliases count as one argument, with the first one defined standing
+    /// in for the rest, and hidden arguments don’t count at all. Nor do
+    /// arguments whose names start with `no-`, so the start of a negation
+    /// such as `--no-co` can’t get taken as one of them.
     fn lookup_long<'a>(&self, long: &'a OsStr) -> Result<&Arg, ParseError> {
-        match self.0.into_iter().find(|arg| arg.long == long) {
-            Some(arg)  => Ok(arg),
-            None       => Err(ParseError::UnknownArgument { attempt: long.to_os_string() })
+        use std::os::unix::ffi::OsStrExt;
+
+        if let Some(arg) = self.0.into_iter().find(|arg| arg.long == long) {
+            return Ok(arg);
+        }
+
+        let mut candidates: Vec<&Arg> = Vec::new();
+        for arg in self.0.into_iter().filter(|arg| arg.takes_prefixes() && arg.long.as_bytes().starts_with(long.as_bytes())) {
+            if !candidates.iter().any(|c| c.is_alias_of(arg)) {
+                candidates.push(arg);
+            }
         }
+
+        match candidates.len() {
+            0  => Err(ParseError::UnknownArgument { attempt: long.to_os_string(), suggestions: suggestions(self, long) }),
+            1  => Ok(candidates[0]),
+            _  => Err(ParseError::AmbiguousArgument { attempt: long.to_os_string(), candidates: candidates.iter().map(|arg| arg.long).collect() }),
+        }
+    }
+
+    /// Looks up the argument that a long argument beginning with `no-`
+    /// turns off, if there is one. Arguments that take values get turned
+    /// off the same way as those that don’t, which resets them to their
+    /// defaults.
+    fn lookup_negation(&self, long: &OsStr) -> Option<&Arg> {
+        use std::os::unix::ffi::OsStrExt;
+
+        let bytes = long.as_bytes();
+        if !bytes.starts_with(b"no-") {
+            return None;
+        }
+
+        let name = OsStr::from_bytes(&bytes[3..]);
+        self.0.into_iter()
+            .find(|arg| arg.long == name)
+            .cloned()
+    }
+
+    /// Whether the given flag is for the given argument or one of its
+    /// aliases, which negating the argument removes along with it.
+    fn is_for(&self, flag: &Flag, arg: &Arg) -> bool {
+        self.0.into_iter().any(|a| flag.matches(a) && a.is_alias_of(arg))
+    }
+
+    /// Removes the flags before the given index for arguments that get
+    /// given again after it, so the options in one layer of arguments
+    /// replace the ones from the layers before it.
+    fn replace_earlier(&self, flags: &mut Vec<(Flag, Option<&OsStr>)>, start: usize) {
+        let later: Vec<&Arg> = flags[start ..].iter()
+                                              .filter_map(|tuple| self.0.into_iter().find(|arg| tuple.0.matches(arg)))
+                                              .cloned()
+                                              .collect();
+
+        let mut index = 0;
+        flags.retain(|tuple| {
+            index += 1;
+            index > start || !later.iter().any(|arg| self.is_for(&tuple.0, arg))
+        });
     }
 }
 
@@ -308,6 +546,16 @@ pub struct MatchedFlags<'args> {
     /// we usually want the one nearest the end to count, and to know this,
     /// we need to know where they are in relation to one another.
     flags: Vec<(Flag, Option<&'args OsStr>)>,
+
+    /// Whether to complain about arguments being given more than once with
+    /// different values, rather than using the last one.
+    strictness: Strictness,
+
+    /// Where the flags from the last layer of arguments, which is the
+    /// command-line itself, start. Strict mode only complains about these,
+    /// so options from the config file or `EXA_OPTS` never conflict with
+    /// the ones given on the command-line.
+    command_line: usize,
 }
 
 impl<'a> MatchedFlags<'a> {
@@ -321,10 +569,81 @@ impl<'a> MatchedFlags<'a> {
 
     /// If the given argument was specified, return its value.
     /// The value is not guaranteed to be valid UTF-8.
-    pub fn get(&self, arg: &Arg) -> Option<&OsStr> {
-        self.flags.iter().rev()
-            .find(|tuple| tuple.1.is_some() && tuple.0.matches(arg))
+    pub fn get(&self, arg: &Arg) -> Result<Option<&OsStr>, Misfire> {
+        self.get_where(|flag| flag.matches(arg))
+    }
+
+    /// Returns the value of the last argument matching the given predicate,
+    /// for arguments that can be given under more than one name, such as
+    /// `--color` and `--colour`. In strict mode, it’s an error for them to
+    /// have been given more than once with different values.
+    pub fn get_where<P>(&self, predicate: P) -> Result<Option<&OsStr>, Misfire>
+    where P: Fn(&Flag) -> bool {
+        let values = self.flags.iter().enumerate().rev()
+                         .filter(|&(_, tuple)| tuple.1.is_some() && predicate(&tuple.0))
+                         .collect::<Vec<_>>();
+
+        self.check_duplicates(&values)?;
+        Ok(values.first().and_then(|&(_, tuple)| tuple.1))
+    }
+
+    /// If the given argument was specified, return its value if it was
+    /// given one, or `Some(None)` if it was given on its own.
+    pub fn get_optional(&self, arg: &Arg) -> Result<Option<Option<&OsStr>>, Misfire> {
+        self.get_optional_where(|flag| flag.matches(arg))
+    }
+
+    /// Returns the value of the last argument matching the given predicate,
+    /// if it was given one, in the same way as `get_optional`.
+    pub fn get_optional_where<P>(&self, predicate: P) -> Result<Option<Option<&OsStr>>, Misfire>
+    where P: Fn(&Flag) -> bool {
+        let values = self.flags.iter().enumerate().rev()
+                         .filter(|&(_, tuple)| predicate(&tuple.0))
+                         .collect::<Vec<_>>();
+
+        self.check_duplicates(&values)?;
+        Ok(values.first().map(|&(_, tuple)| tuple.1))
+    }
+
+    /// In strict mode, makes sure that all the given occurrences of an
+    /// argument on the command-line, from last to first, have the same
+    /// value as the last one. Each comes with its index in the flags.
+    fn check_duplicates(&self, values: &[(usize, &(Flag, Option<&OsStr>))]) -> Result<(), Misfire> {
+        if !self.is_strict() {
+            return Ok(());
+        }
+
+        let values: Vec<&(Flag, Option<&OsStr>)> = values.iter()
+                                                         .filter(|&&(index, _)| index >= self.command_line)
+                                                         .map(|&(_, tuple)| tuple)
+                                                         .collect();
+
+        match (values.first(), values.iter().find(|tuple| tuple.1 != values[0].1)) {
+            (Some(last), Some(other))  => Err(Misfire::Duplicate(other.0.clone(), last.0.clone())),
+            _                          => Ok(()),
+        }
+    }
+
+    /// Whether redundant arguments should be complained about, rather than
+    /// resolved by using the last ones.
+    pub fn is_strict(&self) -> bool {
+        self.strictness == Strictness::ComplainAboutRedundantArguments
+    }
+
+    /// The flags that were given on the command-line itself, rather than
+    /// coming from the config file or `EXA_OPTS`, for strict mode to check
+    /// for conflicts between.
+    pub fn command_line(&self) -> MatchedFlags<'a> {
+        MatchedFlags { flags: self.flags[self.command_line ..].to_vec(), strictness: self.strictness, command_line: 0 }
+    }
+
+    /// Return the values of *every* occurrence of the given argument, in
+    /// the order they were given, for arguments that can be repeated.
+    pub fn get_all(&self, arg: &Arg) -> Vec<&OsStr> {
+        self.flags.iter()
+            .filter(|tuple| tuple.1.is_some() && tuple.0.matches(arg))
             .map(|tuple| tuple.1.unwrap())
+            .collect()
     }
 
     // It’s annoying that ‘has’ and ‘get’ won’t work when accidentally given
@@ -356,8 +675,17 @@ pub enum ParseError {
 
     /// A long argument was not recognised by the program.
     /// We don’t have a known &str version of the flag, so
-    /// this may not be valid UTF-8.
-    UnknownArgument { attempt: OsString },
+    /// this may not be valid UTF-8. The suggestions are the names of the
+    /// arguments closest to it, out of the ones it was parsed against.
+    UnknownArgument { attempt: OsString, suggestions: Vec<LongArg> },
+
+    /// A long argument was the start of more than one argument’s name, so
+    /// it can’t be told which one was meant.
+    AmbiguousArgument { attempt: OsString, candidates: Vec<LongArg> },
+
+    /// A flag that only accepts a fixed set of values was given one that
+    /// isn’t in it.
+    BadValue { flag: Flag, value: OsString, values: Values },
 }
 
 // It’s technically possible for ParseError::UnknownArgument to borrow its
@@ -385,6 +713,33 @@ fn split_on_equals(input: &OsStr) -> Option<(&OsStr, &OsStr)> {
 }
 
 
+/// Checks that the value given to a flag is one of the values it accepts.
+fn check_value(flag: &Flag, value: &OsStr, values: Values) -> Result<(), ParseError> {
+    if values.iter().any(|v| value == *v) {
+        Ok(())
+    }
+    else {
+        Err(ParseError::BadValue { flag: flag.clone(), value: value.to_os_string(), values })
+    }
+}
+
+
+/// Adds a flag with the value it was given to the list of matched flags.
+/// A flag that takes a list of values gets added once for each of them.
+fn push_value<'args>(flags: &mut Vec<(Flag, Option<&'args OsStr>)>, flag: Flag, takes_value: TakesValue, value: &'args OsStr) {
+    use std::os::unix::ffi::OsStrExt;
+
+    match takes_value {
+        TakesValue::List(_) | TakesValue::SuffixedList(..) => {
+            for part in value.as_bytes().split(|&b| b == b',') {
+                flags.push((flag.clone(), Some(OsStr::from_bytes(part))));
+            }
+        },
+        _ => flags.push((flag, Some(value))),
+    }
+}
+
+
 /// Creates an `OSString` (used in tests)
 #[cfg(test)]
 fn os(input: &'static str) -> OsString {
@@ -452,9 +807,8 @@ mod parse_test {
                     .map(|&(ref f, ref os): &(Flag, Option<&'static str>)| (f.clone(), os.map(OsStr::new)))
                     .collect();
 
-                let got = Args(TEST_ARGS).parse(inputs.iter());
-                let expected = Ok(Matches { frees, flags: MatchedFlags { flags } });
-                assert_eq!(got, expected);
+                let got = Args(TEST_ARGS).parse(inputs.iter(), Strictness::UseLastArguments);
+                assert_eq!(got.map(|m| (m.frees, m.flags.flags)), Ok((frees, flags)));
             }
         };
 
@@ -464,17 +818,31 @@ mod parse_test {
                 use self::ParseError::*;
 
                 let bits = $inputs.as_ref().into_iter().map(|&o| os(o)).collect::<Vec<OsString>>();
-                let got = Args(TEST_ARGS).parse(bits.iter());
+                let got = Args(TEST_ARGS).parse(bits.iter(), Strictness::UseLastArguments);
 
                 assert_eq!(got, Err($error));
             }
         };
     }
 
+    static WHENS: Values = &[ "always", "auto", "never" ];
+    static TYPES: Values = &[ "file", "dir" ];
+    static FIELDS: Values = &[ "size", "name", "date" ];
+    static DIRECTIONS: Values = &[ "up", "down" ];
+
     static TEST_ARGS: &[&Arg] = &[
         &Arg { short: Some(b'l'), long: "long",     takes_value: TakesValue::Forbidden },
         &Arg { short: Some(b'v'), long: "verbose",  takes_value: TakesValue::Forbidden },
-        &Arg { short: Some(b'c'), long: "count",    takes_value: TakesValue::Necessary }
+        &Arg { short: Some(b'c'), long: "count",    takes_value: TakesValue::Necessary(None) },
+        &Arg { short: Some(b's'), long: "scale",    takes_value: TakesValue::Optional(None, None) },
+        &Arg { short: None,       long: "when",     takes_value: TakesValue::Optional(Some(WHENS), Some("always")) },
+        &Arg { short: None,       long: "scales",   takes_value: TakesValue::Forbidden },
+        &Arg { short: Some(b't'), long: "type",     takes_value: TakesValue::Necessary(Some(TYPES)) },
+        &Arg { short: Some(b'f'), long: "fields",   takes_value: TakesValue::List(Some(FIELDS)) },
+        &Arg { short: None,       long: "order",    takes_value: TakesValue::SuffixedList(FIELDS, DIRECTIONS) },
+        &Arg { short: None,       long: "marks",    takes_value: TakesValue::OptionalList(FIELDS) },
+        &Arg { short: None,       long: "bench",    takes_value: TakesValue::Forbidden },
+        &Arg { short: None,       long: "no-cache", takes_value: TakesValue::Forbidden }
     ];
 
 
@@ -501,6 +869,71 @@ mod parse_test {
     test!(arg_equals:  ["--count=4"]      => frees: [],  flags: [ (Flag::Long("count"), Some("4")) ]);
     test!(arg_then:    ["--count", "4"]   => frees: [],  flags: [ (Flag::Long("count"), Some("4")) ]);
 
+    // Long args with optional values
+    test!(opt_none:    ["--scale"]          => frees: [],       flags: [ (Flag::Long("scale"), None) ]);
+    test!(opt_equals:  ["--scale=age"]      => frees: [],       flags: [ (Flag::Long("scale"), Some("age")) ]);
+    test!(opt_then:    ["--scale", "age"]   => frees: [ "age" ], flags: [ (Flag::Long("scale"), None) ]);
+
+    // Long args with optional values that have defaults
+    test!(def_none:    ["--when"]           => frees: [],         flags: [ (Flag::Long("when"), Some("always")) ]);
+    test!(def_equals:  ["--when=never"]     => frees: [],         flags: [ (Flag::Long("when"), Some("never")) ]);
+    test!(def_then:    ["--when", "never"]  => frees: [ "never" ], flags: [ (Flag::Long("when"), Some("always")) ]);
+
+    // Args with a fixed set of values
+    test!(val_equals:  ["--type=dir"]       => frees: [],  flags: [ (Flag::Long("type"), Some("dir")) ]);
+    test!(val_then:    ["--type", "file"]   => frees: [],  flags: [ (Flag::Long("type"), Some("file")) ]);
+    test!(val_short:   ["-tdir"]            => frees: [],  flags: [ (Flag::Short(b't'), Some("dir")) ]);
+    test!(val_bad:     ["--type=link"]      => error BadValue { flag: Flag::Long("type"), value: os("link"), values: TYPES });
+    test!(val_bad_2:   ["-l", "-t", "DIR"]  => error BadValue { flag: Flag::Short(b't'), value: os("DIR"), values: TYPES });
+    test!(val_opt_bad: ["--when=sometimes"] => error BadValue { flag: Flag::Long("when"), value: os("sometimes"), values: WHENS });
+
+    // Args with comma-separated lists of values
+    test!(list_one:    ["--fields=size"]        => frees: [],  flags: [ (Flag::Long("fields"), Some("size")) ]);
+    test!(list_equals: ["--fields=size,name"]   => frees: [],  flags: [ (Flag::Long("fields"), Some("size")), (Flag::Long("fields"), Some("name")) ]);
+    test!(list_then:   ["--fields", "name,date"] => frees: [], flags: [ (Flag::Long("fields"), Some("name")), (Flag::Long("fields"), Some("date")) ]);
+    test!(list_short:  ["-lfsize,date"]         => frees: [],  flags: [ (Flag::Short(b'l'), None), (Flag::Short(b'f'), Some("size")), (Flag::Short(b'f'), Some("date")) ]);
+    test!(list_bad:    ["--fields=size,colour"] => error BadValue { flag: Flag::Long("fields"), value: os("colour"), values: FIELDS });
+    test!(not_list:    ["--count=1,2"]          => frees: [],  flags: [ (Flag::Long("count"), Some("1,2")) ]);
+
+    // Lists whose values can have a suffix after a colon
+    test!(suffix_none:  ["--order=size,name"]      => frees: [],  flags: [ (Flag::Long("order"), Some("size")), (Flag::Long("order"), Some("name")) ]);
+    test!(suffix_given: ["--order=size:down,name"] => frees: [],  flags: [ (Flag::Long("order"), Some("size:down")), (Flag::Long("order"), Some("name")) ]);
+    test!(suffix_bad:   ["--order=size:left"]      => error BadValue { flag: Flag::Long("order"), value: os("left"), values: DIRECTIONS });
+    test!(suffix_field: ["--order=colour:up"]      => error BadValue { flag: Flag::Long("order"), value: os("colour"), values: FIELDS });
+    test!(suffix_needs: ["--order"]                => error NeedsValue { flag: Flag::Long("order") });
+
+    // Optional lists, which are only checked when they’re given a value
+    test!(opt_list_none: ["--marks"]               => frees: [],         flags: [ (Flag::Long("marks"), None) ]);
+    test!(opt_list_then: ["--marks", "size"]       => frees: [ "size" ], flags: [ (Flag::Long("marks"), None) ]);
+    test!(opt_list_eq:   ["--marks=size,date"]     => frees: [],         flags: [ (Flag::Long("marks"), Some("size,date")) ]);
+    test!(opt_list_bad:  ["--marks=size,colour"]   => error BadValue { flag: Flag::Long("marks"), value: os("colour"), values: FIELDS });
+
+
+    // Negated long args
+    test!(neg:         ["--no-long"]                     => frees: [],  flags: []);
+    test!(neg_after:   ["--long", "--no-long"]           => frees: [],  flags: []);
+    test!(neg_short:   ["-lv", "--no-long"]              => frees: [],  flags: [ (Flag::Short(b'v'), None) ]);
+    test!(neg_before:  ["--no-long", "--long"]           => frees: [],  flags: [ (Flag::Long("long"), None) ]);
+    test!(neg_others:  ["--long", "--no-long", "--verbose"] => frees: [],  flags: [ (Flag::Long("verbose"), None) ]);
+    test!(neg_value:   ["--count", "4", "--no-count"]    => frees: [],  flags: []);
+    test!(neg_value_short: ["-c4", "--no-count"]         => frees: [],  flags: []);
+    test!(neg_optional: ["--when=never", "--no-when"]    => frees: [],  flags: []);
+    test!(neg_list:    ["--fields=size,name", "--no-fields", "--fields=date"] => frees: [],  flags: [ (Flag::Long("fields"), Some("date")) ]);
+    test!(neg_unknown: ["--no-quiet"]                    => error UnknownArgument { attempt: os("no-quiet"), suggestions: vec![] });
+    test!(neg_equals:  ["--no-long=yes"]                 => error UnknownArgument { attempt: os("no-long"), suggestions: vec![] });
+
+
+    // Layers of arguments
+    test!(layer_replace: ["--count=1", LAYER_END, "--count=2"]               => frees: [],  flags: [ (Flag::Long("count"), Some("2")) ]);
+    test!(layer_list:    ["--fields=size,name", LAYER_END, "-fdate"]         => frees: [],  flags: [ (Flag::Short(b'f'), Some("date")) ]);
+    test!(layer_same:    ["--fields=size", "--fields=name", LAYER_END]       => frees: [],  flags: [ (Flag::Long("fields"), Some("size")), (Flag::Long("fields"), Some("name")) ]);
+    test!(layer_others:  ["-l", "--count=1", LAYER_END, "-c2", "-v"]         => frees: [],  flags: [ (Flag::Short(b'l'), None), (Flag::Short(b'c'), Some("2")), (Flag::Short(b'v'), None) ]);
+    test!(layer_three:   ["-c1", "-l", LAYER_END, "-c2", LAYER_END, "-c3"]   => frees: [],  flags: [ (Flag::Short(b'l'), None), (Flag::Short(b'c'), Some("3")) ]);
+    test!(layer_negate:  ["-l", "--count=1", LAYER_END, "--no-long", "-c2"]  => frees: [],  flags: [ (Flag::Short(b'c'), Some("2")) ]);
+    test!(layer_dashes:  ["--", "-l", LAYER_END, "-v"]                       => frees: [ "-l" ],  flags: [ (Flag::Short(b'v'), None) ]);
+    test!(layer_value:   ["--count", LAYER_END, "4"]                         => error NeedsValue { flag: Flag::Long("count") });
+    test!(layer_short:   ["-c", LAYER_END, "4"]                              => error NeedsValue { flag: Flag::Short(b'c') });
+
 
     // Short args
     test!(short:       ["-l"]            => frees: [],       flags: [ (Flag::Short(b'l'), None) ]);
@@ -516,15 +949,71 @@ mod parse_test {
     test!(short_two_together: ["-lctwo"]      => frees: [],  flags: [(Flag::Short(b'l'), None), (Flag::Short(b'c'), Some("two")) ]);
     test!(short_two_equals:   ["-lc=two"]     => frees: [],  flags: [(Flag::Short(b'l'), None), (Flag::Short(b'c'), Some("two")) ]);
     test!(short_two_next:     ["-lc", "two"]  => frees: [],  flags: [(Flag::Short(b'l'), None), (Flag::Short(b'c'), Some("two")) ]);
+    test!(short_opt:          ["-sl"]         => frees: [],  flags: [(Flag::Short(b's'), None), (Flag::Short(b'l'), None) ]);
+    test!(short_opt_equals:   ["-ls=age"]     => frees: [],  flags: [(Flag::Short(b'l'), None), (Flag::Short(b's'), Some("age")) ]);
 
 
     // Unknown args
-    test!(unknown_long:          ["--quiet"]      => error UnknownArgument      { attempt: os("quiet") });
-    test!(unknown_long_eq:       ["--quiet=shhh"] => error UnknownArgument      { attempt: os("quiet") });
+    test!(unknown_long:          ["--quiet"]      => error UnknownArgument      { attempt: os("quiet"), suggestions: vec![] });
+    test!(unknown_close:         ["--cont"]       => error UnknownArgument      { attempt: os("cont"), suggestions: vec![ "count" ] });
+    test!(unknown_long_eq:       ["--quiet=shhh"] => error UnknownArgument      { attempt: os("quiet"), suggestions: vec![] });
     test!(unknown_short:         ["-q"]           => error UnknownShortArgument { attempt: b'q' });
     test!(unknown_short_2nd:     ["-lq"]          => error UnknownShortArgument { attempt: b'q' });
     test!(unknown_short_eq:      ["-q=shhh"]      => error UnknownShortArgument { attempt: b'q' });
     test!(unknown_short_2nd_eq:  ["-lq=shhh"]     => error UnknownShortArgument { attempt: b'q' });
+
+    // Prefixes of long args
+    test!(prefix:                ["--lo"]         => frees: [],  flags: [ (Flag::Long("long"), None) ]);
+    test!(prefix_eq:             ["--co=4"]       => frees: [],  flags: [ (Flag::Long("count"), Some("4")) ]);
+    test!(prefix_then:           ["--co", "4"]    => frees: [],  flags: [ (Flag::Long("count"), Some("4")) ]);
+    test!(prefix_ambiguous:      ["--s"]          => error AmbiguousArgument    { attempt: os("s"), candidates: vec![ "scale", "scales" ] });
+    test!(prefix_exact:          ["--scale"]      => frees: [],  flags: [ (Flag::Long("scale"), None) ]);
+    test!(prefix_too_long:       ["--longer"]     => error UnknownArgument      { attempt: os("longer"), suggestions: vec![ "long" ] });
+
+    // Hidden args, which only match their whole names
+    test!(hidden_exact:          ["--bench"]      => frees: [],  flags: [ (Flag::Long("bench"), None) ]);
+    test!(hidden_prefix:         ["--benc"]       => error UnknownArgument      { attempt: os("benc"), suggestions: vec![] });
+    test!(hidden_typo:           ["--bnch"]       => error UnknownArgument      { attempt: os("bnch"), suggestions: vec![] });
+
+    // Args whose names start with “no-”, which only match their whole names
+    test!(no_exact:              ["--no-cache"]   => frees: [],  flags: [ (Flag::Long("no-cache"), None) ]);
+    test!(no_alone:              ["--no"]         => error UnknownArgument      { attempt: os("no"), suggestions: vec![] });
+    test!(no_prefix:             ["--no-c"]       => error UnknownArgument      { attempt: os("no-c"), suggestions: vec![] });
+    test!(no_prefix_longer:      ["--no-cac"]     => error UnknownArgument      { attempt: os("no-cac"), suggestions: vec![ "no-cache" ] });
+
+
+    // Aliases, which can’t go in TEST_ARGS without making `--co` ambiguous
+    static COLOR:  Arg = Arg { short: None, long: "color",  takes_value: TakesValue::Optional(Some(WHENS), Some("always")) };
+    static COLOUR: Arg = Arg { short: None, long: "colour", takes_value: TakesValue::Optional(Some(WHENS), Some("always")) };
+    static ALIAS_ARGS: &[&Arg] = &[ &COLOR, &COLOUR ];
+
+    #[test]
+    fn neg_alias() {
+        let bits = vec![ os("--color=never"), os("--colour"), os("--no-colour") ];
+        let got = Args(ALIAS_ARGS).parse(bits.iter(), Strictness::UseLastArguments).map(|m| m.flags.flags);
+        assert_eq!(got, Ok(Vec::new()));
+    }
+
+    #[test]
+    fn prefix_alias() {
+        let bits = vec![ os("--col=never") ];
+        let got = Args(ALIAS_ARGS).parse(bits.iter(), Strictness::UseLastArguments).map(|m| m.flags.flags);
+        assert_eq!(got, Ok(vec![ (Flag::Long("color"), Some(OsStr::new("never"))) ]));
+    }
+
+    #[test]
+    fn layer_alias() {
+        let bits = vec![ os("--color=never"), os(LAYER_END), os("--colour=auto") ];
+        let got = Args(ALIAS_ARGS).parse(bits.iter(), Strictness::UseLastArguments).map(|m| m.flags.flags);
+        assert_eq!(got, Ok(vec![ (Flag::Long("colour"), Some(OsStr::new("auto"))) ]));
+    }
+
+    #[test]
+    fn neg_alias_before() {
+        let bits = vec![ os("--no-color"), os("--colour=auto") ];
+        let got = Args(ALIAS_ARGS).parse(bits.iter(), Strictness::UseLastArguments).map(|m| m.flags.flags);
+        assert_eq!(got, Ok(vec![ (Flag::Long("colour"), Some(OsStr::new("auto"))) ]));
+    }
 }
 
 
@@ -536,14 +1025,14 @@ mod matches_test {
         ($name:ident: $input:expr, has $param:expr => $result:expr) => {
             #[test]
             fn $name() {
-                let flags = MatchedFlags { flags: $input.to_vec() };
+                let flags = MatchedFlags { flags: $input.to_vec(), strictness: Strictness::UseLastArguments, command_line: 0 };
                 assert_eq!(flags.has(&$param), $result);
             }
         };
     }
 
     static VERBOSE: Arg = Arg { short: Some(b'v'), long: "verbose", takes_value: TakesValue::Forbidden };
-    static COUNT:   Arg = Arg { short: Some(b'c'), long: "count",   takes_value: TakesValue::Necessary };
+    static COUNT:   Arg = Arg { short: Some(b'c'), long: "count",   takes_value: TakesValue::Necessary(None) };
 
 
     test!(short_never:  [],                                                              has VERBOSE => false);
@@ -557,8 +1046,8 @@ mod matches_test {
     #[test]
     fn only_count() {
         let everything = os("everything");
-        let flags = MatchedFlags { flags: vec![ (Flag::Short(b'c'), Some(&*everything)) ] };
-        assert_eq!(flags.get(&COUNT), Some(&*everything));
+        let flags = MatchedFlags { flags: vec![ (Flag::Short(b'c'), Some(&*everything)) ], strictness: Strictness::UseLastArguments, command_line: 0 };
+        assert_eq!(flags.get(&COUNT), Ok(Some(&*everything)));
     }
 
     #[test]
@@ -568,15 +1057,106 @@ mod matches_test {
 
         let flags = MatchedFlags {
             flags: vec![ (Flag::Short(b'c'), Some(&*everything)),
-                         (Flag::Short(b'c'), Some(&*nothing)) ]
+                         (Flag::Short(b'c'), Some(&*nothing)) ],
+            strictness: Strictness::UseLastArguments,
+            command_line: 0,
+        };
+
+        assert_eq!(flags.get(&COUNT), Ok(Some(&*nothing)));
+    }
+
+    #[test]
+    fn every_count() {
+        let everything = os("everything");
+        let nothing    = os("nothing");
+
+        let flags = MatchedFlags {
+            flags: vec![ (Flag::Short(b'c'), Some(&*everything)),
+                         (Flag::Long("verbose"), None),
+                         (Flag::Long("count"), Some(&*nothing)) ],
+            strictness: Strictness::UseLastArguments,
+            command_line: 0,
+        };
+
+        assert_eq!(flags.get_all(&COUNT), vec![ &*everything, &*nothing ]);
+    }
+
+    #[test]
+    fn optional_count() {
+        let nothing = os("nothing");
+
+        let flags = MatchedFlags {
+            flags: vec![ (Flag::Short(b'c'), Some(&*nothing)),
+                         (Flag::Long("count"), None) ],
+            strictness: Strictness::UseLastArguments,
+            command_line: 0,
+        };
+
+        assert_eq!(flags.get_optional(&COUNT), Ok(Some(None)));
+    }
+
+    #[test]
+    fn strict_same_count() {
+        let everything = os("everything");
+
+        let flags = MatchedFlags {
+            flags: vec![ (Flag::Short(b'c'), Some(&*everything)),
+                         (Flag::Long("count"), Some(&*everything)) ],
+            strictness: Strictness::ComplainAboutRedundantArguments,
+            command_line: 0,
+        };
+
+        assert_eq!(flags.get(&COUNT), Ok(Some(&*everything)));
+    }
+
+    #[test]
+    fn strict_different_counts() {
+        let everything = os("everything");
+        let nothing    = os("nothing");
+
+        let flags = MatchedFlags {
+            flags: vec![ (Flag::Short(b'c'), Some(&*everything)),
+                         (Flag::Long("verbose"), None),
+                         (Flag::Long("count"), Some(&*nothing)) ],
+            strictness: Strictness::ComplainAboutRedundantArguments,
+            command_line: 0,
+        };
+
+        assert_eq!(flags.get(&COUNT), Err(Misfire::Duplicate(Flag::Short(b'c'), Flag::Long("count"))));
+    }
+
+    #[test]
+    fn strict_earlier_layer() {
+        let everything = os("everything");
+        let nothing    = os("nothing");
+
+        let flags = MatchedFlags {
+            flags: vec![ (Flag::Short(b'c'), Some(&*everything)),
+                         (Flag::Long("count"), Some(&*nothing)) ],
+            strictness: Strictness::ComplainAboutRedundantArguments,
+            command_line: 1,
+        };
+
+        assert_eq!(flags.get(&COUNT), Ok(Some(&*nothing)));
+    }
+
+    #[test]
+    fn strict_optional_count() {
+        let nothing = os("nothing");
+
+        let flags = MatchedFlags {
+            flags: vec![ (Flag::Short(b'c'), Some(&*nothing)),
+                         (Flag::Long("count"), None) ],
+            strictness: Strictness::ComplainAboutRedundantArguments,
+            command_line: 0,
         };
 
-        assert_eq!(flags.get(&COUNT), Some(&*nothing));
+        assert_eq!(flags.get_optional(&COUNT), Err(Misfire::Duplicate(Flag::Short(b'c'), Flag::Long("count"))));
     }
 
     #[test]
     fn no_count() {
-        let flags = MatchedFlags { flags: Vec::new() };
+        let flags = MatchedFlags { flags: Vec::new(), strictness: Strictness::UseLastArguments, command_line: 0 };
 
         assert!(!flags.has(&COUNT));
     }
@@ -0,0 +1,108 @@
+//! Expanding `@file` arguments into the paths listed in their files.
+//!
+//! An argument of `@` followed by a path gets replaced by the paths in that
+//! file, one per line or separated by NUL bytes, the same way `--stdin`
+//! reads them. This happens straight after the options have been parsed,
+//! so nothing in the file ever gets taken as an option. An argument that
+//! starts with `@@` is the path with its first `@` taken off, for files
+//! whose names start with one.
+//!
+//! A list that can’t be read doesn’t stop the others from being listed:
+//! it gets kept in its place, so it can be warned about along with the
+//! arguments that don’t exist.
+
+use std::ffi::OsStr;
+use std::fs::File;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+
+use fs::path_list;
+
+
+/// A **path argument** is one of the free arguments, after any `@file`
+/// arguments have been expanded.
+#[derive(PartialEq, Debug)]
+pub enum PathArg<'args> {
+
+    /// An argument that was given as it is, which might be `-`.
+    Given(&'args OsStr),
+
+    /// A path read from a list file.
+    Listed(PathBuf),
+
+    /// A list file that couldn’t be read, and the reason why.
+    Unreadable(&'args Path, String),
+}
+
+/// Expands every `@file` argument in the given list into the paths in its
+/// file, leaving the others as they are.
+pub fn expand<'args>(frees: Vec<&'args OsStr>) -> Vec<PathArg<'args>> {
+    let mut args = Vec::new();
+
+    for free in frees {
+        let bytes = free.as_bytes();
+
+        if bytes.starts_with(b"@@") {
+            args.push(PathArg::Given(OsStr::from_bytes(&bytes[1..])));
+        }
+        else if bytes.len() > 1 && bytes[0] == b'@' {
+            let list = Path::new(OsStr::from_bytes(&bytes[1..]));
+            match File::open(list).and_then(path_list::read) {
+                Ok(paths)  => args.extend(paths.into_iter().map(PathArg::Listed)),
+                Err(e)     => args.push(PathArg::Unreadable(list, e.to_string())),
+            }
+        }
+        else {
+            args.push(PathArg::Given(free));
+        }
+    }
+
+    args
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::env::temp_dir;
+    use std::fs::remove_file;
+    use std::io::Write;
+    use std::process;
+
+    #[test]
+    fn given() {
+        let args = expand(vec![ OsStr::new("src"), OsStr::new("-"), OsStr::new("@") ]);
+        assert_eq!(args, vec![ PathArg::Given(OsStr::new("src")), PathArg::Given(OsStr::new("-")), PathArg::Given(OsStr::new("@")) ]);
+    }
+
+    #[test]
+    fn escaped() {
+        let args = expand(vec![ OsStr::new("@@paths.txt") ]);
+        assert_eq!(args, vec![ PathArg::Given(OsStr::new("@paths.txt")) ]);
+    }
+
+    #[test]
+    fn listed() {
+        let list = temp_dir().join(format!("exa-path-args-{}", process::id()));
+        File::create(&list).and_then(|mut f| f.write_all(b"one\ntwo\n")).unwrap();
+
+        let arg = format!("@{}", list.display());
+        let args = expand(vec![ OsStr::new("zero"), OsStr::new(&arg), OsStr::new("three") ]);
+        remove_file(&list).unwrap();
+
+        assert_eq!(args, vec![ PathArg::Given(OsStr::new("zero")),
+                               PathArg::Listed(PathBuf::from("one")),
+                               PathArg::Listed(PathBuf::from("two")),
+                               PathArg::Given(OsStr::new("three")) ]);
+    }
+
+    #[test]
+    fn unreadable() {
+        let args = expand(vec![ OsStr::new("@/nonexistent/paths.txt"), OsStr::new("src") ]);
+        match args[0] {
+            PathArg::Unreadable(path, _)  => assert_eq!(path, Path::new("/nonexistent/paths.txt")),
+            ref other                     => panic!("expected an unreadable list, got {:?}", other),
+        }
+        assert_eq!(args[1], PathArg::Given(OsStr::new("src")));
+    }
+}
@@ -0,0 +1,101 @@
+//! Suggesting the options that the user might have meant when they give
+//! one that doesn’t exist.
+//!
+//! The suggestions are the long options whose names are the fewest edits
+//! away from what was typed, where an edit is adding, removing, or changing
+//! one character, so `--revrse` suggests `--reverse`. Names that need too
+//! many edits aren’t suggested at all, as they’re probably not what was meant.
+
+use std::ffi::OsStr;
+use std::mem;
+use std::os::unix::ffi::OsStrExt;
+
+use options::parser::{Args, LongArg};
+
+
+/// The most edits a name can be away from what was typed and still be
+/// suggested.
+const MAX_DISTANCE: usize = 2;
+
+
+/// Returns the long names of the arguments closest to the given attempt,
+/// in the order they’re defined, or nothing if none of them are close.
+/// Hidden arguments never get suggested.
+pub fn suggestions(args: &Args, attempt: &OsStr) -> Vec<LongArg> {
+    let distances: Vec<(LongArg, usize)> = args.0.iter()
+        .filter(|arg| !arg.is_hidden())
+        .map(|arg| (arg.long, edit_distance(attempt.as_bytes(), arg.long.as_bytes())))
+        .collect();
+
+    let best = match distances.iter().map(|&(_, distance)| distance).min() {
+        Some(distance) if distance <= MAX_DISTANCE  => distance,
+        _                                           => return Vec::new(),
+    };
+
+    distances.into_iter()
+             .filter(|&(_, distance)| distance == best)
+             .map(|(long, _)| long)
+             .collect()
+}
+
+
+/// The number of single-byte insertions, deletions, or substitutions it
+/// takes to turn one string into another.
+fn edit_distance(from: &[u8], to: &[u8]) -> usize {
+    let mut previous: Vec<usize> = (0 .. to.len() + 1).collect();
+    let mut current = vec![0; to.len() + 1];
+
+    for (i, a) in from.iter().enumerate() {
+        current[0] = i + 1;
+
+        for (j, b) in to.iter().enumerate() {
+            let substitution = previous[j] + if a == b { 0 } else { 1 };
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+
+        mem::swap(&mut previous, &mut current);
+    }
+
+    previous[to.len()]
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use options::parser::{Arg, TakesValue};
+
+    static TEST_ARGS: &[&Arg] = &[
+        &Arg { short: Some(b'T'), long: "tree",     takes_value: TakesValue::Forbidden },
+        &Arg { short: None,       long: "tee",      takes_value: TakesValue::Forbidden },
+        &Arg { short: Some(b'r'), long: "reverse",  takes_value: TakesValue::Forbidden },
+        &Arg { short: Some(b'R'), long: "recurse",  takes_value: TakesValue::Forbidden },
+        &Arg { short: None,       long: "bench",    takes_value: TakesValue::Forbidden },
+    ];
+
+    macro_rules! test {
+        ($name:ident: $attempt:expr => $result:expr) => {
+            #[test]
+            fn $name() {
+                let expected: &[LongArg] = &$result;
+                assert_eq!(suggestions(&Args(TEST_ARGS), OsStr::new($attempt)), expected);
+            }
+        };
+    }
+
+    // None of these are the start of an argument’s name, as those get
+    // matched as prefixes before anything gets suggested.
+    test!(missing:     "revrse"   => [ "reverse" ]);
+    test!(swapped:     "recruse"  => [ "recurse" ]);
+    test!(two_close:   "ree"      => [ "tree", "tee" ]);
+    test!(too_far:     "icons"    => []);
+    test!(short:       "x"        => []);
+    test!(hidden:      "bnch"     => []);
+
+    #[test]
+    fn distances() {
+        assert_eq!(edit_distance(b"kitten", b"sitting"), 3);
+        assert_eq!(edit_distance(b"", b"tree"), 4);
+        assert_eq!(edit_distance(b"tree", b"tree"), 0);
+    }
+}
@@ -0,0 +1,374 @@
+//! Finding out how many colours a terminal has, and which text attributes
+//! it can show, from its terminfo entry.
+//!
+//! Each terminal type has a compiled terminfo file, named after the value
+//! of `TERM`, that lists what the terminal can do. All exa needs from it is
+//! the `colors` number and whether a few strings are there, so rather than
+//! linking to a terminfo library, this reads them straight out of the file.
+
+use std::env::var_os;
+use std::fs::File;
+use std::io::Read;
+use std::path::PathBuf;
+
+use output::Attributes;
+
+
+/// The magic number at the start of a terminfo file whose numbers are two
+/// bytes long.
+const MAGIC_LEGACY: i32 = 0o432;
+
+/// The magic number at the start of a terminfo file whose numbers are four
+/// bytes long, as ncurses writes when a number doesn’t fit in two.
+const MAGIC_32BIT: i32 = 0o1036;
+
+/// The position of `colors` in the list of numeric capabilities.
+const MAX_COLOURS: usize = 13;
+
+/// The positions of the strings that turn on each text attribute in the
+/// list of string capabilities.
+const BLINK:      usize = 26;
+const BOLD:       usize = 27;
+const DIM:        usize = 30;
+const INVISIBLE:  usize = 32;
+const REVERSE:    usize = 34;
+const UNDERLINE:  usize = 36;
+const ITALICS:    usize = 311;
+
+/// The name of the string that turns on strikethrough, which isn’t one of
+/// the standard capabilities, so it’s in the extended ones instead.
+const STRIKETHROUGH: &[u8] = b"smxx";
+
+
+/// The number of colours the terminal with the given name says it has, which
+/// is 0 for terminals without colours, or `None` if its terminfo file can’t
+/// be found or read.
+pub fn max_colours(term: &str) -> Option<i32> {
+    read(term).and_then(|bytes| parse_max_colours(&bytes))
+}
+
+/// The text attributes that the terminal with the given name says it can
+/// show, or `None` if its terminfo file can’t be found or read.
+pub fn attributes(term: &str) -> Option<Attributes> {
+    read(term).and_then(|bytes| parse_attributes(&bytes))
+}
+
+/// Reads the terminfo file for the terminal with the given name.
+fn read(term: &str) -> Option<Vec<u8>> {
+    let first = match term.chars().next() {
+        Some(c)  => c,
+        None     => return None,
+    };
+
+    if term.contains('/') {
+        return None;
+    }
+
+    for dir in search_dirs() {
+
+        // Most systems put the file in a directory named after the first
+        // letter of the terminal’s name, but macOS uses its hex value.
+        for sub in &[ first.to_string(), format!("{:x}", first as u32) ] {
+            let mut bytes = Vec::new();
+            if File::open(dir.join(sub).join(term)).and_then(|mut f| f.read_to_end(&mut bytes)).is_ok() {
+                return Some(bytes);
+            }
+        }
+    }
+
+    None
+}
+
+/// The directories to search for terminfo files in, in the same order as
+/// ncurses searches them.
+fn search_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+
+    if let Some(dir) = var_os("TERMINFO") {
+        dirs.push(PathBuf::from(dir));
+    }
+
+    if let Some(home) = var_os("HOME") {
+        dirs.push(PathBuf::from(home).join(".terminfo"));
+    }
+
+    let defaults = [ "/etc/terminfo", "/lib/terminfo", "/usr/share/terminfo" ];
+    match var_os("TERMINFO_DIRS") {
+        Some(list) => {
+            for dir in list.to_string_lossy().split(':') {
+                if dir.is_empty() {
+                    dirs.extend(defaults.iter().map(PathBuf::from));
+                }
+                else {
+                    dirs.push(PathBuf::from(dir));
+                }
+            }
+        },
+        None => dirs.extend(defaults.iter().map(PathBuf::from)),
+    }
+
+    dirs
+}
+
+/// Where everything is in a compiled terminfo file, as worked out from the
+/// header at its start.
+struct Layout {
+
+    /// How many bytes each number takes up.
+    number_size: usize,
+
+    /// How many numbers and strings there are.
+    numbers_count: usize,
+    strings_count: usize,
+
+    /// Where the numbers and the offsets of the strings start.
+    numbers_start: usize,
+    strings_start: usize,
+
+    /// Where the extended capabilities start, after the end of the strings.
+    extended_start: usize,
+}
+
+impl Layout {
+    fn read(bytes: &[u8]) -> Option<Layout> {
+        let number_size = match short(bytes, 0) {
+            Some(MAGIC_LEGACY)  => 2,
+            Some(MAGIC_32BIT)   => 4,
+            _                   => return None,
+        };
+
+        let counts = [ short(bytes, 2), short(bytes, 4), short(bytes, 6), short(bytes, 8), short(bytes, 10) ];
+        let (names_size, bools_count, numbers_count, strings_count, table_size) = match counts {
+            [ Some(n), Some(b), Some(c), Some(s), Some(t) ] if n >= 0 && b >= 0 && c >= 0 && s >= 0 && t >= 0
+                => (n as usize, b as usize, c as usize, s as usize, t as usize),
+            _   => return None,
+        };
+
+        // The numbers start on an even byte, after the header, the names, and
+        // one byte for each boolean.
+        let numbers_start = even(12 + names_size + bools_count);
+        let strings_start = numbers_start + numbers_count * number_size;
+        let extended_start = even(strings_start + strings_count * 2 + table_size);
+
+        Some(Layout { number_size, numbers_count, strings_count, numbers_start, strings_start, extended_start })
+    }
+
+    /// Whether the string capability at the given position is there.
+    fn has_string(&self, bytes: &[u8], index: usize) -> bool {
+        index < self.strings_count && short(bytes, self.strings_start + index * 2).map_or(false, |offset| offset >= 0)
+    }
+}
+
+/// Reads the `colors` number out of the contents of a compiled terminfo
+/// file, returning `None` if the file is malformed.
+fn parse_max_colours(bytes: &[u8]) -> Option<i32> {
+    let layout = match Layout::read(bytes) {
+        Some(l)  => l,
+        None     => return None,
+    };
+
+    if layout.numbers_count <= MAX_COLOURS {
+        return Some(0);
+    }
+
+    let index = layout.numbers_start + MAX_COLOURS * layout.number_size;
+    let number = if layout.number_size == 2 {
+        short(bytes, index)
+    }
+    else {
+        match bytes.get(index .. index + 4) {
+            Some(b)  => Some(i32::from(b[0]) | i32::from(b[1]) << 8 | i32::from(b[2]) << 16 | i32::from(b[3]) << 24),
+            None     => None,
+        }
+    };
+
+    // Absent and cancelled numbers are stored as negative numbers.
+    number.map(|n| n.max(0))
+}
+
+/// Reads which text attributes can be turned on out of the contents of a
+/// compiled terminfo file, returning `None` if the file is malformed.
+fn parse_attributes(bytes: &[u8]) -> Option<Attributes> {
+    let layout = match Layout::read(bytes) {
+        Some(l)  => l,
+        None     => return None,
+    };
+
+    Some(Attributes {
+        bold:           layout.has_string(bytes, BOLD),
+        dimmed:         layout.has_string(bytes, DIM),
+        italic:         layout.has_string(bytes, ITALICS),
+        underline:      layout.has_string(bytes, UNDERLINE),
+        blink:          layout.has_string(bytes, BLINK),
+        reverse:        layout.has_string(bytes, REVERSE),
+        hidden:         layout.has_string(bytes, INVISIBLE),
+        strikethrough:  has_extended_string(bytes, &layout, STRIKETHROUGH),
+    })
+}
+
+/// Whether the extended string capability with the given name is there.
+///
+/// The extended capabilities have a header of their own, and then their
+/// booleans, numbers, and the offsets of their strings and names, the same
+/// as the standard ones. Their table holds the strings that are there,
+/// followed by the names of every capability, so the names are at the end.
+fn has_extended_string(bytes: &[u8], layout: &Layout, name: &[u8]) -> bool {
+    let start = layout.extended_start;
+    let (bools_count, numbers_count, strings_count, table_size) = match (short(bytes, start), short(bytes, start + 2), short(bytes, start + 4), short(bytes, start + 8)) {
+        (Some(b), Some(n), Some(s), Some(t)) if b >= 0 && n >= 0 && s >= 0 && t >= 0  => (b as usize, n as usize, s as usize, t as usize),
+        _                                                                            => return false,
+    };
+
+    let strings_start = even(start + 10 + bools_count) + numbers_count * layout.number_size;
+    let names_count = bools_count + numbers_count + strings_count;
+    let table_start = strings_start + (strings_count + names_count) * 2;
+
+    let table = match bytes.get(table_start .. table_start + table_size) {
+        Some(t)  => t,
+        None     => return false,
+    };
+
+    let entries = table.split(|&b| b == 0).collect::<Vec<_>>();
+    if entries.len() <= names_count {
+        return false;
+    }
+
+    // The last entry is the empty one after the final NUL.
+    let string_names = &entries[entries.len() - 1 - strings_count .. entries.len() - 1];
+    match string_names.iter().position(|&n| n == name) {
+        Some(index)  => short(bytes, strings_start + index * 2).map_or(false, |offset| offset >= 0),
+        None         => false,
+    }
+}
+
+/// Reads a little-endian two-byte number.
+fn short(bytes: &[u8], index: usize) -> Option<i32> {
+    match (bytes.get(index), bytes.get(index + 1)) {
+        (Some(&lo), Some(&hi))  => Some(i32::from(i16::from(lo) | i16::from(hi) << 8)),
+        _                       => None,
+    }
+}
+
+/// Rounds up to the next even number, as sections of the file start on
+/// even bytes.
+fn even(n: usize) -> usize {
+    n + n % 2
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Builds a terminfo file with the given `colors` number, and no other
+    /// numbers set.
+    fn terminfo(magic: i32, colours: i32) -> Vec<u8> {
+        let size = if magic == MAGIC_LEGACY { 2 } else { 4 };
+        let names = b"test|a test terminal\0";
+        let mut bytes = Vec::new();
+
+        for &n in &[ magic, names.len() as i32, 1, MAX_COLOURS as i32 + 1, 0, 0 ] {
+            bytes.push(n as u8);
+            bytes.push((n >> 8) as u8);
+        }
+
+        bytes.extend(names.iter());
+        bytes.push(1);
+        if bytes.len() % 2 == 1 {
+            bytes.push(0);
+        }
+
+        for index in 0 .. MAX_COLOURS + 1 {
+            let n = if index == MAX_COLOURS { colours } else { -1 };
+            for byte in 0 .. size {
+                bytes.push((n >> (byte * 8)) as u8);
+            }
+        }
+
+        bytes
+    }
+
+    /// Builds a terminfo file with the given standard strings set, and the
+    /// given extended strings, but no numbers.
+    fn with_strings(strings: &[usize], extended: &[&str]) -> Vec<u8> {
+        let count = strings.iter().max().map_or(0, |&m| m + 1);
+        let mut bytes = Vec::new();
+        let push = |bytes: &mut Vec<u8>, n: i32| { bytes.push(n as u8); bytes.push((n >> 8) as u8); };
+
+        for &n in &[ MAGIC_LEGACY, 2, 0, 0, count as i32, 2 ] {
+            push(&mut bytes, n);
+        }
+
+        bytes.extend(b"t\0");
+        for index in 0 .. count {
+            push(&mut bytes, if strings.contains(&index) { 0 } else { -1 });
+        }
+        bytes.extend(b"x\0");
+
+        let names = extended.iter().map(|n| n.len() + 1).sum::<usize>();
+        for &n in &[ 0, 0, extended.len() as i32, extended.len() as i32 * 2, (extended.len() * 2 + names) as i32 ] {
+            push(&mut bytes, n);
+        }
+
+        for index in 0 .. extended.len() {
+            push(&mut bytes, index as i32 * 2);
+        }
+
+        let mut offset = 0;
+        for name in extended {
+            push(&mut bytes, offset);
+            offset += name.len() as i32 + 1;
+        }
+
+        for _ in extended {
+            bytes.extend(b"y\0");
+        }
+
+        for name in extended {
+            bytes.extend(name.as_bytes());
+            bytes.push(0);
+        }
+
+        bytes
+    }
+
+    #[test]
+    fn attributes() {
+        let attrs = parse_attributes(&with_strings(&[ BOLD, UNDERLINE, ITALICS ], &[ "Tc", "smxx", "rmxx" ])).unwrap();
+        assert!(attrs.bold && attrs.underline && attrs.italic && attrs.strikethrough);
+        assert!(!attrs.dimmed && !attrs.blink && !attrs.reverse && !attrs.hidden);
+    }
+
+    #[test]
+    fn no_strikethrough() {
+        let attrs = parse_attributes(&with_strings(&[ BOLD ], &[ "Tc" ])).unwrap();
+        assert!(attrs.bold && !attrs.italic && !attrs.strikethrough);
+    }
+
+    #[test]
+    fn legacy() {
+        assert_eq!(parse_max_colours(&terminfo(MAGIC_LEGACY, 256)), Some(256));
+    }
+
+    #[test]
+    fn extended() {
+        assert_eq!(parse_max_colours(&terminfo(MAGIC_32BIT, 0x100_0000)), Some(0x100_0000));
+    }
+
+    #[test]
+    fn absent() {
+        assert_eq!(parse_max_colours(&terminfo(MAGIC_LEGACY, -1)), Some(0));
+    }
+
+    #[test]
+    fn truncated() {
+        let mut bytes = terminfo(MAGIC_LEGACY, 8);
+        bytes.truncate(30);
+        assert_eq!(parse_max_colours(&bytes), None);
+    }
+
+    #[test]
+    fn not_terminfo() {
+        assert_eq!(parse_max_colours(b"#!/bin/sh\n"), None);
+    }
+}
@@ -0,0 +1,731 @@
+//! Reading colour themes from a file.
+//!
+//! A theme is a TOML file that gives a style to each part of the output,
+//! using either the same ANSI codes as `LS_COLORS` or words:
+//!
+//! ```toml
+//! preset = "gruvbox"
+//!
+//! [colours]
+//! directory = "1;34"
+//! punctuation = "38;5;244"
+//! git_deleted = "strikethrough red"
+//!
+//! [suffixes]
+//! tar = "31"
+//! "*.tar.gz" = "1;31"
+//!
+//! [names]
+//! Makefile = "4;33"
+//! "*.test.js" = "32"
+//!
+//! [age]
+//! newest = "5m"
+//! oldest = "1y"
+//!
+//! [size]
+//! mega = "100M"
+//! mode = "gradient"
+//!
+//! [columns]
+//! inode = "2"
+//! name = "1"
+//! plain = "date,size"
+//! ```
+//!
+//! The `preset` key picks one of the built-in themes to start from, unless
+//! one gets picked with `--theme` instead. The `[names]` section matches
+//! globs against whole file names, trying them in order before any of the
+//! suffixes. The `[age]` section sets the two
+//! ends of the age colour scale, as a number of seconds, minutes, hours,
+//! days, weeks, or years. The `[size]` section sets the sizes at which the
+//! size colour scale’s `kilo`, `mega`, `giga`, and `huge` styles start, and
+//! whether the scale is `stepped` or a `gradient`. The `[columns]` section
+//! gives a style to go underneath everything in a column of the details
+//! view, and its `plain` key lists columns to show without colours. Only the parts of TOML that a theme needs are understood: comments,
+//! section headers, and keys with string values.
+
+use std::env::var_os;
+use std::fs::File;
+use std::io::{Read, ErrorKind};
+use std::path::PathBuf;
+
+use glob;
+
+use output::Colours;
+use output::lsc::Pair;
+
+use options::Misfire;
+
+
+/// The path of the theme file to load, if there is one: the one named by
+/// `EXA_THEME`, or otherwise `exa/theme.toml` in the user’s config directory.
+/// The second value is whether it’s fine for the file not to exist.
+fn theme_path() -> Option<(PathBuf, bool)> {
+    if let Some(path) = var_os("EXA_THEME") {
+        return Some((PathBuf::from(path), false));
+    }
+
+    config_dir().map(|dir| (dir.join("theme.toml"), true))
+}
+
+/// exa’s directory in the user’s config directory, which is
+/// `$XDG_CONFIG_HOME/exa`, or `~/.config/exa` if that isn’t set.
+pub fn config_dir() -> Option<PathBuf> {
+    let config = match (var_os("XDG_CONFIG_HOME"), var_os("HOME")) {
+        (Some(config), _)   => PathBuf::from(config),
+        (None, Some(home))  => PathBuf::from(home).join(".config"),
+        (None, None)        => return None,
+    };
+
+    Some(config.join("exa"))
+}
+
+/// A theme file that’s been read, but not yet applied.
+pub struct Theme {
+    path: PathBuf,
+    text: String,
+}
+
+impl Theme {
+
+    /// Reads the user’s theme file, if they have one.
+    pub fn read() -> Result<Option<Theme>, Misfire> {
+        let (path, optional) = match theme_path() {
+            Some(p)  => p,
+            None     => return Ok(None),
+        };
+
+        let mut text = String::new();
+        if let Err(e) = File::open(&path).and_then(|mut f| f.read_to_string(&mut text)) {
+            if optional && e.kind() == ErrorKind::NotFound {
+                return Ok(None);
+            }
+
+            return Err(Misfire::BadTheme(path, e.to_string()));
+        }
+
+        Ok(Some(Theme { path, text }))
+    }
+
+    /// The name of the built-in theme this one starts from, if it has one.
+    pub fn preset(&self) -> Option<String> {
+        for line in self.text.lines() {
+            let line = strip_comment(line).trim();
+
+            if line.starts_with('[') {
+                break;
+            }
+            else if let Some(index) = line.find('=') {
+                if line[.. index].trim() == "preset" {
+                    return unquote(line[index + 1 ..].trim(), false);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Applies this theme on top of the given colours.
+    pub fn apply(&self, colours: &mut Colours) -> Result<(), Misfire> {
+        match apply_theme(&self.text, colours) {
+            Ok(())  => Ok(()),
+            Err(e)  => Err(Misfire::BadTheme(self.path.clone(), e)),
+        }
+    }
+}
+
+
+/// Which section of the file a line is in.
+#[derive(PartialEq, Debug, Copy, Clone)]
+enum Section {
+
+    /// Styles for parts of the output, named by the keys in `NAMES` or by
+    /// their two-letter codes. Keys before any section header go here.
+    Colours,
+
+    /// Styles for files whose names end with each key.
+    Suffixes,
+
+    /// Styles for files whose whole names match each key, as a glob.
+    Names,
+
+    /// The thresholds of the age colour scale.
+    Age,
+
+    /// The thresholds and mode of the size colour scale.
+    Size,
+
+    /// Styles for whole columns of the details view.
+    Columns,
+}
+
+/// Applies the text of a theme file to the given colours, returning a
+/// description of the first problem with it, if there is one.
+fn apply_theme(text: &str, colours: &mut Colours) -> Result<(), String> {
+    let mut section = Section::Colours;
+    let mut in_header = true;
+
+    for (number, line) in text.lines().enumerate() {
+        let line = strip_comment(line).trim();
+        let problem = |what: &str| format!("line {}: {}", number + 1, what);
+
+        if line.is_empty() {
+            continue;
+        }
+        else if line.starts_with('[') && line.ends_with(']') {
+            section = match line[1 .. line.len() - 1].trim() {
+                "colours" | "colors"  => Section::Colours,
+                "suffixes"            => Section::Suffixes,
+                "names"               => Section::Names,
+                "age"                 => Section::Age,
+                "size"                => Section::Size,
+                "columns"             => Section::Columns,
+                other                 => return Err(problem(&format!("unknown section {:?}", other))),
+            };
+            in_header = false;
+            continue;
+        }
+
+        let (key, value) = match line.find('=') {
+            Some(index)  => (unquote(line[.. index].trim(), true), unquote(line[index + 1 ..].trim(), false)),
+            None         => return Err(problem("expected key = \"value\"")),
+        };
+
+        let (key, value) = match (key, value) {
+            (Some(k), Some(v))  => (k, v),
+            (_, None)           => return Err(problem("the value should be a quoted string")),
+            (None, _)           => return Err(problem("unfinished quoted key")),
+        };
+
+        if in_header && key == "preset" {
+            continue;
+        }
+
+        // Styles can be written as words, such as `bold red on black`, as
+        // well as with the codes that `LS_COLORS` uses. Unknown names get
+        // complained about below instead.
+        let known_name = key.len() == 2 || NAMES.iter().any(|n| n.0 == key);
+        let value = match section {
+            Section::Age | Section::Size          => value,
+            Section::Columns if key == "plain"    => value,
+            Section::Colours if !known_name       => value,
+            _ => match style_codes(&value) {
+                Some(codes)  => codes,
+                None         => return Err(problem(&format!("invalid style {:?}", value))),
+            },
+        };
+
+        match section {
+            Section::Suffixes => {
+                let key = if key.starts_with('*') { key } else { format!("*.{}", key) };
+                colours.set_ls(&Pair { key: &key, value: &value });
+            },
+
+            Section::Names => {
+                let pattern = match glob::Pattern::new(&key) {
+                    Ok(p)   => p,
+                    Err(_)  => return Err(problem(&format!("invalid glob {:?}", key))),
+                };
+
+                let style = Pair { key: &key, value: &value }.to_style();
+                colours.names.push((pattern, style));
+            },
+
+            Section::Colours => {
+                let pair = Pair { key: &key, value: &value };
+
+                match NAMES.iter().find(|n| n.0 == key) {
+                    Some(&(_, code, Codes::Ls))   => colours.set_ls(&Pair { key: code, value: &value }),
+                    Some(&(_, code, Codes::Exa))  => { let _ = colours.set_exa(&Pair { key: code, value: &value }); },
+                    None if key.len() == 2        => if !colours.set_exa(&pair) { colours.set_ls(&pair) },
+                    None                          => return Err(problem(&format!("unknown colour {:?}", key))),
+                }
+            },
+
+            Section::Age => {
+                let seconds = match parse_duration(&value) {
+                    Some(s)  => s,
+                    None     => return Err(problem(&format!("invalid duration {:?}", value))),
+                };
+
+                match &*key {
+                    "newest"  => colours.age.newest_seconds = seconds,
+                    "oldest"  => colours.age.oldest_seconds = seconds,
+                    _         => return Err(problem(&format!("unknown age threshold {:?}", key))),
+                }
+            },
+
+            Section::Size => {
+                let index = match &*key {
+                    "mode" => {
+                        colours.size.scale_gradient = match &*value {
+                            "stepped"   => false,
+                            "gradient"  => true,
+                            _           => return Err(problem(&format!("invalid mode {:?}", value))),
+                        };
+                        continue;
+                    },
+                    "kilo"  => 0,
+                    "mega"  => 1,
+                    "giga"  => 2,
+                    "huge"  => 3,
+                    _       => return Err(problem(&format!("unknown size threshold {:?}", key))),
+                };
+
+                colours.size.scale_thresholds[index] = match parse_size(&value) {
+                    Some(bytes)  => bytes,
+                    None         => return Err(problem(&format!("invalid size {:?}", value))),
+                };
+            },
+
+            Section::Columns if key == "plain" => {
+                for name in value.split(',') {
+                    if !colours.plain_columns.add(name.trim()) {
+                        return Err(problem(&format!("unknown column {:?}", name.trim())));
+                    }
+                }
+            },
+
+            Section::Columns => {
+                let style = Pair { key: &key, value: &value }.to_style();
+                let columns = &mut colours.columns;
+
+                match &*key {
+                    "permissions"  => columns.permissions = style,
+                    "size"         => columns.size        = style,
+                    "date"         => columns.date        = style,
+                    "blocks"       => columns.blocks      = style,
+                    "user"         => columns.user        = style,
+                    "group"        => columns.group       = style,
+                    "links"        => columns.links       = style,
+                    "inode"        => columns.inode       = style,
+                    "git"          => columns.git         = style,
+                    "custom"       => columns.custom      = style,
+                    "name"         => columns.name        = style,
+                    _              => return Err(problem(&format!("unknown column {:?}", key))),
+                }
+            },
+        }
+    }
+
+    Ok(())
+}
+
+/// Turns a style written as words, such as `bold italic #ff8700 on blue`,
+/// into the codes that `LS_COLORS` uses. Styles that are already written
+/// as codes get left alone. Each colour can be one of the eight basic
+/// colours’ names, a number from the 256-colour palette, or `#rrggbb`, and
+/// a colour after `on` is the background.
+fn style_codes(value: &str) -> Option<String> {
+    if value.split(';').all(|code| code.starts_with('#') || code.chars().all(|c| c.is_digit(10))) {
+        return Some(value.into());
+    }
+
+    let mut codes = Vec::new();
+    let mut background = false;
+
+    for word in value.split_whitespace() {
+        let attribute = match word {
+            "bold"           => Some(1),
+            "dimmed"         => Some(2),
+            "italic"         => Some(3),
+            "underline"      => Some(4),
+            "blink"          => Some(5),
+            "reverse"        => Some(7),
+            "hidden"         => Some(8),
+            "strikethrough"  => Some(9),
+            _                => None,
+        };
+
+        if let Some(code) = attribute {
+            if background {
+                return None;
+            }
+
+            codes.push(code.to_string());
+            continue;
+        }
+
+        if word == "on" {
+            if background {
+                return None;
+            }
+
+            background = true;
+            continue;
+        }
+
+        let (basic, extended) = if background { (40, 48) } else { (30, 38) };
+        let colour = match word {
+            "black"             => format!("{}", basic),
+            "red"               => format!("{}", basic + 1),
+            "green"             => format!("{}", basic + 2),
+            "yellow"            => format!("{}", basic + 3),
+            "blue"              => format!("{}", basic + 4),
+            "purple"|"magenta"  => format!("{}", basic + 5),
+            "cyan"              => format!("{}", basic + 6),
+            "white"             => format!("{}", basic + 7),
+            hex if hex.len() == 7 && hex.starts_with('#') => {
+                let channel = |index: usize| u8::from_str_radix(&hex[index .. index + 2], 16).ok();
+                match (channel(1), channel(3), channel(5)) {
+                    (Some(r), Some(g), Some(b))  => format!("{};2;{};{};{}", extended, r, g, b),
+                    _                            => return None,
+                }
+            },
+            number => match number.parse::<u8>() {
+                Ok(n)   => format!("{};5;{}", extended, n),
+                Err(_)  => return None,
+            },
+        };
+
+        codes.push(colour);
+        background = false;
+    }
+
+    if background {
+        return None;
+    }
+
+    Some(codes.join(";"))
+}
+
+/// Reads a length of time, such as `90s`, `5m`, or `2y`, as a number of
+/// seconds. A number on its own is already in seconds.
+fn parse_duration(text: &str) -> Option<u64> {
+    let (number, unit) = match text.find(|c: char| !c.is_digit(10)) {
+        Some(index)  => text.split_at(index),
+        None         => (text, "s"),
+    };
+
+    let multiplier = match unit {
+        "s"  => 1,
+        "m"  => 60,
+        "h"  => 60 * 60,
+        "d"  => 24 * 60 * 60,
+        "w"  => 7 * 24 * 60 * 60,
+        "y"  => 365 * 24 * 60 * 60,
+        _    => return None,
+    };
+
+    number.parse::<u64>().ok().and_then(|n| n.checked_mul(multiplier))
+}
+
+/// Reads a number of bytes, such as `512`, `64K`, or `1G`, where each
+/// suffix is 1024 times the one before.
+fn parse_size(text: &str) -> Option<u64> {
+    let (number, unit) = match text.find(|c: char| !c.is_digit(10)) {
+        Some(index)  => text.split_at(index),
+        None         => (text, ""),
+    };
+
+    let shift = match unit {
+        ""   => 0,
+        "K"  => 10,
+        "M"  => 20,
+        "G"  => 30,
+        "T"  => 40,
+        _    => return None,
+    };
+
+    number.parse::<u64>().ok().and_then(|n| n.checked_mul(1 << shift))
+}
+
+/// Removes a comment from the end of a line, leaving any `#` characters
+/// inside quotes alone.
+pub fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+
+    for (index, c) in line.char_indices() {
+        match (quote, c) {
+            (None, '"') | (None, '\'')  => quote = Some(c),
+            (Some(q), _) if c == q      => quote = None,
+            (None, '#')                 => return &line[.. index],
+            _                           => {},
+        }
+    }
+
+    line
+}
+
+/// Reads a TOML string, which can be in double quotes with backslash
+/// escapes, or in single quotes without them. Keys can also be bare, if
+/// `bare` is true.
+pub fn unquote(text: &str, bare: bool) -> Option<String> {
+    if text.len() >= 2 && text.starts_with('\'') && text.ends_with('\'') {
+        return Some(text[1 .. text.len() - 1].to_owned());
+    }
+    else if !text.starts_with('"') {
+        let is_bare = text.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-');
+        return if bare && is_bare && !text.is_empty() { Some(text.to_owned()) } else { None };
+    }
+
+    let mut result = String::new();
+    let mut chars = text[1..].chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"'   => return if chars.next().is_none() { Some(result) } else { None },
+            '\\'  => match chars.next() {
+                         Some(c @ '"') | Some(c @ '\\')  => result.push(c),
+                         _                               => return None,
+                     },
+            c     => result.push(c),
+        }
+    }
+
+    None
+}
+
+
+/// Which set of codes a name in a theme file stands for.
+#[derive(PartialEq, Debug, Copy, Clone)]
+enum Codes { Ls, Exa }
+
+/// The names that can be given styles in a theme file, and the codes they
+/// stand for. A few codes mean different things in `LS_COLORS` and
+/// `EXA_COLORS`, so each name says which it’s from.
+static NAMES: &[(&str, &str, Codes)] = &[
+    ("normal",                 "fi", Codes::Ls),
+    ("directory",              "di", Codes::Ls),
+    ("symlink",                "ln", Codes::Ls),
+    ("pipe",                   "pi", Codes::Ls),
+    ("socket",                 "so", Codes::Ls),
+    ("device",                 "bd", Codes::Ls),
+    ("executable",             "ex", Codes::Ls),
+    ("broken_symlink",         "or", Codes::Ls),
+    ("missing_target",         "mi", Codes::Ls),
+    ("setuid",                 "su", Codes::Ls),
+    ("setgid",                 "sg", Codes::Ls),
+    ("sticky",                 "st", Codes::Ls),
+    ("sticky_other_writable",  "tw", Codes::Ls),
+    ("other_writable",         "ow", Codes::Ls),
+
+    ("image",       "im", Codes::Exa),
+    ("video",       "vi", Codes::Exa),
+    ("music",       "mu", Codes::Exa),
+    ("lossless",    "lo", Codes::Exa),
+    ("crypto",      "cr", Codes::Exa),
+    ("document",    "do", Codes::Exa),
+    ("compressed",  "co", Codes::Exa),
+    ("temp",        "tm", Codes::Exa),
+    ("compiled",    "cm", Codes::Exa),
+    ("immediate",   "bu", Codes::Exa),
+    ("special",     "sx", Codes::Exa),
+
+    ("user_read",           "ur", Codes::Exa),
+    ("user_write",          "uw", Codes::Exa),
+    ("user_execute_file",   "ux", Codes::Exa),
+    ("user_execute_other",  "ue", Codes::Exa),
+    ("group_read",          "gr", Codes::Exa),
+    ("group_write",         "gw", Codes::Exa),
+    ("group_execute",       "gx", Codes::Exa),
+    ("other_read",          "tr", Codes::Exa),
+    ("other_write",         "tw", Codes::Exa),
+    ("other_execute",       "tx", Codes::Exa),
+    ("special_user_file",   "sf", Codes::Exa),
+    ("special_other",       "sp", Codes::Exa),
+    ("attribute",           "xa", Codes::Exa),
+
+    ("size_number",   "sn", Codes::Exa),
+    ("size_unit",     "sb", Codes::Exa),
+    ("device_major",  "df", Codes::Exa),
+    ("device_minor",  "ds", Codes::Exa),
+    ("scale_byte",    "nb", Codes::Exa),
+    ("scale_kilo",    "nk", Codes::Exa),
+    ("scale_mega",    "nm", Codes::Exa),
+    ("scale_giga",    "ng", Codes::Exa),
+    ("scale_huge",    "nt", Codes::Exa),
+
+    ("user_you",           "uu", Codes::Exa),
+    ("user_someone_else",  "un", Codes::Exa),
+    ("group_yours",        "gu", Codes::Exa),
+    ("group_not_yours",    "gn", Codes::Exa),
+    ("links",              "lc", Codes::Exa),
+    ("multi_link_file",    "lm", Codes::Exa),
+    ("duplicate_file",     "ld", Codes::Exa),
+    ("owned_by_someone_else",  "fo", Codes::Exa),
+    ("owned_by_root",          "fr", Codes::Exa),
+
+    ("git_new",         "ga", Codes::Exa),
+    ("git_modified",    "gm", Codes::Exa),
+    ("git_deleted",     "gd", Codes::Exa),
+    ("git_renamed",     "gv", Codes::Exa),
+    ("git_typechange",  "gt", Codes::Exa),
+    ("git_ignored",     "gi", Codes::Exa),
+
+    ("punctuation",   "xx", Codes::Exa),
+    ("tree",          "tl", Codes::Exa),
+    ("date",          "da", Codes::Exa),
+    ("inode",         "in", Codes::Exa),
+    ("blocks",        "bl", Codes::Exa),
+    ("header",        "hd", Codes::Exa),
+    ("link_arrow",        "la", Codes::Exa),
+    ("column_separator",  "cs", Codes::Exa),
+    ("symlink_path",  "lp", Codes::Exa),
+    ("broken_arrow",  "ba", Codes::Exa),
+    ("control_char",  "cc", Codes::Exa),
+    ("age_newest",    "an", Codes::Exa),
+    ("age_oldest",    "ao", Codes::Exa),
+];
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use output::ColourScale;
+    use ansi_term::Style;
+    use ansi_term::Colour::*;
+
+    fn themed(text: &str) -> Result<Colours, String> {
+        let mut colours = Colours::colourful(ColourScale::default());
+        apply_theme(text, &mut colours).map(|_| colours)
+    }
+
+    #[test]
+    fn names() {
+        let colours = themed("directory = \"1;33\"\nother_write = '31' # comment").unwrap();
+        assert_eq!(colours.filetypes.directory, Yellow.bold());
+        assert_eq!(colours.perms.other_write, Red.normal());
+    }
+
+    #[test]
+    fn codes() {
+        let colours = themed("[colours]\ntl = \"32\"\ndi = \"35\"").unwrap();
+        assert_eq!(colours.tree, Green.normal());
+        assert_eq!(colours.filetypes.directory, Purple.normal());
+    }
+
+    #[test]
+    fn suffixes() {
+        let colours = themed("[suffixes]\ntar = \"31\"\n\"*README\" = \"4\"").unwrap();
+        assert_eq!(colours.suffix_style("a.tar"), Some(Red.normal()));
+        assert_eq!(colours.suffix_style("README"), Some(Style::default().underline()));
+    }
+
+    #[test]
+    fn name_globs() {
+        let colours = themed("[names]\nMakefile = \"4\"\n\"README*\" = \"31\"\n\"*\" = \"32\"").unwrap();
+        assert_eq!(colours.name_style("Makefile"), Some(Style::default().underline()));
+        assert_eq!(colours.name_style("README.md"), Some(Red.normal()));
+        assert_eq!(colours.name_style("makefile"), Some(Green.normal()));
+    }
+
+    #[test]
+    fn invalid_glob() {
+        assert_eq!(themed("[names]\n\"[a\" = \"1\"").unwrap_err(), "line 2: invalid glob \"[a\"");
+    }
+
+    #[test]
+    fn hash_in_quotes() {
+        let colours = themed("[suffixes]\n\"*#\" = \"31\"").unwrap();
+        assert_eq!(colours.suffix_style("file#"), Some(Red.normal()));
+    }
+
+    #[test]
+    fn preset() {
+        let theme = Theme { path: PathBuf::new(), text: "# mine\npreset = \"dracula\"\ntree = \"31\"".into() };
+        assert_eq!(theme.preset(), Some("dracula".into()));
+        assert!(themed(&theme.text).is_ok());
+    }
+
+    #[test]
+    fn preset_in_section() {
+        assert_eq!(themed("[colours]\npreset = \"dracula\"").unwrap_err(), "line 2: unknown colour \"preset\"");
+    }
+
+    #[test]
+    fn unknown_name() {
+        assert_eq!(themed("\n\nfolder = \"34\"").unwrap_err(), "line 3: unknown colour \"folder\"");
+    }
+
+    #[test]
+    fn unquoted_value() {
+        assert_eq!(themed("directory = 34").unwrap_err(), "line 1: the value should be a quoted string");
+    }
+
+    #[test]
+    fn age() {
+        let colours = themed("age_oldest = \"38;5;90\"\n[age]\nnewest = \"90s\"\noldest = \"2w\"").unwrap();
+        assert_eq!(colours.age.oldest, Fixed(90).normal());
+        assert_eq!(colours.age.newest_seconds, 90);
+        assert_eq!(colours.age.oldest_seconds, 14 * 24 * 60 * 60);
+    }
+
+    #[test]
+    fn bad_duration() {
+        assert_eq!(themed("[age]\nnewest = \"soon\"").unwrap_err(), "line 2: invalid duration \"soon\"");
+    }
+
+    #[test]
+    fn size() {
+        let colours = themed("[size]\nkilo = \"100K\"\nhuge = \"512\"\nmode = \"gradient\"").unwrap();
+        assert_eq!(colours.size.scale_thresholds, [ 100 << 10, 1 << 20, 1 << 30, 512 ]);
+        assert!(colours.size.scale_gradient);
+    }
+
+    #[test]
+    fn bad_size() {
+        assert_eq!(themed("[size]\nmega = \"1MB\"").unwrap_err(), "line 2: invalid size \"1MB\"");
+    }
+
+    #[test]
+    fn columns() {
+        let colours = themed("[columns]\ninode = \"2\"\nname = \"1;4\"").unwrap();
+        assert_eq!(colours.columns.inode, Style::default().dimmed());
+        assert_eq!(colours.columns.name, Style::default().bold().underline());
+        assert_eq!(colours.columns.size, Style::default());
+    }
+
+    #[test]
+    fn plain_columns() {
+        let colours = themed("[columns]\nplain = \"date, size\"").unwrap();
+        assert!(colours.plain_columns.date);
+        assert!(colours.plain_columns.size);
+        assert!(!colours.plain_columns.name);
+    }
+
+    #[test]
+    fn unknown_plain_column() {
+        assert_eq!(themed("[columns]\nplain = \"date,icons\"").unwrap_err(), "line 2: unknown column \"icons\"");
+    }
+
+    #[test]
+    fn unknown_column() {
+        assert_eq!(themed("[columns]\nicons = \"1\"").unwrap_err(), "line 2: unknown column \"icons\"");
+    }
+
+    #[test]
+    fn style_words() {
+        assert_eq!(style_codes("1;34"),                         Some("1;34".into()));
+        assert_eq!(style_codes("#ff8700"),                      Some("#ff8700".into()));
+        assert_eq!(style_codes("bold blue"),                    Some("1;34".into()));
+        assert_eq!(style_codes("strikethrough 244 on #000080"), Some("9;38;5;244;48;2;0;0;128".into()));
+        assert_eq!(style_codes("italic on red"),                Some("3;41".into()));
+    }
+
+    #[test]
+    fn bad_style_words() {
+        assert_eq!(style_codes("bold mauve"), None);
+        assert_eq!(style_codes("red on"),     None);
+        assert_eq!(style_codes("on bold"),    None);
+        assert_eq!(style_codes("300"),        Some("300".into()));
+    }
+
+    #[test]
+    fn words_in_theme() {
+        let colours = themed("[colours]\ngit_deleted = \"strikethrough red\"\n[suffixes]\ntar = \"underline on yellow\"").unwrap();
+        assert_eq!(colours.git.deleted, Red.strikethrough());
+        assert_eq!(colours.suffix_style("a.tar"), Some(Style::default().underline().on(Yellow)));
+    }
+
+    #[test]
+    fn invalid_style() {
+        assert_eq!(themed("tree = \"very grey\"").unwrap_err(), "line 1: invalid style \"very grey\"");
+    }
+
+    #[test]
+    fn unknown_section() {
+        assert_eq!(themed("[icons]").unwrap_err(), "line 1: unknown section \"icons\"");
+    }
+}
@@ -1,12 +1,19 @@
 use std::env::var_os;
+use std::ffi::{OsStr, OsString};
+use std::sync::Arc;
 
-use output::Colours;
-use output::{View, Mode, grid, details};
+use users::get_current_uid;
+
+use output::{Colours, Attributes, ColourDepth, ColourScale, PlainColumns, LSColors, COLUMN_NAMES, PRESETS};
+use output::{View, Mode, grid, details, porcelain};
 use output::table::{TimeTypes, Environment, SizeFormat, Options as TableOptions};
-use output::file_name::{Classify, FileStyle};
+use output::table::{CustomColumn, XattrColumn};
+use output::file_name::{Classify, FileStyle, Owners};
 use output::time::TimeFormat;
 
 use options::{flags, Misfire};
+use options::terminfo;
+use options::theme::Theme;
 use options::parser::MatchedFlags;
 
 use fs::feature::xattr;
@@ -55,6 +62,10 @@ impl Mode {
                 }
             }
 
+            if matches.get(&flags::XATTR_COLUMN)?.is_some() {
+                return Err(Useless(&flags::XATTR_COLUMN, false, &flags::LONG));
+            }
+
             if cfg!(feature="git") && matches.has(&flags::GIT) {
                 Err(Useless(&flags::GIT, false, &flags::LONG))
             }
@@ -66,8 +77,27 @@ impl Mode {
             }
         };
 
+        // These would all be resolved one way or another, but when being
+        // strict, they’re complained about instead, as long as they were
+        // given on the command-line.
+        let strict_scan = || {
+            let given = matches.command_line();
+
+            for &(a, b) in &[ (&flags::GRID, &flags::ONE_LINE), (&flags::GRID, &flags::TREE), (&flags::ONE_LINE, &flags::TREE) ] {
+                if given.has(a) && given.has(b) {
+                    return Err(Conflict(a, b));
+                }
+            }
+
+            if !matches.has(&flags::LONG) && given.get(&flags::TIME_STYLE)?.is_some() {
+                return Err(Useless(&flags::TIME_STYLE, false, &flags::LONG));
+            }
+
+            Ok(())
+        };
+
         let other_options_scan = || {
-            if let Some(width) = TerminalWidth::deduce()?.width() {
+            if let Some(width) = TerminalWidth::deduce(matches)?.width() {
                 if matches.has(&flags::ONE_LINE) {
                     if matches.has(&flags::ACROSS) {
                         Err(Useless(&flags::ACROSS, true, &flags::ONE_LINE))
@@ -114,6 +144,21 @@ impl Mode {
             }
         };
 
+        if matches.is_strict() {
+            strict_scan()?;
+        }
+
+        if let Some(format) = porcelain::Format::deduce(matches)? {
+            for option in &[ &flags::LONG, &flags::GRID, &flags::ONE_LINE, &flags::ACROSS, &flags::TREE ] {
+                if matches.has(option) {
+                    return Err(Conflict(&flags::PORCELAIN, *option));
+                }
+            }
+
+            long_options_scan()?;
+            return Ok(Mode::Porcelain(format));
+        }
+
         if matches.has(&flags::LONG) {
             let details = long()?;
             if matches.has(&flags::GRID) {
@@ -134,6 +179,29 @@ impl Mode {
 }
 
 
+impl porcelain::Format {
+
+    /// Determine which machine-readable format to print files in, if the
+    /// user asked for one instead of a view meant for people.
+    fn deduce(matches: &MatchedFlags) -> Result<Option<porcelain::Format>, Misfire> {
+        let word = match matches.get(&flags::PORCELAIN)? {
+            Some(w)  => w,
+            None     => return Ok(None),
+        };
+
+        if word == "fzf" {
+            Ok(Some(porcelain::Format::Fzf))
+        }
+        else if word == "v1" {
+            Ok(Some(porcelain::Format::V1))
+        }
+        else {
+            Err(Misfire::bad_argument(&flags::PORCELAIN, word, flags::FORMATS))
+        }
+    }
+}
+
+
 /// The width of the terminal requested by the user.
 #[derive(PartialEq, Debug)]
 enum TerminalWidth {
@@ -151,15 +219,20 @@ enum TerminalWidth {
 impl TerminalWidth {
 
     /// Determine a requested terminal width from the command-line arguments.
+    /// With `--deterministic`, the terminal’s own width gets ignored, and
+    /// the width is always the same unless `COLUMNS` is set.
     ///
     /// Returns an error if a requested width doesn’t parse to an integer.
-    fn deduce() -> Result<TerminalWidth, Misfire> {
+    fn deduce(matches: &MatchedFlags) -> Result<TerminalWidth, Misfire> {
         if let Some(columns) = var_os("COLUMNS").and_then(|s| s.into_string().ok()) {
             match columns.parse() {
                 Ok(width)  => Ok(TerminalWidth::Set(width)),
                 Err(e)     => Err(Misfire::FailedParse(e)),
             }
         }
+        else if matches.has(&flags::DETERMINISTIC) {
+            Ok(TerminalWidth::Set(DETERMINISTIC_WIDTH))
+        }
         else if let Some(width) = *TERM_WIDTH {
             Ok(TerminalWidth::Terminal(width))
         }
@@ -178,10 +251,15 @@ impl TerminalWidth {
 }
 
 
+/// The width to lay grids out in with `--deterministic`, when `COLUMNS`
+/// isn’t set.
+const DETERMINISTIC_WIDTH: usize = 80;
+
+
 impl TableOptions {
     fn deduce(matches: &MatchedFlags) -> Result<Self, Misfire> {
         Ok(TableOptions {
-            env:         Environment::load_all(),
+            env:         if matches.has(&flags::DETERMINISTIC) { Environment::fixed() } else { Environment::load_all() },
             time_format: TimeFormat::deduce(matches)?,
             size_format: SizeFormat::deduce(matches)?,
             time_types:  TimeTypes::deduce(matches)?,
@@ -190,11 +268,25 @@ impl TableOptions {
             blocks: matches.has(&flags::BLOCKS),
             group:  matches.has(&flags::GROUP),
             git:    cfg!(feature="git") && matches.has(&flags::GIT),
+            custom_columns: deduce_custom_columns(matches),
         })
     }
 }
 
 
+/// Determine the custom columns to add to the table. Each `--xattr-column`
+/// argument adds a column for that extended attribute, in the order given.
+fn deduce_custom_columns(matches: &MatchedFlags) -> Vec<Arc<CustomColumn>> {
+    let mut columns: Vec<Arc<CustomColumn>> = Vec::new();
+
+    for name in matches.get_all(&flags::XATTR_COLUMN) {
+        columns.push(Arc::new(XattrColumn { name: name.to_string_lossy().into_owned() }));
+    }
+
+    columns
+}
+
+
 impl SizeFormat {
 
     /// Determine which file size to use in the file size column based on
@@ -222,16 +314,24 @@ impl SizeFormat {
 impl TimeFormat {
 
     /// Determine how time should be formatted in timestamp columns.
+    ///
+    /// The default and ISO formats depend on the current year, and the
+    /// default one on the locale too, so with `--deterministic`, they get
+    /// replaced by the number of seconds since the epoch.
     fn deduce(matches: &MatchedFlags) -> Result<TimeFormat, Misfire> {
         pub use output::time::{DefaultFormat, ISOFormat};
-        const STYLES: &[&str] = &["default", "long-iso", "full-iso", "iso"];
 
-        let word = match matches.get(&flags::TIME_STYLE) {
-            Some(w) => w,
-            None    => return Ok(TimeFormat::DefaultFormat(DefaultFormat::new())),
+        let deterministic = matches.has(&flags::DETERMINISTIC);
+        let word = match matches.get(&flags::TIME_STYLE)? {
+            Some(w)                => w,
+            None if deterministic  => return Ok(TimeFormat::Epoch),
+            None                   => return Ok(TimeFormat::DefaultFormat(DefaultFormat::new())),
         };
 
-        if word == "default" {
+        if deterministic && (word == "default" || word == "iso") {
+            Ok(TimeFormat::Epoch)
+        }
+        else if word == "default" {
             Ok(TimeFormat::DefaultFormat(DefaultFormat::new()))
         }
         else if word == "iso" {
@@ -244,14 +344,12 @@ impl TimeFormat {
             Ok(TimeFormat::FullISO)
         }
         else {
-            Err(Misfire::bad_argument(&flags::TIME_STYLE, word, STYLES))
+            Err(Misfire::bad_argument(&flags::TIME_STYLE, word, flags::TIME_STYLES))
         }
     }
 }
 
 
-static TIMES: &[&str] = &["modified", "accessed", "created"];
-
 impl TimeTypes {
 
     /// Determine which of a file’s time fields should be displayed for it
@@ -262,36 +360,42 @@ impl TimeTypes {
     /// `--time=modified`). An error is signaled if both ways are used.
     ///
     /// It’s valid to show more than one column by passing in more than one
-    /// option, but passing *no* options means that the user just wants to
-    /// see the default set.
-    fn deduce(matches: &MatchedFlags) -> Result<TimeTypes, Misfire> {
-        let possible_word = matches.get(&flags::TIME);
+    /// option, or a comma-separated list of fields, but passing *no*
+    /// options means that the user just wants to see the default set.
+    pub fn deduce(matches: &MatchedFlags) -> Result<TimeTypes, Misfire> {
+        let words = matches.get_all(&flags::TIME);
         let modified = matches.has(&flags::MODIFIED);
         let created  = matches.has(&flags::CREATED);
         let accessed = matches.has(&flags::ACCESSED);
 
-        if let Some(word) = possible_word {
+        if !words.is_empty() {
             if modified {
-                Err(Misfire::Useless(&flags::MODIFIED, true, &flags::TIME))
+                return Err(Misfire::Useless(&flags::MODIFIED, true, &flags::TIME));
             }
             else if created {
-                Err(Misfire::Useless(&flags::CREATED, true, &flags::TIME))
+                return Err(Misfire::Useless(&flags::CREATED, true, &flags::TIME));
             }
             else if accessed {
-                Err(Misfire::Useless(&flags::ACCESSED, true, &flags::TIME))
-            }
-            else if word == "mod" || word == "modified" {
-                Ok(TimeTypes { accessed: false, modified: true,  created: false })
+                return Err(Misfire::Useless(&flags::ACCESSED, true, &flags::TIME));
             }
-            else if word == "acc" || word == "accessed" {
-                Ok(TimeTypes { accessed: true,  modified: false, created: false })
-            }
-            else if word == "cr" || word == "created" {
-                Ok(TimeTypes { accessed: false, modified: false, created: true  })
-            }
-            else {
-                Err(Misfire::bad_argument(&flags::TIME, word, TIMES))
+
+            let mut types = TimeTypes { accessed: false, modified: false, created: false };
+            for word in words {
+                if word == "mod" || word == "modified" {
+                    types.modified = true;
+                }
+                else if word == "acc" || word == "accessed" {
+                    types.accessed = true;
+                }
+                else if word == "cr" || word == "created" {
+                    types.created = true;
+                }
+                else {
+                    return Err(Misfire::bad_argument(&flags::TIME, word, flags::TIMES));
+                }
             }
+
+            Ok(types)
         }
         else if modified || created || accessed {
             Ok(TimeTypes { accessed, modified, created })
@@ -333,11 +437,9 @@ impl TerminalColours {
 
     /// Determine which terminal colour conditions to use.
     fn deduce(matches: &MatchedFlags) -> Result<TerminalColours, Misfire> {
-        const COLOURS: &[&str] = &["always", "auto", "never"];
-
-        let word = match matches.get(&flags::COLOR).or_else(|| matches.get(&flags::COLOUR)) {
+        let word = match matches.get_where(|f| f.matches(&flags::COLOR) || f.matches(&flags::COLOUR))? {
             Some(w) => w,
-            None    => return Ok(TerminalColours::default()),
+            None    => return Ok(TerminalColours::from_env(var_os("NO_COLOR"), var_os("CLICOLOR"), var_os("CLICOLOR_FORCE"))),
         };
 
         if word == "always" {
@@ -350,20 +452,137 @@ impl TerminalColours {
             Ok(TerminalColours::Never)
         }
         else {
-            Err(Misfire::bad_argument(&flags::COLOR, word, COLOURS))
+            Err(Misfire::bad_argument(&flags::COLOR, word, flags::COLOURS))
+        }
+    }
+
+    /// Determine which terminal colour conditions to use when there’s no
+    /// command-line option, from the environment variables that other
+    /// programs use for the same thing: `NO_COLOR` being set to anything
+    /// turns colours off, `CLICOLOR_FORCE` being set to anything but `0`
+    /// turns them on even when output isn’t going to a terminal, and
+    /// `CLICOLOR` being set to `0` turns them off.
+    fn from_env(no_color: Option<OsString>, clicolor: Option<OsString>, clicolor_force: Option<OsString>) -> TerminalColours {
+        let is_set = |var: &Option<OsString>| var.as_ref().map_or(false, |v| !v.is_empty());
+
+        if is_set(&no_color) {
+            TerminalColours::Never
+        }
+        else if is_set(&clicolor_force) && clicolor_force != Some(OsString::from("0")) {
+            TerminalColours::Always
+        }
+        else if clicolor == Some(OsString::from("0")) {
+            TerminalColours::Never
+        }
+        else {
+            TerminalColours::default()
         }
     }
 }
 
 
+impl PlainColumns {
+
+    /// Determine which columns to show without colours, from a
+    /// comma-separated list of their names. These replace any columns
+    /// made plain by the theme.
+    fn deduce(matches: &MatchedFlags) -> Result<Option<PlainColumns>, Misfire> {
+        let words = match matches.get(&flags::PLAIN_COLUMNS)? {
+            Some(w)  => w,
+            None     => return Ok(None),
+        };
+
+        let mut plain = PlainColumns::default();
+        for word in words.to_string_lossy().split(',') {
+            if !plain.add(word) {
+                return Err(Misfire::bad_argument(&flags::PLAIN_COLUMNS, OsStr::new(word), COLUMN_NAMES));
+            }
+        }
+
+        Ok(Some(plain))
+    }
+}
+
+
 impl Colours {
     fn deduce(matches: &MatchedFlags) -> Result<Colours, Misfire> {
         use self::TerminalColours::*;
 
-        let tc = TerminalColours::deduce(matches)?;
+        let mut tc = TerminalColours::deduce(matches)?;
+        let depth = ColourDepth::deduce(matches)?;
+        let scale = ColourScale::deduce(matches)?;
+        let plain = PlainColumns::deduce(matches)?;
+
+        // The output of the fzf format always goes to another program, so
+        // it gets coloured unless colours were turned off, for that program
+        // to show.
+        if tc == Automatic && porcelain::Format::deduce(matches)? == Some(porcelain::Format::Fzf) {
+            tc = Always;
+        }
+
         if tc == Always || (tc == Automatic && TERM_WIDTH.is_some()) {
-            let scale = matches.has(&flags::COLOR_SCALE) || matches.has(&flags::COLOUR_SCALE);
-            Ok(Colours::colourful(scale))
+            let theme = Theme::read()?;
+
+            // A preset picked on the command line wins over the one in the
+            // theme file, which the rest of the file then gets applied on.
+            let mut colours = match matches.get(&flags::THEME)? {
+                Some(word) => match word.to_str().and_then(|w| Colours::preset(w, scale)) {
+                    Some(colours)  => colours,
+                    None           => return Err(Misfire::bad_argument(&flags::THEME, word, PRESETS)),
+                },
+                None => match theme.as_ref().and_then(|t| t.preset()) {
+                    Some(name) => match Colours::preset(&name, scale) {
+                        Some(colours)  => colours,
+                        None           => return Err(Misfire::BadValue(&flags::THEME, name.into(), "one of the built-in themes")),
+                    },
+                    None => Colours::colourful(scale),
+                },
+            };
+
+            if let Some(lsc) = var_os("LS_COLORS") {
+                let lsc = lsc.to_string_lossy();
+                LSColors(&lsc).each_pair(|pair| colours.set_ls(&pair));
+            }
+
+            if let Some(theme) = theme {
+                theme.apply(&mut colours)?;
+            }
+
+            // `EXA_COLORS` can start with `reset` to throw away exa’s own
+            // colours, so a theme can be built up from nothing.
+            if let Some(exa) = var_os("EXA_COLORS") {
+                let exa = exa.to_string_lossy();
+                if exa.split(':').next() == Some("reset") {
+                    colours = Colours { scale, .. Colours::plain() };
+                }
+
+                LSColors(&exa).each_pair(|pair| {
+                    if !colours.set_exa(&pair) {
+                        colours.set_ls(&pair);
+                    }
+                });
+            }
+
+            if let Some(plain) = plain {
+                colours.plain_columns = plain;
+            }
+
+            // Unless the user says otherwise, `--colour=always` should
+            // still print colours and attributes when the terminal doesn’t
+            // seem to have any, as the output is probably going somewhere
+            // else.
+            let (depth, attributes) = match depth {
+                Some(depth)  => (depth, Attributes::detect()),
+                None         => match ColourDepth::detect() {
+                    ColourDepth::NoColours if tc == Always  => (ColourDepth::Fixed256, Attributes::default()),
+                    depth                                   => (depth, Attributes::detect()),
+                },
+            };
+
+            colours.limit_depth(depth);
+            colours.limit_attributes(attributes);
+
+            Ok(colours)
         }
         else {
             Ok(Colours::plain())
@@ -373,11 +592,117 @@ impl Colours {
 
 
 
+impl ColourScale {
+
+    /// Determine which parts of the output to colour on a scale. The
+    /// option on its own means file sizes, as it always has; otherwise,
+    /// it takes a comma-separated list.
+    fn deduce(matches: &MatchedFlags) -> Result<ColourScale, Misfire> {
+        let words = match matches.get_optional_where(|f| f.matches(&flags::COLOR_SCALE) || f.matches(&flags::COLOUR_SCALE))? {
+            Some(Some(words))  => words,
+            Some(None)         => return Ok(ColourScale { size: true, .. ColourScale::default() }),
+            None               => return Ok(ColourScale::default()),
+        };
+
+        let mut scale = ColourScale::default();
+        for word in words.to_string_lossy().split(',') {
+            match word {
+                "size"       => scale.size = true,
+                "age"        => scale.age = true,
+                "age-names"  => scale.age_names = true,
+                "blocks"     => scale.blocks = true,
+                "links"      => scale.links = true,
+                _            => return Err(Misfire::bad_argument(&flags::COLOR_SCALE, words, flags::SCALES)),
+            }
+        }
+
+        Ok(scale)
+    }
+}
+
+
+impl ColourDepth {
+
+    /// Determine how many colours the user has said the terminal can
+    /// show, if they’ve said so.
+    fn deduce(matches: &MatchedFlags) -> Result<Option<ColourDepth>, Misfire> {
+        let word = match matches.get_where(|f| f.matches(&flags::COLOR_DEPTH) || f.matches(&flags::COLOUR_DEPTH))? {
+            Some(w) => w,
+            None    => return Ok(None),
+        };
+
+        if word == "truecolor" || word == "truecolour" || word == "24bit" {
+            Ok(Some(ColourDepth::TrueColour))
+        }
+        else if word == "256" {
+            Ok(Some(ColourDepth::Fixed256))
+        }
+        else if word == "16" || word == "8" {
+            Ok(Some(ColourDepth::Basic))
+        }
+        else if word == "none" {
+            Ok(Some(ColourDepth::NoColours))
+        }
+        else {
+            Err(Misfire::bad_argument(&flags::COLOR_DEPTH, word, flags::DEPTHS))
+        }
+    }
+
+    /// Work out how many colours the terminal can show. Terminals that can
+    /// show 24-bit colours say so in `COLORTERM`; for the rest, the number
+    /// comes from the terminfo entry for `TERM`. Terminals that exa can’t
+    /// find out anything about get the 256 colours it has always used.
+    fn detect() -> ColourDepth {
+        match var_os("COLORTERM") {
+            Some(ref ct) if ct == "truecolor" || ct == "24bit"  => return ColourDepth::TrueColour,
+            _                                                 => {},
+        }
+
+        let term = match var_os("TERM") {
+            Some(term) => term.to_string_lossy().into_owned(),
+            None       => return ColourDepth::NoColours,
+        };
+
+        if term.is_empty() || term == "dumb" {
+            return ColourDepth::NoColours;
+        }
+
+        match terminfo::max_colours(&term) {
+            Some(n) if n >= 0x100_0000  => ColourDepth::TrueColour,
+            Some(n) if n >= 256         => ColourDepth::Fixed256,
+            Some(n) if n >= 8           => ColourDepth::Basic,
+            Some(_)                     => ColourDepth::NoColours,
+            None                        => ColourDepth::Fixed256,
+        }
+    }
+}
+
+
+impl Attributes {
+
+    /// Works out which text attributes the terminal can show from its
+    /// terminfo entry. Terminals without one are assumed to be able to
+    /// show all of them, as the output is probably going somewhere else.
+    fn detect() -> Attributes {
+        let term = var_os("TERM").map(|t| t.to_string_lossy().into_owned()).unwrap_or_default();
+        terminfo::attributes(&term).unwrap_or_default()
+    }
+}
+
+
 impl FileStyle {
     fn deduce(matches: &MatchedFlags) -> FileStyle {
         let classify = Classify::deduce(matches);
         let exts = FileExtensions;
-        FileStyle { classify, exts }
+        let owners = Owners::deduce(matches);
+        FileStyle { classify, exts, owners }
+    }
+}
+
+impl Owners {
+    fn deduce(matches: &MatchedFlags) -> Owners {
+        if matches.has(&flags::HIGHLIGHT_OWNERS) { Owners::Highlight(get_current_uid()) }
+                                            else { Owners::Ignore }
     }
 }
 
@@ -409,6 +734,7 @@ mod test {
     use super::*;
     use std::ffi::OsString;
     use options::flags;
+    use options::parser::{Flag, ParseError};
 
     pub fn os(input: &'static str) -> OsString {
         let mut os = OsString::new();
@@ -418,17 +744,24 @@ mod test {
 
     macro_rules! test {
         ($name:ident: $type:ident <- $inputs:expr => $result:expr) => {
+            test!($name: $type <- $inputs; UseLastArguments => $result);
+        };
+
+        ($name:ident: $type:ident <- $inputs:expr; $strictness:ident => $result:expr) => {
             #[test]
             fn $name() {
-                use options::parser::{Args, Arg};
+                use options::parser::{Args, Arg, Strictness};
                 use std::ffi::OsString;
 
                 static TEST_ARGS: &[&Arg] = &[ &flags::BINARY, &flags::BYTES,
-                                               &flags::TIME, &flags::MODIFIED, &flags::CREATED, &flags::ACCESSED ];
+                                               &flags::TIME, &flags::MODIFIED, &flags::CREATED, &flags::ACCESSED,
+                                               &flags::COLOR, &flags::COLOUR, &flags::COLOR_DEPTH, &flags::COLOUR_DEPTH,
+                                               &flags::COLOR_SCALE, &flags::COLOUR_SCALE, &flags::PLAIN_COLUMNS,
+                                               &flags::PORCELAIN ];
 
                 let bits = $inputs.as_ref().into_iter().map(|&o| os(o)).collect::<Vec<OsString>>();
-                let results = Args(TEST_ARGS).parse(bits.iter());
-                assert_eq!($type::deduce(&results.unwrap().flags), $result);
+                let results = Args(TEST_ARGS).parse(bits.iter(), Strictness::$strictness).map_err(Misfire::InvalidOptions);
+                assert_eq!(results.and_then(|r| $type::deduce(&r.flags)), $result);
             }
         };
     }
@@ -444,6 +777,83 @@ mod test {
     }
 
 
+    mod porcelain_formats {
+        use super::*;
+        use output::porcelain::Format;
+
+        test!(empty:    Format <- []                    => Ok(None));
+        test!(fzf:      Format <- ["--porcelain=fzf"]   => Ok(Some(Format::Fzf)));
+        test!(v1:       Format <- ["--porcelain=v1"]    => Ok(Some(Format::V1)));
+        test!(unknown:  Format <- ["--porcelain=json"]  => Err(Misfire::InvalidOptions(ParseError::BadValue { flag: Flag::Long("porcelain"), value: os("json"), values: flags::FORMATS })));
+    }
+
+
+    mod terminal_colours {
+        use super::*;
+
+        macro_rules! test {
+            ($name:ident: $no_color:expr, $clicolor:expr, $clicolor_force:expr => $result:expr) => {
+                #[test]
+                fn $name() {
+                    assert_eq!(TerminalColours::from_env($no_color.map(os), $clicolor.map(os), $clicolor_force.map(os)), $result);
+                }
+            };
+        }
+
+        test!(nothing:       None,       None,       None       => TerminalColours::Automatic);
+        test!(no_color:      Some("1"),  None,       Some("1")  => TerminalColours::Never);
+        test!(empty:         Some(""),   None,       None       => TerminalColours::Automatic);
+        test!(forced:        None,       Some("0"),  Some("1")  => TerminalColours::Always);
+        test!(not_forced:    None,       None,       Some("0")  => TerminalColours::Automatic);
+        test!(clicolor_off:  None,       Some("0"),  None       => TerminalColours::Never);
+        test!(clicolor_on:   None,       Some("1"),  None       => TerminalColours::Automatic);
+    }
+
+
+    mod terminal_colour_flags {
+        use super::*;
+
+        test!(bare:    TerminalColours <- ["--colour"]             => Ok(TerminalColours::Always));
+        test!(never:   TerminalColours <- ["--color=never"]        => Ok(TerminalColours::Never));
+        test!(auto:    TerminalColours <- ["--colour=automatic"]   => Ok(TerminalColours::Automatic));
+        test!(bad:     TerminalColours <- ["--color=sometimes"]    => Err(Misfire::InvalidOptions(ParseError::BadValue { flag: Flag::Long("color"), value: os("sometimes"), values: flags::COLOURS })));
+    }
+
+
+    mod colour_scales {
+        use super::*;
+
+        test!(empty:      ColourScale <- []                             => Ok(ColourScale::default()));
+        test!(size:       ColourScale <- ["--colour-scale"]             => Ok(ColourScale { size: true, .. ColourScale::default() }));
+        test!(age:        ColourScale <- ["--color-scale=age"]          => Ok(ColourScale { age: true, .. ColourScale::default() }));
+        test!(both:       ColourScale <- ["--color-scale=size,age"]     => Ok(ColourScale { size: true, age: true, .. ColourScale::default() }));
+        test!(names:      ColourScale <- ["--colour-scale=age-names"]   => Ok(ColourScale { age_names: true, .. ColourScale::default() }));
+        test!(numbers:    ColourScale <- ["--color-scale=blocks,links"] => Ok(ColourScale { blocks: true, links: true, .. ColourScale::default() }));
+        test!(bad:        ColourScale <- ["--color-scale=age,colour"]   => Err(Misfire::InvalidOptions(ParseError::BadValue { flag: Flag::Long("color-scale"), value: os("colour"), values: flags::SCALES })));
+    }
+
+
+    mod colour_depths {
+        use super::*;
+
+        test!(empty:      ColourDepth <- []                          => Ok(None));
+        test!(truecolor:  ColourDepth <- ["--color-depth=truecolor"]  => Ok(Some(ColourDepth::TrueColour)));
+        test!(fixed:      ColourDepth <- ["--colour-depth=256"]       => Ok(Some(ColourDepth::Fixed256)));
+        test!(basic:      ColourDepth <- ["--color-depth", "16"]      => Ok(Some(ColourDepth::Basic)));
+        test!(none:       ColourDepth <- ["--colour-depth=none"]      => Ok(Some(ColourDepth::NoColours)));
+        test!(bad:        ColourDepth <- ["--color-depth=88"]         => Err(Misfire::InvalidOptions(ParseError::BadValue { flag: Flag::Long("color-depth"), value: os("88"), values: flags::DEPTHS })));
+    }
+
+
+    mod plain_columns {
+        use super::*;
+
+        test!(empty:  PlainColumns <- []                                => Ok(None));
+        test!(two:    PlainColumns <- ["--plain-columns=date,size"]     => Ok(Some(PlainColumns { date: true, size: true, .. PlainColumns::default() })));
+        test!(bad:    PlainColumns <- ["--plain-columns", "date,icons"] => Err(Misfire::bad_argument(&flags::PLAIN_COLUMNS, &os("icons"), COLUMN_NAMES)));
+    }
+
+
     mod time_types {
         use super::*;
 
@@ -465,13 +875,14 @@ mod test {
         test!(time_c:    TimeTypes <- ["-tcr"]                => Ok(TimeTypes { accessed: false,  modified: false,  created: true  }));
 
         // Multiples
-        test!(time_uu:    TimeTypes <- ["-uU"]                => Ok(TimeTypes { accessed: true,   modified: false,  created: true  }));
-
-        // Overriding
-        test!(time_mc:    TimeTypes <- ["-tcr", "-tmod"]      => Ok(TimeTypes { accessed: false,  modified: true,   created: false }));
+        test!(time_uu:    TimeTypes <- ["-uU"]                       => Ok(TimeTypes { accessed: true,   modified: false,  created: true  }));
+        test!(time_mc:    TimeTypes <- ["-tcr", "-tmod"]             => Ok(TimeTypes { accessed: false,  modified: true,   created: true  }));
+        test!(time_list:  TimeTypes <- ["--time=modified,accessed"]  => Ok(TimeTypes { accessed: true,   modified: true,   created: false }));
+        test!(time_ca:    TimeTypes <- ["-tcr,acc"]                  => Ok(TimeTypes { accessed: true,   modified: false,  created: true  }));
 
         // Errors
-        test!(time_tea:  TimeTypes <- ["--time=tea"]  => Err(Misfire::bad_argument(&flags::TIME, &os("tea"), super::TIMES)));
-        test!(time_ea:   TimeTypes <- ["-tea"]        => Err(Misfire::bad_argument(&flags::TIME, &os("ea"), super::TIMES)));
+        test!(time_tea:  TimeTypes <- ["--time=tea"]      => Err(Misfire::InvalidOptions(ParseError::BadValue { flag: Flag::Long("time"), value: os("tea"), values: flags::TIMES })));
+        test!(time_mtea: TimeTypes <- ["--time=mod,tea"]  => Err(Misfire::InvalidOptions(ParseError::BadValue { flag: Flag::Long("time"), value: os("tea"), values: flags::TIMES })));
+        test!(time_ea:   TimeTypes <- ["-tea"]            => Err(Misfire::InvalidOptions(ParseError::BadValue { flag: Flag::Short(b't'), value: os("ea"), values: flags::TIMES })));
     }
 }
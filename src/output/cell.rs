@@ -1,5 +1,9 @@
 //! The `TextCell` type for the details and lines views.
 
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::Write as FmtWrite;
+use std::io::{Write, Result as IOResult};
 use std::iter::Sum;
 use std::ops::{Add, Deref, DerefMut};
 
@@ -161,6 +165,21 @@ impl TextCellContents {
         ANSIStrings(&self.0)
     }
 
+    /// Writes these contents to the given writer as one line, with a single
+    /// call to `write_all`. The escape codes and text get formatted into the
+    /// given buffer first, which can be reused from one line to the next.
+    ///
+    /// Writing them straight to the output instead would issue a separate
+    /// write, and lock standard output again, for every escape code and
+    /// every piece of text in the line.
+    pub fn write_line<W: Write>(&self, w: &mut W, buffer: &mut String) -> IOResult<()> {
+        buffer.clear();
+
+        // Formatting into a `String` can’t fail.
+        let _ = writeln!(buffer, "{}", self.strings());
+        w.write_all(buffer.as_bytes())
+    }
+
     /// Calculates the width that a cell with these contents would take up, by
     /// counting the number of characters in each unformatted ANSI string.
     pub fn width(&self) -> DisplayWidth {
@@ -198,10 +217,43 @@ pub struct DisplayWidth(usize);
 
 impl<'a> From<&'a str> for DisplayWidth {
     fn from(input: &'a str) -> DisplayWidth {
-        DisplayWidth(UnicodeWidthStr::width(input))
+
+        // Printable ASCII characters are all one column wide, which covers
+        // most file names without having to look anything up.
+        if input.bytes().all(|b| b >= 0x20 && b < 0x7F) {
+            return DisplayWidth(input.len());
+        }
+
+        WIDTHS.with(|widths| {
+            let mut widths = widths.borrow_mut();
+
+            if let Some(&width) = widths.get(input) {
+                return DisplayWidth(width);
+            }
+
+            if widths.len() >= WIDTH_CACHE_SIZE {
+                widths.clear();
+            }
+
+            let width = UnicodeWidthStr::width(input);
+            let _ = widths.insert(input.to_owned(), width);
+            DisplayWidth(width)
+        })
     }
 }
 
+/// The most widths of non-ASCII strings to remember before starting again.
+const WIDTH_CACHE_SIZE: usize = 4096;
+
+thread_local! {
+    /// The widths of the non-ASCII strings that have been measured on this
+    /// thread. Working out the width of a string means looking up every
+    /// character in it, which adds up for long CJK or emoji file names, and
+    /// the same names get measured again whenever they turn up in another
+    /// directory or another attempt at fitting a grid.
+    static WIDTHS: RefCell<HashMap<String, usize>> = RefCell::new(HashMap::new());
+}
+
 impl From<usize> for DisplayWidth {
     fn from(width: usize) -> DisplayWidth {
         DisplayWidth(width)
@@ -274,3 +326,25 @@ mod width_unit_test {
         assert_eq!(*(cell + 8), 17);
     }
 }
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn ascii() {
+        assert_eq!(*DisplayWidth::from("Cargo.toml"), 10);
+    }
+
+    #[test]
+    fn wide_characters() {
+        assert_eq!(*DisplayWidth::from("日本語.txt"), 10);
+        assert_eq!(*DisplayWidth::from("日本語.txt"), 10);
+    }
+
+    #[test]
+    fn control_characters() {
+        assert_eq!(*DisplayWidth::from("a\u{7f}b"), UnicodeWidthStr::width("a\u{7f}b"));
+    }
+}
@@ -1,10 +1,14 @@
-use ansi_term::Style;
-use ansi_term::Colour::{Red, Green, Yellow, Blue, Cyan, Purple, Fixed};
+use ansi_term::{Colour, Style};
+use ansi_term::Colour::{Black, Red, Green, Yellow, Blue, Cyan, Purple, White, Fixed, RGB};
 
+use glob;
 
-#[derive(Clone, Copy, Debug, Default, PartialEq)]
+use output::lsc::Pair;
+
+
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct Colours {
-    pub scale: bool,
+    pub scale: ColourScale,
 
     pub filetypes:  FileTypes,
     pub perms:      Permissions,
@@ -14,15 +18,67 @@ pub struct Colours {
     pub git:        Git,
 
     pub punctuation:  Style,
+    pub tree:         Style,
     pub date:         Style,
     pub inode:        Style,
     pub blocks:       Style,
     pub header:       Style,
 
+    pub link_arrow:        Style,
+    pub column_separator:  Style,
+
     pub symlink_path:     Style,
+    pub broken_symlink:   Style,
     pub broken_arrow:     Style,
     pub broken_filename:  Style,
     pub control_char:     Style,
+
+    /// Styles for files whose names end with particular strings, from the
+    /// `*.ext` entries in `LS_COLORS`. These take priority over exa’s own
+    /// file type colours, with later entries winning over earlier ones.
+    pub suffixes: Vec<(String, Style)>,
+
+    /// Styles for files whose whole names match glob patterns, from the
+    /// `[names]` section of a theme. These are checked in the order they
+    /// were given, before the suffixes, and the first match wins.
+    pub names: Vec<(glob::Pattern, Style)>,
+
+    /// The styles and thresholds for colouring by how old things are.
+    pub age: Age,
+
+    /// The styles underneath everything in each column of the details view.
+    pub columns: Columns,
+
+    /// The columns of the details view that don’t get any colours at all.
+    pub plain_columns: PlainColumns,
+
+    /// How many colours the terminal can show, which the colours worked
+    /// out while rendering, such as the age scale’s, have to fit into.
+    pub depth: ColourDepth,
+}
+
+/// Which parts of the output get coloured on a scale, rather than all
+/// being given the same style.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ColourScale {
+
+    /// Whether file sizes are coloured by how big they are.
+    pub size: bool,
+
+    /// Whether dates are coloured by how long ago they were.
+    pub age: bool,
+
+    /// Whether file names are coloured by how long ago the files were
+    /// modified, instead of by their types.
+    pub age_names: bool,
+
+    /// Whether block counts are coloured by how much space they take up,
+    /// on the same scale as file sizes.
+    pub blocks: bool,
+
+    /// Whether link counts are coloured by how many links there are, using
+    /// the styles of the file size scale.
+    pub links: bool,
 }
 
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
@@ -45,6 +101,14 @@ pub struct FileTypes {
     pub temp: Style,
     pub immediate: Style,
     pub compiled: Style,
+
+    // Files and directories with special permissions get the backgrounds
+    // that `ls` gives them, unless `LS_COLORS` turns them off with `0`.
+    pub setuid: Option<Style>,
+    pub setgid: Option<Style>,
+    pub sticky: Option<Style>,
+    pub sticky_other_writable: Option<Style>,
+    pub other_writable: Option<Style>,
 }
 
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
@@ -68,7 +132,7 @@ pub struct Permissions {
     pub attribute: Style,
 }
 
-#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Size {
     pub numbers: Style,
     pub unit: Style,
@@ -81,6 +145,117 @@ pub struct Size {
     pub scale_mega: Style,
     pub scale_giga: Style,
     pub scale_huge: Style,
+
+    /// The sizes, in bytes, at which the kilo, mega, giga, and huge
+    /// styles start being used.
+    pub scale_thresholds: [u64; 4],
+
+    /// Whether sizes between two thresholds get a colour mixed between
+    /// the styles on either side, rather than the style for the step
+    /// they’re in.
+    pub scale_gradient: bool,
+}
+
+impl Default for Size {
+    fn default() -> Size {
+        Size {
+            numbers: Style::default(),
+            unit: Style::default(),
+            major: Style::default(),
+            minor: Style::default(),
+            scale_byte: Style::default(),
+            scale_kilo: Style::default(),
+            scale_mega: Style::default(),
+            scale_giga: Style::default(),
+            scale_huge: Style::default(),
+            scale_thresholds: [ 1 << 10, 1 << 20, 1 << 30, 1 << 40 ],
+            scale_gradient: false,
+        }
+    }
+}
+
+/// Styles that get added underneath the contents of each column in the
+/// details view, such as to dim a column that’s less important than the
+/// others. They’re all plain unless a theme sets them.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Columns {
+    pub permissions: Style,
+    pub size: Style,
+    pub date: Style,
+    pub blocks: Style,
+    pub user: Style,
+    pub group: Style,
+    pub links: Style,
+    pub inode: Style,
+    pub git: Style,
+    pub custom: Style,
+    pub name: Style,
+}
+
+/// Which columns of the details view get shown without any colours, for
+/// people who find the colours in them more distracting than useful.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PlainColumns {
+    pub permissions: bool,
+    pub size: bool,
+    pub date: bool,
+    pub blocks: bool,
+    pub user: bool,
+    pub group: bool,
+    pub links: bool,
+    pub inode: bool,
+    pub git: bool,
+    pub custom: bool,
+    pub name: bool,
+}
+
+/// The names of the columns, as used by themes and `--plain-columns`.
+pub static COLUMN_NAMES: &[&str] = &[ "permissions", "size", "date", "blocks", "user", "group", "links", "inode", "git", "custom", "name" ];
+
+impl PlainColumns {
+
+    /// Makes the column with the given name plain, returning whether
+    /// there’s a column with that name.
+    pub fn add(&mut self, name: &str) -> bool {
+        match name {
+            "permissions"  => self.permissions = true,
+            "size"         => self.size        = true,
+            "date"         => self.date        = true,
+            "blocks"       => self.blocks      = true,
+            "user"         => self.user        = true,
+            "group"        => self.group       = true,
+            "links"        => self.links       = true,
+            "inode"        => self.inode       = true,
+            "git"          => self.git         = true,
+            "custom"       => self.custom      = true,
+            "name"         => self.name        = true,
+            _              => return false,
+        }
+
+        true
+    }
+}
+
+/// The two ends of the age scale. Anything newer than `newest_seconds` gets
+/// the `newest` style, anything older than `oldest_seconds` gets the
+/// `oldest` style, and everything in between gets a colour between the two.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Age {
+    pub newest: Style,
+    pub oldest: Style,
+    pub newest_seconds: u64,
+    pub oldest_seconds: u64,
+}
+
+impl Default for Age {
+    fn default() -> Age {
+        Age {
+            newest: Style::default(),
+            oldest: Style::default(),
+            newest_seconds: 60,
+            oldest_seconds: 2 * 365 * 24 * 60 * 60,
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
@@ -89,12 +264,22 @@ pub struct Users {
     pub user_someone_else: Style,
     pub group_yours: Style,
     pub group_not_yours: Style,
+
+    // These get added on top of the styles of file names, when files
+    // are being highlighted by who owns them.
+    pub owned_by_someone_else: Style,
+    pub owned_by_root: Style,
 }
 
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub struct Links {
     pub normal: Style,
     pub multi_link_file: Style,
+
+    // This gets added on top of the styles of the names of files that share
+    // their inode with others, either as hard links or as duplicates that
+    // `--dedupe` left out.
+    pub duplicate_file: Style,
 }
 
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
@@ -104,6 +289,7 @@ pub struct Git {
     pub deleted: Style,
     pub renamed: Style,
     pub typechange: Style,
+    pub ignored: Style,
 }
 
 impl Colours {
@@ -111,7 +297,7 @@ impl Colours {
         Colours::default()
     }
 
-    pub fn colourful(scale: bool) -> Colours {
+    pub fn colourful(scale: ColourScale) -> Colours {
         Colours {
             scale: scale,
 
@@ -134,6 +320,12 @@ impl Colours {
                 temp:        Fixed(244).normal(),
                 immediate:   Yellow.bold().underline(),
                 compiled:    Fixed(137).normal(),
+
+                setuid:                 Some(White.on(Red)),
+                setgid:                 Some(Black.on(Yellow)),
+                sticky:                 Some(White.on(Blue)),
+                sticky_other_writable:  Some(Black.on(Green)),
+                other_writable:         Some(Blue.on(Green)),
             },
 
             perms: Permissions {
@@ -168,6 +360,8 @@ impl Colours {
                 scale_mega: Fixed(226).normal(),
                 scale_giga: Fixed(220).normal(),
                 scale_huge: Fixed(214).normal(),
+
+                .. Size::default()
             },
 
             users: Users {
@@ -175,11 +369,15 @@ impl Colours {
                 user_someone_else:  Style::default(),
                 group_yours:        Yellow.bold(),
                 group_not_yours:    Style::default(),
+
+                owned_by_someone_else:  Style::default().underline(),
+                owned_by_root:          Style::default().on(Red),
             },
 
             links: Links {
                 normal:          Red.bold(),
                 multi_link_file: Red.on(Yellow),
+                duplicate_file:  Style::default().italic(),
             },
 
             git: Git {
@@ -188,41 +386,995 @@ impl Colours {
                 deleted:     Red.normal(),
                 renamed:     Yellow.normal(),
                 typechange:  Purple.normal(),
+                ignored:     Style::default().dimmed(),
             },
 
             punctuation:  Fixed(244).normal(),
+            tree:         Fixed(244).normal(),
             date:         Blue.normal(),
             inode:        Purple.normal(),
             blocks:       Cyan.normal(),
             header:       Style::default().underline(),
 
+            link_arrow:        Fixed(244).normal(),
+            column_separator:  Style::default(),
+
             symlink_path:     Cyan.normal(),
+            broken_symlink:   Red.normal(),
             broken_arrow:     Red.normal(),
             broken_filename:  Red.underline(),
             control_char:     Red.normal(),
+
+            suffixes: Vec::new(),
+            names:    Vec::new(),
+
+            columns: Columns::default(),
+            plain_columns: PlainColumns::default(),
+
+            age: Age {
+                newest: Fixed(40).normal(),
+                oldest: Fixed(240).normal(),
+                .. Age::default()
+            },
+
+            depth: ColourDepth::default(),
         }
     }
 
+    /// The built-in theme with the given name, if there is one.
+    pub fn preset(name: &str, scale: ColourScale) -> Option<Colours> {
+        match name {
+            "default"          => Some(Colours::colourful(scale)),
+            "solarized-dark"   => Some(Colours::from_palette(&SOLARIZED_DARK, scale)),
+            "solarized-light"  => Some(Colours::from_palette(&SOLARIZED_LIGHT, scale)),
+            "gruvbox"          => Some(Colours::from_palette(&GRUVBOX, scale)),
+            "dracula"          => Some(Colours::from_palette(&DRACULA, scale)),
+            "monochrome"       => Some(Colours::monochrome(scale)),
+            _                  => None,
+        }
+    }
+
+    /// Builds a theme that uses the colours in the given palette in the
+    /// same places that the default theme uses the basic terminal colours.
+    fn from_palette(p: &Palette, scale: ColourScale) -> Colours {
+        Colours {
+            scale: scale,
+
+            filetypes: FileTypes {
+                normal:      Style::default(),
+                directory:   p.blue.bold(),
+                symlink:     p.cyan.normal(),
+                pipe:        p.yellow.normal(),
+                device:      p.yellow.bold(),
+                socket:      p.red.bold(),
+                special:     p.yellow.normal(),
+                executable:  p.green.bold(),
+                image:       p.purple.normal(),
+                video:       p.purple.bold(),
+                music:       p.cyan.normal(),
+                lossless:    p.cyan.bold(),
+                crypto:      p.green.normal(),
+                document:    p.blue.normal(),
+                compressed:  p.red.normal(),
+                temp:        p.grey.normal(),
+                immediate:   p.yellow.bold().underline(),
+                compiled:    p.orange.normal(),
+
+                setuid:                 Some(White.on(p.red)),
+                setgid:                 Some(Black.on(p.yellow)),
+                sticky:                 Some(White.on(p.blue)),
+                sticky_other_writable:  Some(Black.on(p.green)),
+                other_writable:         Some(p.blue.on(p.green)),
+            },
+
+            perms: Permissions {
+                user_read:           p.yellow.bold(),
+                user_write:          p.red.bold(),
+                user_execute_file:   p.green.bold().underline(),
+                user_execute_other:  p.green.bold(),
+
+                group_read:          p.yellow.normal(),
+                group_write:         p.red.normal(),
+                group_execute:       p.green.normal(),
+
+                other_read:          p.yellow.normal(),
+                other_write:         p.red.normal(),
+                other_execute:       p.green.normal(),
+
+                special_user_file:   p.purple.normal(),
+                special_other:       p.purple.normal(),
+
+                attribute:           Style::default(),
+            },
+
+            size: Size {
+                numbers:  p.green.bold(),
+                unit:     p.green.normal(),
+
+                major:  p.green.bold(),
+                minor:  p.green.normal(),
+
+                scale_byte: p.green.normal(),
+                scale_kilo: p.yellow.normal(),
+                scale_mega: p.orange.normal(),
+                scale_giga: p.red.normal(),
+                scale_huge: p.purple.normal(),
+
+                .. Size::default()
+            },
+
+            users: Users {
+                user_you:           p.yellow.bold(),
+                user_someone_else:  Style::default(),
+                group_yours:        p.yellow.bold(),
+                group_not_yours:    Style::default(),
+
+                owned_by_someone_else:  Style::default().underline(),
+                owned_by_root:          Style::default().on(p.red),
+            },
+
+            links: Links {
+                normal:          p.red.bold(),
+                multi_link_file: p.red.on(p.yellow),
+                duplicate_file:  Style::default().italic(),
+            },
+
+            git: Git {
+                new:         p.green.normal(),
+                modified:    p.blue.normal(),
+                deleted:     p.red.normal(),
+                renamed:     p.yellow.normal(),
+                typechange:  p.purple.normal(),
+                ignored:     Style::default().dimmed(),
+            },
+
+            punctuation:  p.grey.normal(),
+            tree:         p.grey.normal(),
+            date:         p.blue.normal(),
+            inode:        p.purple.normal(),
+            blocks:       p.cyan.normal(),
+            header:       Style::default().underline(),
+
+            link_arrow:        p.grey.normal(),
+            column_separator:  Style::default(),
+
+            symlink_path:     p.cyan.normal(),
+            broken_symlink:   p.red.normal(),
+            broken_arrow:     p.red.normal(),
+            broken_filename:  p.red.underline(),
+            control_char:     p.red.normal(),
+
+            suffixes: Vec::new(),
+            names:    Vec::new(),
+
+            columns: Columns::default(),
+            plain_columns: PlainColumns::default(),
+
+            age: Age {
+                newest: p.green.normal(),
+                oldest: p.grey.normal(),
+                .. Age::default()
+            },
+
+            depth: ColourDepth::default(),
+        }
+    }
+
+    /// A theme for terminals without colours, which tells files apart
+    /// using bold, underlined, italic, and dimmed text instead.
+    fn monochrome(scale: ColourScale) -> Colours {
+        let mut colours = Colours { scale, .. Colours::plain() };
+        let bold = Style::default().bold();
+        let dim = Style::default().dimmed();
+
+        colours.filetypes.directory   = bold;
+        colours.filetypes.symlink     = Style::default().italic();
+        colours.filetypes.executable  = Style::default().underline();
+        colours.filetypes.device      = bold.italic();
+        colours.filetypes.socket      = bold.italic();
+        colours.filetypes.immediate   = bold.underline();
+        colours.filetypes.temp        = dim;
+
+        colours.filetypes.setuid                 = Some(Style::default().reverse());
+        colours.filetypes.setgid                 = Some(Style::default().reverse());
+        colours.filetypes.sticky_other_writable  = Some(Style::default().reverse());
+        colours.filetypes.other_writable         = Some(Style::default().reverse());
+
+        colours.perms.user_write         = bold;
+        colours.perms.user_execute_file  = bold.underline();
+        colours.perms.user_execute_other = bold;
+
+        colours.size.numbers = bold;
+        colours.size.major   = bold;
+        colours.size.scale_giga = bold;
+        colours.size.scale_huge = bold.underline();
+        colours.age.newest = bold;
+        colours.age.oldest = dim;
+
+        colours.users.user_you    = bold;
+        colours.users.group_yours = bold;
+        colours.users.owned_by_someone_else = Style::default().underline();
+        colours.users.owned_by_root         = Style::default().reverse();
+        colours.links.multi_link_file = Style::default().reverse();
+        colours.links.duplicate_file  = Style::default().italic();
+
+        colours.git.new      = bold;
+        colours.git.modified = bold;
+        colours.git.deleted  = bold;
+        colours.git.ignored  = dim;
+
+        colours.punctuation      = dim;
+        colours.tree             = dim;
+        colours.link_arrow       = dim;
+        colours.header           = Style::default().underline();
+        colours.broken_symlink   = Style::default().reverse();
+        colours.broken_arrow     = Style::default().reverse();
+        colours.broken_filename  = Style::default().underline();
+        colours.control_char     = Style::default().reverse();
+
+        colours
+    }
+
+    /// Sets the style given by one of the pairs in `LS_COLORS`, on top of
+    /// the styles that are already there. Keys that `ls` uses for things exa
+    /// doesn’t show, such as doors, get ignored.
+    pub fn set_ls(&mut self, pair: &Pair) {
+        let style = pair.to_style();
+
+        match pair.key {
+            "no" | "fi"  => self.filetypes.normal     = style,
+            "di"         => self.filetypes.directory  = style,
+            "ex"         => self.filetypes.executable = style,
+            "pi"         => self.filetypes.pipe       = style,
+            "so"         => self.filetypes.socket     = style,
+            "bd" | "cd"  => self.filetypes.device     = style,
+            "or"         => self.broken_symlink       = style,
+            "mi"         => self.broken_filename      = style,
+
+            "su"  => self.filetypes.setuid                = unless_plain(style),
+            "sg"  => self.filetypes.setgid                = unless_plain(style),
+            "st"  => self.filetypes.sticky                = unless_plain(style),
+            "tw"  => self.filetypes.sticky_other_writable = unless_plain(style),
+            "ow"  => self.filetypes.other_writable        = unless_plain(style),
+
+            // A value of `target` means links should be coloured like the
+            // files they point to, which exa doesn’t do, so it gets left
+            // with its usual style.
+            "ln" if pair.value != "target"  => self.filetypes.symlink = style,
+
+            key if key.starts_with('*') && key.len() > 1  => self.suffixes.push((key[1..].to_owned(), style)),
+            _  => {},
+        }
+    }
+
+    /// Sets the style given by one of the pairs in `EXA_COLORS`, which has
+    /// codes for all the parts of exa’s output that aren’t file names.
+    /// Returns `false` if the key isn’t one of them, so it can be tried as
+    /// an `LS_COLORS` key instead.
+    pub fn set_exa(&mut self, pair: &Pair) -> bool {
+        let style = pair.to_style();
+
+        match pair.key {
+            "ur" => self.perms.user_read          = style,
+            "uw" => self.perms.user_write         = style,
+            "ux" => self.perms.user_execute_file  = style,
+            "ue" => self.perms.user_execute_other = style,
+            "gr" => self.perms.group_read         = style,
+            "gw" => self.perms.group_write        = style,
+            "gx" => self.perms.group_execute      = style,
+            "tr" => self.perms.other_read         = style,
+            "tw" => self.perms.other_write        = style,
+            "tx" => self.perms.other_execute      = style,
+            "sf" => self.perms.special_user_file  = style,
+            "sp" => self.perms.special_other      = style,
+            "xa" => self.perms.attribute          = style,
+
+            "sn" => self.size.numbers     = style,
+            "sb" => self.size.unit        = style,
+            "df" => self.size.major       = style,
+            "ds" => self.size.minor       = style,
+            "nb" => self.size.scale_byte  = style,
+            "nk" => self.size.scale_kilo  = style,
+            "nm" => self.size.scale_mega  = style,
+            "ng" => self.size.scale_giga  = style,
+            "nt" => self.size.scale_huge  = style,
+
+            "uu" => self.users.user_you           = style,
+            "un" => self.users.user_someone_else  = style,
+            "gu" => self.users.group_yours        = style,
+            "gn" => self.users.group_not_yours    = style,
+            "fo" => self.users.owned_by_someone_else  = style,
+            "fr" => self.users.owned_by_root          = style,
+
+            "lc" => self.links.normal           = style,
+            "lm" => self.links.multi_link_file  = style,
+            "ld" => self.links.duplicate_file   = style,
+
+            "ga" => self.git.new         = style,
+            "gm" => self.git.modified    = style,
+            "gd" => self.git.deleted     = style,
+            "gv" => self.git.renamed     = style,
+            "gt" => self.git.typechange  = style,
+            "gi" => self.git.ignored     = style,
+
+            "im" => self.filetypes.image       = style,
+            "vi" => self.filetypes.video       = style,
+            "mu" => self.filetypes.music       = style,
+            "lo" => self.filetypes.lossless    = style,
+            "cr" => self.filetypes.crypto      = style,
+            "do" => self.filetypes.document    = style,
+            "co" => self.filetypes.compressed  = style,
+            "tm" => self.filetypes.temp        = style,
+            "cm" => self.filetypes.compiled    = style,
+            "bu" => self.filetypes.immediate   = style,
+            "sx" => self.filetypes.special     = style,
+
+            "tl" => self.tree          = style,
+            "da" => self.date          = style,
+            "in" => self.inode         = style,
+            "bl" => self.blocks        = style,
+            "hd" => self.header        = style,
+            "lp" => self.symlink_path  = style,
+            "ba" => self.broken_arrow  = style,
+            "cc" => self.control_char  = style,
+
+            // The arrow used to be punctuation, so it still follows it,
+            // unless it gets a style of its own afterwards.
+            "xx" => { self.punctuation = style; self.link_arrow = style; },
+            "la" => self.link_arrow        = style,
+            "cs" => self.column_separator  = style,
+
+            "an" => self.age.newest    = style,
+            "ao" => self.age.oldest    = style,
+
+            _    => return false,
+        }
+
+        true
+    }
+
+    /// Changes every colour into the nearest one that can be shown with
+    /// the given number of colours, so terminals that can’t show 24-bit
+    /// or 256-colour escapes don’t get sent them.
+    pub fn limit_depth(&mut self, depth: ColourDepth) {
+        self.depth = depth;
+
+        if depth == ColourDepth::TrueColour {
+            return;
+        }
+        else if depth == ColourDepth::NoColours {
+            *self = Colours::plain();
+            return;
+        }
+
+        self.change_styles(|style| limit_style(style, depth));
+    }
+
+    /// Turns off every text attribute that the terminal can’t show, so it
+    /// doesn’t get sent escapes that it’d show as something else.
+    pub fn limit_attributes(&mut self, attributes: Attributes) {
+        self.change_styles(|mut style| {
+            style.is_bold          &= attributes.bold;
+            style.is_dimmed        &= attributes.dimmed;
+            style.is_italic        &= attributes.italic;
+            style.is_underline     &= attributes.underline;
+            style.is_blink         &= attributes.blink;
+            style.is_reverse       &= attributes.reverse;
+            style.is_hidden        &= attributes.hidden;
+            style.is_strikethrough &= attributes.strikethrough;
+            style
+        });
+    }
+
+    /// A copy of these colours with the given style added underneath every
+    /// one of them, for parts of the output that the theme gives a base
+    /// style to.
+    pub fn with_base_style(&self, base: Style) -> Colours {
+        let mut colours = self.clone();
+        colours.change_styles(|style| overlay(base, style));
+        colours
+    }
+
+    /// Replaces every style in these colours, including the ones that
+    /// only get used if they’ve been set, with the result of the function.
+    fn change_styles<F>(&mut self, mut change: F) where F: FnMut(Style) -> Style {
+        for style in self.styles_mut() {
+            *style = change(*style);
+        }
+
+        for style in self.optional_styles_mut().into_iter().filter_map(|s| s.as_mut()) {
+            *style = change(*style);
+        }
+
+        for &mut (_, ref mut style) in &mut self.suffixes {
+            *style = change(*style);
+        }
+
+        for &mut (_, ref mut style) in &mut self.names {
+            *style = change(*style);
+        }
+    }
+
+    /// The colours for the name column of the details view, which could
+    /// have a base style or have no colours at all.
+    pub fn for_names(&self) -> Colours {
+        if self.plain_columns.name { Colours::plain() }
+                              else { self.with_base_style(self.columns.name) }
+    }
+
+    /// Every style in these colours that’s always used.
+    fn styles_mut(&mut self) -> Vec<&mut Style> {
+        vec![
+            &mut self.filetypes.normal, &mut self.filetypes.directory, &mut self.filetypes.symlink,
+            &mut self.filetypes.pipe, &mut self.filetypes.device, &mut self.filetypes.socket,
+            &mut self.filetypes.special, &mut self.filetypes.executable, &mut self.filetypes.image,
+            &mut self.filetypes.video, &mut self.filetypes.music, &mut self.filetypes.lossless,
+            &mut self.filetypes.crypto, &mut self.filetypes.document, &mut self.filetypes.compressed,
+            &mut self.filetypes.temp, &mut self.filetypes.immediate, &mut self.filetypes.compiled,
+
+            &mut self.perms.user_read, &mut self.perms.user_write, &mut self.perms.user_execute_file,
+            &mut self.perms.user_execute_other, &mut self.perms.group_read, &mut self.perms.group_write,
+            &mut self.perms.group_execute, &mut self.perms.other_read, &mut self.perms.other_write,
+            &mut self.perms.other_execute, &mut self.perms.special_user_file, &mut self.perms.special_other,
+            &mut self.perms.attribute,
+
+            &mut self.size.numbers, &mut self.size.unit, &mut self.size.major, &mut self.size.minor,
+            &mut self.size.scale_byte, &mut self.size.scale_kilo, &mut self.size.scale_mega,
+            &mut self.size.scale_giga, &mut self.size.scale_huge,
+
+            &mut self.users.user_you, &mut self.users.user_someone_else,
+            &mut self.users.group_yours, &mut self.users.group_not_yours,
+            &mut self.users.owned_by_someone_else, &mut self.users.owned_by_root,
+
+            &mut self.links.normal, &mut self.links.multi_link_file, &mut self.links.duplicate_file,
+
+            &mut self.git.new, &mut self.git.modified, &mut self.git.deleted,
+            &mut self.git.renamed, &mut self.git.typechange, &mut self.git.ignored,
+
+            &mut self.punctuation, &mut self.tree, &mut self.date, &mut self.inode,
+            &mut self.blocks, &mut self.header, &mut self.link_arrow, &mut self.column_separator, &mut self.symlink_path, &mut self.broken_symlink,
+            &mut self.broken_arrow, &mut self.broken_filename, &mut self.control_char,
+
+            &mut self.age.newest, &mut self.age.oldest,
+
+            &mut self.columns.permissions, &mut self.columns.size, &mut self.columns.date,
+            &mut self.columns.blocks, &mut self.columns.user, &mut self.columns.group,
+            &mut self.columns.links, &mut self.columns.inode, &mut self.columns.git,
+            &mut self.columns.custom, &mut self.columns.name,
+        ]
+    }
+
+    /// Every style in these colours that only gets used if it’s been set.
+    fn optional_styles_mut(&mut self) -> Vec<&mut Option<Style>> {
+        vec![
+            &mut self.filetypes.setuid, &mut self.filetypes.setgid, &mut self.filetypes.sticky,
+            &mut self.filetypes.sticky_other_writable, &mut self.filetypes.other_writable,
+        ]
+    }
+
+    /// The style for a file with the given name, if it matches any of the
+    /// suffixes from `LS_COLORS`.
+    pub fn suffix_style(&self, name: &str) -> Option<Style> {
+        self.suffixes.iter().rev()
+            .find(|&&(ref suffix, _)| name.ends_with(&**suffix))
+            .map(|&(_, style)| style)
+    }
+
+    /// The style for a file with the given name, if it matches any of the
+    /// glob patterns from a theme.
+    pub fn name_style(&self, name: &str) -> Option<Style> {
+        self.names.iter()
+            .find(|&&(ref pattern, _)| pattern.matches(name))
+            .map(|&(_, style)| style)
+    }
+
     pub fn file_size(&self, size: u64) -> Style {
-        if self.scale {
-            if size < 1024 {
-                self.size.scale_byte
-            }
-            else if size < 1024 * 1024 {
-                self.size.scale_kilo
-            }
-            else if size < 1024 * 1024 * 1024 {
-                self.size.scale_mega
-            }
-            else if size < 1024 * 1024 * 1024 * 1024 {
-                self.size.scale_giga
-            }
-            else {
-                self.size.scale_huge
-            }
+        if !self.scale.size {
+            return self.size.numbers;
+        }
+
+        self.number_style(size, &self.size.scale_thresholds)
+    }
+
+    /// The style for a number of blocks, which is on the file size scale
+    /// when blocks are being coloured on a scale.
+    pub fn blocks_style(&self, blocks: u64) -> Style {
+        if !self.scale.blocks {
+            return self.blocks;
+        }
+
+        self.number_style(blocks.saturating_mul(BLOCK_SIZE), &self.size.scale_thresholds)
+    }
+
+    /// The style for a number of hard links. Files with more than one link
+    /// always get highlighted, whether or not links are on a scale.
+    pub fn links_style(&self, count: u64, multiple: bool) -> Style {
+        if multiple {
+            self.links.multi_link_file
+        }
+        else if self.scale.links {
+            self.number_style(count, &LINK_THRESHOLDS)
         }
         else {
-            self.size.numbers
+            self.links.normal
+        }
+    }
+
+    /// The style for a number on the scale made of the file size styles,
+    /// with each threshold being where the next style starts.
+    fn number_style(&self, number: u64, thresholds: &[u64; 4]) -> Style {
+        let steps = [ self.size.scale_byte, self.size.scale_kilo, self.size.scale_mega,
+                      self.size.scale_giga, self.size.scale_huge ];
+        let step = thresholds.iter().take_while(|&&t| number >= t).count();
+
+        if !self.size.scale_gradient || step == 0 || step == steps.len() - 1 {
+            return steps[step];
+        }
+
+        // In gradient mode, each style is exactly right at the start of its
+        // step, and gets mixed into the next style towards the next one.
+        let position = log_position(number, thresholds[step - 1], thresholds[step]);
+        self.mix(steps[step], steps[step + 1], position)
+    }
+
+    /// The style for something the given number of seconds old, on the
+    /// scale between the newest and oldest styles. The scale is
+    /// logarithmic, so minutes and hours can be told apart as easily as
+    /// months and years.
+    pub fn age_style(&self, seconds_ago: i64) -> Style {
+        let position = log_position(seconds_ago.max(0) as u64, self.age.newest_seconds, self.age.oldest_seconds);
+        self.mix(self.age.newest, self.age.oldest, position)
+    }
+
+    /// A style part of the way between two others, with 0 being the first
+    /// and 1 being the second. If both have colours, the colour gets mixed
+    /// between them; otherwise, it’s whichever style is nearer.
+    fn mix(&self, from: Style, to: Style, position: f64) -> Style {
+        let nearest = if position < 0.5 { from } else { to };
+
+        match (from.foreground, to.foreground) {
+            (Some(a), Some(b)) => {
+                let (a, b) = (colour_to_rgb(a), colour_to_rgb(b));
+                let channel = |x: u8, y: u8| (f64::from(x) + (f64::from(y) - f64::from(x)) * position).round() as u8;
+                let colour = RGB(channel(a.0, b.0), channel(a.1, b.1), channel(a.2, b.2));
+                Style { foreground: Some(limit_colour(colour, self.depth)), .. nearest }
+            },
+            _ => nearest,
+        }
+    }
+}
+
+
+
+/// The number of bytes in each block, as counted by the blocks column.
+const BLOCK_SIZE: u64 = 512;
+
+/// Where each step of the scale starts for link counts: everything with
+/// only one link is at the bottom.
+const LINK_THRESHOLDS: [u64; 4] = [ 2, 10, 100, 1000 ];
+
+/// The names of the built-in themes, for `--theme`.
+pub static PRESETS: &[&str] = &[ "default", "solarized-dark", "solarized-light", "gruvbox", "dracula", "monochrome" ];
+
+/// The colours a built-in theme gets made from, taking the places of the
+/// eight basic terminal colours, plus an orange and a grey.
+struct Palette {
+    red:     Colour,
+    green:   Colour,
+    yellow:  Colour,
+    blue:    Colour,
+    purple:  Colour,
+    cyan:    Colour,
+    orange:  Colour,
+    grey:    Colour,
+}
+
+static SOLARIZED_DARK: Palette = Palette {
+    red: Fixed(160), green: Fixed(64), yellow: Fixed(136), blue: Fixed(33),
+    purple: Fixed(125), cyan: Fixed(37), orange: Fixed(166), grey: Fixed(240),
+};
+
+static SOLARIZED_LIGHT: Palette = Palette {
+    red: Fixed(160), green: Fixed(64), yellow: Fixed(136), blue: Fixed(33),
+    purple: Fixed(125), cyan: Fixed(37), orange: Fixed(166), grey: Fixed(245),
+};
+
+static GRUVBOX: Palette = Palette {
+    red: Fixed(167), green: Fixed(142), yellow: Fixed(214), blue: Fixed(109),
+    purple: Fixed(175), cyan: Fixed(108), orange: Fixed(208), grey: Fixed(245),
+};
+
+static DRACULA: Palette = Palette {
+    red: Fixed(203), green: Fixed(84), yellow: Fixed(228), blue: Fixed(141),
+    purple: Fixed(212), cyan: Fixed(117), orange: Fixed(215), grey: Fixed(61),
+};
+
+
+/// Which text attributes the terminal can show. Terminals are assumed to
+/// be able to show all of them unless their terminfo entries say otherwise.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct Attributes {
+    pub bold: bool,
+    pub dimmed: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub blink: bool,
+    pub reverse: bool,
+    pub hidden: bool,
+    pub strikethrough: bool,
+}
+
+impl Default for Attributes {
+    fn default() -> Attributes {
+        Attributes {
+            bold: true, dimmed: true, italic: true, underline: true,
+            blink: true, reverse: true, hidden: true, strikethrough: true,
+        }
+    }
+}
+
+/// How many different colours the terminal can show.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum ColourDepth {
+
+    /// Any 24-bit colour.
+    TrueColour,
+
+    /// The 256 colours of the xterm palette.
+    Fixed256,
+
+    /// Only the eight basic colours, which 16-colour terminals also have
+    /// bright versions of.
+    Basic,
+
+    /// No colours or styles at all.
+    NoColours,
+}
+
+impl Default for ColourDepth {
+    fn default() -> ColourDepth {
+        ColourDepth::Fixed256
+    }
+}
+
+/// Adds the colours and attributes of the second style on top of the
+/// first, keeping the first style’s colours where the second has none.
+pub fn overlay(base: Style, top: Style) -> Style {
+    Style {
+        foreground:       top.foreground.or(base.foreground),
+        background:       top.background.or(base.background),
+        is_bold:          base.is_bold          || top.is_bold,
+        is_dimmed:        base.is_dimmed        || top.is_dimmed,
+        is_italic:        base.is_italic        || top.is_italic,
+        is_underline:     base.is_underline     || top.is_underline,
+        is_blink:         base.is_blink         || top.is_blink,
+        is_reverse:       base.is_reverse       || top.is_reverse,
+        is_hidden:        base.is_hidden        || top.is_hidden,
+        is_strikethrough: base.is_strikethrough || top.is_strikethrough,
+    }
+}
+
+/// The given style, or nothing if it’s plain, which is how `LS_COLORS`
+/// turns off the styles that only some files get.
+fn unless_plain(style: Style) -> Option<Style> {
+    if style == Style::default() { None }
+                            else { Some(style) }
+}
+
+/// How far the given value is between two others, from 0 to 1, on a
+/// logarithmic scale.
+fn log_position(value: u64, low: u64, high: u64) -> f64 {
+    let log = |n: u64| (n as f64 + 1.0).ln();
+
+    if value <= low {
+        0.0
+    }
+    else if value >= high {
+        1.0
+    }
+    else {
+        (log(value) - log(low)) / (log(high) - log(low))
+    }
+}
+
+/// Makes both of a style’s colours fit into the given depth.
+fn limit_style(mut style: Style, depth: ColourDepth) -> Style {
+    style.foreground = style.foreground.map(|c| limit_colour(c, depth));
+    style.background = style.background.map(|c| limit_colour(c, depth));
+    style
+}
+
+/// The nearest colour to the given one that fits into the given depth.
+fn limit_colour(colour: Colour, depth: ColourDepth) -> Colour {
+    match (colour, depth) {
+        (RGB(r, g, b), ColourDepth::Fixed256)  => Fixed(rgb_to_fixed(r, g, b)),
+        (RGB(r, g, b), ColourDepth::Basic)     => nearest_basic(r, g, b),
+        (Fixed(n),     ColourDepth::Basic)     => if n < 16 { BASIC[n as usize % 8].0 }
+                                                  else { let (r, g, b) = fixed_to_rgb(n); nearest_basic(r, g, b) },
+        (colour, _)                            => colour,
+    }
+}
+
+/// The eight basic colours, along with how xterm shows them.
+static BASIC: [(Colour, (u8, u8, u8)); 8] = [
+    (Black,  (0, 0, 0)),       (Red,    (205, 0, 0)),
+    (Green,  (0, 205, 0)),     (Yellow, (205, 205, 0)),
+    (Blue,   (0, 0, 238)),     (Purple, (205, 0, 205)),
+    (Cyan,   (0, 205, 205)),   (White,  (229, 229, 229)),
+];
+
+/// The levels of each channel in the 6×6×6 colour cube that makes up
+/// colours 16 to 231 of the 256-colour palette.
+static CUBE_LEVELS: [u8; 6] = [ 0, 95, 135, 175, 215, 255 ];
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| { let d = i32::from(x) - i32::from(y); (d * d) as u32 };
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+
+fn nearest_basic(r: u8, g: u8, b: u8) -> Colour {
+
+    // Greys are closer to some of the coloured colours than they are to
+    // black or white, but they should never turn into them.
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    if max - min < 32 {
+        return if max < 48 { Black } else { White };
+    }
+
+    BASIC.iter()
+         .min_by_key(|&&(_, rgb)| distance(rgb, (r, g, b)))
+         .map(|&(colour, _)| colour)
+         .unwrap_or(White)
+}
+
+/// How xterm shows the given colour.
+fn colour_to_rgb(colour: Colour) -> (u8, u8, u8) {
+    match colour {
+        Fixed(n)         => fixed_to_rgb(n),
+        RGB(r, g, b)     => (r, g, b),
+        basic            => BASIC.iter().find(|b| b.0 == basic).map(|b| b.1).unwrap_or((229, 229, 229)),
+    }
+}
+
+/// How xterm shows the given colour from the 256-colour palette.
+fn fixed_to_rgb(n: u8) -> (u8, u8, u8) {
+    if n < 8 {
+        BASIC[n as usize].1
+    }
+    else if n < 16 {
+        let (r, g, b) = BASIC[n as usize - 8].1;
+        (r.saturating_add(50), g.saturating_add(50), b.saturating_add(50))
+    }
+    else if n < 232 {
+        let n = n - 16;
+        (CUBE_LEVELS[(n / 36) as usize], CUBE_LEVELS[(n / 6 % 6) as usize], CUBE_LEVELS[(n % 6) as usize])
+    }
+    else {
+        let grey = 8 + (n - 232) * 10;
+        (grey, grey, grey)
+    }
+}
+
+/// The colour from the 256-colour palette nearest to the given one, out of
+/// the colour cube and the greyscale ramp.
+fn rgb_to_fixed(r: u8, g: u8, b: u8) -> u8 {
+    let level = |v: u8| CUBE_LEVELS.iter().enumerate()
+                                   .min_by_key(|&(_, &l)| (i32::from(l) - i32::from(v)).abs())
+                                   .map(|(i, _)| i as u8)
+                                   .unwrap_or(0);
+
+    let cube = 16 + 36 * level(r) + 6 * level(g) + level(b);
+
+    let average = (u32::from(r) + u32::from(g) + u32::from(b)) / 3;
+    let grey = 232 + (average.saturating_sub(3) / 10).min(23) as u8;
+
+    if distance(fixed_to_rgb(grey), (r, g, b)) < distance(fixed_to_rgb(cube), (r, g, b)) { grey }
+                                                                                     else { cube }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use output::lsc::Pair;
+
+    #[test]
+    fn exa_code() {
+        let mut colours = Colours::colourful(ColourScale::default());
+        assert!(colours.set_exa(&Pair { key: "tl", value: "32" }));
+        assert_eq!(colours.tree, Green.normal());
+    }
+
+    #[test]
+    fn ls_code_is_not_an_exa_code() {
+        let mut colours = Colours::colourful(ColourScale::default());
+        assert!(!colours.set_exa(&Pair { key: "di", value: "32" }));
+    }
+
+    #[test]
+    fn later_suffixes_win() {
+        let mut colours = Colours::plain();
+        colours.set_ls(&Pair { key: "*.gz",     value: "31" });
+        colours.set_ls(&Pair { key: "*.tar.gz", value: "32" });
+        assert_eq!(colours.suffix_style("a.tar.gz"), Some(Green.normal()));
+        assert_eq!(colours.suffix_style("a.gz"),     Some(Red.normal()));
+        assert_eq!(colours.suffix_style("a.tar"),    None);
+    }
+
+    #[test]
+    fn special_permissions_off() {
+        let mut colours = Colours::colourful(ColourScale::default());
+        colours.set_ls(&Pair { key: "tw", value: "0" });
+        colours.set_ls(&Pair { key: "su", value: "1" });
+        assert_eq!(colours.filetypes.sticky_other_writable, None);
+        assert_eq!(colours.filetypes.setuid, Some(Style::default().bold()));
+        assert_eq!(colours.filetypes.setgid, Some(Black.on(Yellow)));
+    }
+
+    #[test]
+    fn arrow_follows_punctuation() {
+        let mut colours = Colours::colourful(ColourScale::default());
+        assert!(colours.set_exa(&Pair { key: "xx", value: "31" }));
+        assert_eq!(colours.link_arrow, Red.normal());
+
+        assert!(colours.set_exa(&Pair { key: "la", value: "32" }));
+        assert_eq!(colours.link_arrow, Green.normal());
+        assert_eq!(colours.punctuation, Red.normal());
+    }
+
+    #[test]
+    fn blocks_scale() {
+        let mut colours = Colours::colourful(ColourScale { blocks: true, .. ColourScale::default() });
+        colours.size.scale_kilo = Red.normal();
+        colours.size.scale_mega = Green.normal();
+        assert_eq!(colours.blocks_style(2),    Red.normal());
+        assert_eq!(colours.blocks_style(2048), Green.normal());
+    }
+
+    #[test]
+    fn links_scale() {
+        let mut colours = Colours::colourful(ColourScale { links: true, .. ColourScale::default() });
+        colours.size.scale_byte = Red.normal();
+        colours.size.scale_kilo = Green.normal();
+        assert_eq!(colours.links_style(1, false),  Red.normal());
+        assert_eq!(colours.links_style(3, false),  Green.normal());
+        assert_eq!(colours.links_style(3, true),   colours.links.multi_link_file);
+    }
+
+    #[test]
+    fn without_strikethrough() {
+        let mut colours = Colours::plain();
+        colours.git.deleted = Red.bold().strikethrough();
+        colours.limit_attributes(Attributes { strikethrough: false, .. Attributes::default() });
+        assert_eq!(colours.git.deleted, Red.bold());
+    }
+
+    #[test]
+    fn first_name_wins() {
+        let mut colours = Colours::plain();
+        colours.names.push((glob::Pattern::new("*.test.js").unwrap(), Red.normal()));
+        colours.names.push((glob::Pattern::new("*.js").unwrap(),      Green.normal()));
+        assert_eq!(colours.name_style("a.test.js"), Some(Red.normal()));
+        assert_eq!(colours.name_style("a.js"),      Some(Green.normal()));
+        assert_eq!(colours.name_style("a.jsx"),     None);
+    }
+
+    #[test]
+    fn presets() {
+        for name in PRESETS {
+            assert!(Colours::preset(name, ColourScale::default()).is_some(), "Preset {} is missing", name);
         }
     }
+
+    #[test]
+    fn rgb_to_256() {
+        assert_eq!(limit_colour(RGB(255, 135, 0), ColourDepth::Fixed256), Fixed(208));
+        assert_eq!(limit_colour(RGB(128, 128, 128), ColourDepth::Fixed256), Fixed(244));
+    }
+
+    #[test]
+    fn to_basic() {
+        assert_eq!(limit_colour(RGB(250, 10, 20), ColourDepth::Basic), Red);
+        assert_eq!(limit_colour(Fixed(9), ColourDepth::Basic), Red);
+        assert_eq!(limit_colour(Fixed(18), ColourDepth::Basic), Blue);
+        assert_eq!(limit_colour(Fixed(244), ColourDepth::Basic), White);
+    }
+
+    #[test]
+    fn no_colours() {
+        let mut colours = Colours::colourful(ColourScale::default());
+        colours.limit_depth(ColourDepth::NoColours);
+        assert_eq!(colours.filetypes.directory, Style::default());
+    }
+
+    #[test]
+    fn true_colour_is_untouched() {
+        let mut colours = Colours::colourful(ColourScale::default());
+        colours.date = RGB(1, 2, 3).normal();
+        colours.limit_depth(ColourDepth::TrueColour);
+        assert_eq!(colours.date, RGB(1, 2, 3).normal());
+    }
+
+    #[test]
+    fn limits_every_style() {
+        let mut colours = Colours::colourful(ColourScale::default());
+        colours.limit_depth(ColourDepth::Basic);
+        assert_eq!(colours.punctuation, White.normal());
+        assert_eq!(colours.filetypes.directory, Blue.bold());
+    }
+
+    #[test]
+    fn overlaid() {
+        assert_eq!(overlay(Blue.bold(), Style::default().on(Red).underline()), Blue.on(Red).bold().underline());
+        assert_eq!(overlay(Blue.normal(), Green.normal()), Green.normal());
+    }
+
+    #[test]
+    fn default_preset() {
+        let scale = ColourScale { size: true, .. ColourScale::default() };
+        assert_eq!(Colours::preset("default", scale), Some(Colours::colourful(scale)));
+    }
+
+    fn scaled(gradient: bool) -> Colours {
+        let mut colours = Colours::colourful(ColourScale { size: true, .. ColourScale::default() });
+        colours.size.scale_kilo = RGB(0, 0, 0).normal();
+        colours.size.scale_mega = RGB(200, 0, 100).normal();
+        colours.size.scale_thresholds = [ 1000, 10_000, 1_000_000, 1_000_000_000 ];
+        colours.size.scale_gradient = gradient;
+        colours.depth = ColourDepth::TrueColour;
+        colours
+    }
+
+    #[test]
+    fn size_steps() {
+        let colours = scaled(false);
+        assert_eq!(colours.file_size(999), colours.size.scale_byte);
+        assert_eq!(colours.file_size(5000), colours.size.scale_kilo);
+        assert_eq!(colours.file_size(10_000), colours.size.scale_mega);
+        assert_eq!(colours.file_size(1 << 40), colours.size.scale_huge);
+    }
+
+    #[test]
+    fn size_gradient() {
+        let colours = scaled(true);
+        assert_eq!(colours.file_size(1000), RGB(0, 0, 0).normal());
+        assert_eq!(colours.file_size(3162), RGB(100, 0, 50).normal());
+        assert_eq!(colours.file_size(10_000), RGB(200, 0, 100).normal());
+    }
+
+    fn aged() -> Colours {
+        let mut colours = Colours::plain();
+        colours.age = Age { newest: RGB(0, 200, 0).bold(), oldest: RGB(100, 100, 100).normal(), newest_seconds: 10, oldest_seconds: 10_000 };
+        colours.depth = ColourDepth::TrueColour;
+        colours
+    }
+
+    #[test]
+    fn age_ends() {
+        assert_eq!(aged().age_style(5), RGB(0, 200, 0).bold());
+        assert_eq!(aged().age_style(-5), RGB(0, 200, 0).bold());
+        assert_eq!(aged().age_style(50_000), RGB(100, 100, 100).normal());
+    }
+
+    #[test]
+    fn age_middle() {
+        // Two thirds of the way from 10 to 10,000 seconds, on a
+        // logarithmic scale.
+        assert_eq!(aged().age_style(1000), RGB(66, 134, 66).normal());
+    }
+
+    #[test]
+    fn age_limited() {
+        let mut colours = aged();
+        colours.depth = ColourDepth::Fixed256;
+        assert_eq!(colours.age_style(0), Fixed(40).bold());
+    }
+
+    #[test]
+    fn age_without_colours() {
+        let mut colours = aged();
+        colours.age.oldest = Style::default().dimmed();
+        assert_eq!(colours.age_style(1000), Style::default().dimmed());
+        assert_eq!(colours.age_style(100), RGB(0, 200, 0).bold());
+    }
 }
@@ -64,10 +64,13 @@ use std::io::{Write, Error as IOError, Result as IOResult};
 use std::path::PathBuf;
 use std::vec::IntoIter as VecIntoIter;
 
+use scoped_threadpool::Pool;
+
 use fs::{Dir, File};
 use fs::dir_action::RecurseOptions;
 use fs::filter::FileFilter;
 use fs::feature::xattr::{Attribute, FileAttributes};
+use interrupt;
 use output::colours::Colours;
 use output::cell::TextCell;
 use output::tree::{TreeTrunk, TreeParams, TreeDepth};
@@ -137,7 +140,9 @@ impl<'a> AsRef<File<'a>> for Egg<'a> {
 
 
 impl<'a> Render<'a> {
-    pub fn render<W: Write>(self, w: &mut W) -> IOResult<()> {
+    /// Renders the files, getting their details on the threads of the
+    /// given pool.
+    pub fn render<W: Write>(self, pool: &mut Pool, w: &mut W) -> IOResult<()> {
         let mut rows = Vec::new();
 
         if let Some(ref table) = self.opts.table {
@@ -152,17 +157,19 @@ impl<'a> Render<'a> {
             // This is weird, but I can't find a way around it:
             // https://internals.rust-lang.org/t/should-option-mut-t-implement-copy/3715/6
             let mut table = Some(table);
-            self.add_files_to_table(&mut table, &mut rows, &self.files, TreeDepth::root());
+            self.add_files_to_table(pool, &mut table, &mut rows, &self.files, TreeDepth::root());
 
+            let mut buffer = String::new();
             for row in self.iterate_with_table(table.unwrap(), rows) {
-                writeln!(w, "{}", row.strings())?
+                row.write_line(w, &mut buffer)?
             }
         }
         else {
-            self.add_files_to_table(&mut None, &mut rows, &self.files, TreeDepth::root());
+            self.add_files_to_table(pool, &mut None, &mut rows, &self.files, TreeDepth::root());
 
+            let mut buffer = String::new();
             for row in self.iterate(rows) {
-                writeln!(w, "{}", row.strings())?
+                row.write_line(w, &mut buffer)?
             }
         }
 
@@ -170,20 +177,23 @@ impl<'a> Render<'a> {
     }
 
     /// Adds files to the table, possibly recursively. This is easily
-    /// parallelisable, and uses a pool of threads.
-    fn add_files_to_table<'dir>(&self, table: &mut Option<Table<'a>>, rows: &mut Vec<Row>, src: &Vec<File<'dir>>, depth: TreeDepth) {
-        use num_cpus;
-        use scoped_threadpool::Pool;
+    /// parallelisable, and uses the given pool of threads.
+    fn add_files_to_table<'dir>(&self, pool: &mut Pool, table: &mut Option<Table<'a>>, rows: &mut Vec<Row>, src: &Vec<File<'dir>>, depth: TreeDepth) {
         use std::sync::{Arc, Mutex};
         use fs::feature::xattr;
 
-        let mut pool = Pool::new(num_cpus::get() as u32);
+        let name_colours = self.colours.for_names();
         let mut file_eggs = Vec::new();
 
         pool.scoped(|scoped| {
             let file_eggs = Arc::new(Mutex::new(&mut file_eggs));
             let table = table.as_ref();
 
+            // Only list each file’s extended attributes if they’re going to
+            // be displayed, or if one of the columns needs to know they’re
+            // there.
+            let needs_xattrs = xattr::ENABLED && (self.opts.xattr || table.map_or(false, |t| t.needs_xattrs()));
+
             for file in src {
                 let file_eggs = file_eggs.clone();
 
@@ -191,7 +201,7 @@ impl<'a> Render<'a> {
                     let mut errors = Vec::new();
                     let mut xattrs = Vec::new();
 
-                    if xattr::ENABLED {
+                    if needs_xattrs {
                         match file.path.attributes() {
                             Ok(xs) => xattrs.extend(xs),
                             Err(e) => errors.push((e, None)),
@@ -208,7 +218,7 @@ impl<'a> Render<'a> {
 
                     if let Some(r) = self.recurse {
                         if file.is_directory() && r.tree && !r.is_too_deep(depth.0) {
-                            match file.to_dir(false) {
+                            match file.to_dir(self.filter.needs_git()) {
                                 Ok(d)  => { dir = Some(d); },
                                 Err(e) => { errors.push((e, None)) },
                             }
@@ -223,56 +233,79 @@ impl<'a> Render<'a> {
 
         self.filter.sort_files(&mut file_eggs);
 
+        // Files above the minimum depth don’t get rows of their own, and
+        // the files below them get moved up in the tree to take their place.
+        let min_depth = self.recurse.and_then(|r| r.min_depth).unwrap_or(0);
+        let too_shallow = self.recurse.map_or(false, |r| r.is_too_shallow(depth.0));
+
         for (tree_params, egg) in depth.iterate_over(file_eggs.into_iter()) {
             let mut files = Vec::new();
             let mut errors = egg.errors;
 
-            if let (Some(ref mut t), Some(ref row)) = (table.as_mut(), egg.table_row.as_ref()) {
-                t.add_widths(row);
-            }
+            if !too_shallow {
+                if let (Some(ref mut t), Some(ref row)) = (table.as_mut(), egg.table_row.as_ref()) {
+                    t.add_widths(row);
+                }
+
+                let row = Row {
+                    tree:   tree_params.shallower(min_depth),
+                    cells:  egg.table_row,
+                    name:   self.style.for_file(&egg.file, &name_colours)
+                                      .with_link_paths()
+                                      .paint().promote(),
+                };
 
-            let row = Row {
-                tree:   tree_params,
-                cells:  egg.table_row,
-                name:   self.style.for_file(&egg.file, self.colours)
-                                  .with_link_paths()
-                                  .paint().promote(),
-            };
+                rows.push(row);
+            }
 
-            rows.push(row);
+            // After an interrupt, the directories that haven’t been read yet
+            // get left out, and the tree so far is printed as it is.
+            let dir = if interrupt::requested() { None } else { egg.dir.as_ref() };
 
-            if let Some(ref dir) = egg.dir {
-                for file_to_add in dir.files(self.filter.dot_filter) {
+            if let Some(dir) = dir {
+                for file_to_add in dir.files_in_parallel(self.filter.dot_filter, pool) {
                     match file_to_add {
                         Ok(f)          => files.push(f),
                         Err((path, e)) => errors.push((e, Some(path)))
                     }
                 }
 
+                self.filter.normalize_files(&mut files);
                 self.filter.filter_child_files(&mut files);
 
+                // Deduplicating keeps the first file of each group, so the
+                // files have to be put in order before it happens.
+                if self.filter.dedupe {
+                    self.filter.sort_files(&mut files);
+                    self.filter.dedupe_files(&mut files);
+                }
+
                 if !files.is_empty() {
-                    for xattr in egg.xattrs {
-                        rows.push(self.render_xattr(xattr, TreeParams::new(depth.deeper(), false)));
+                    if !too_shallow {
+                        for xattr in egg.xattrs {
+                            rows.push(self.render_xattr(xattr, TreeParams::new(depth.deeper(), false).shallower(min_depth)));
+                        }
                     }
 
                     for (error, path) in errors {
-                        rows.push(self.render_error(&error, TreeParams::new(depth.deeper(), false), path));
+                        rows.push(self.render_error(&error, TreeParams::new(depth.deeper(), false).shallower(min_depth), path));
                     }
 
-                    self.add_files_to_table(table, rows, &files, depth.deeper());
+                    self.add_files_to_table(pool, table, rows, &files, depth.deeper());
                     continue;
                 }
             }
 
-            let count = egg.xattrs.len();
-            for (index, xattr) in egg.xattrs.into_iter().enumerate() {
-                rows.push(self.render_xattr(xattr, TreeParams::new(depth.deeper(), errors.is_empty() && index == count - 1)));
+            if !too_shallow {
+                let count = egg.xattrs.len();
+                for (index, xattr) in egg.xattrs.into_iter().enumerate() {
+                    rows.push(self.render_xattr(xattr, TreeParams::new(depth.deeper(), errors.is_empty() && index == count - 1).shallower(min_depth)));
+                }
             }
 
             let count = errors.len();
             for (index, (error, path)) in errors.into_iter().enumerate() {
-                rows.push(self.render_error(&error, TreeParams::new(depth.deeper(), index == count - 1), path));
+                rows.push(self.render_error(&error, TreeParams::new(depth.deeper(), index == count - 1).shallower(min_depth), path));
             }
         }
     }
@@ -367,7 +400,7 @@ impl<'a> Iterator for TableIter<'a> {
                 };
 
             for tree_part in self.tree_trunk.new_row(row.tree) {
-                cell.push(self.colours.punctuation.paint(tree_part.ascii_art()), 4);
+                cell.push(self.colours.tree.paint(tree_part.ascii_art()), 4);
             }
 
             // If any tree characters have been printed, then add an extra
@@ -397,7 +430,7 @@ impl<'a> Iterator for Iter<'a> {
             let mut cell = TextCell::default();
 
             for tree_part in self.tree_trunk.new_row(row.tree) {
-                cell.push(self.colours.punctuation.paint(tree_part.ascii_art()), 4);
+                cell.push(self.colours.tree.paint(tree_part.ascii_art()), 4);
             }
 
             // If any tree characters have been printed, then add an extra
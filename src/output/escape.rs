@@ -1,3 +1,8 @@
+use std::ffi::OsStr;
+use std::mem;
+use std::os::unix::ffi::OsStrExt;
+use std::str;
+
 use ansi_term::{ANSIString, Style};
 
 
@@ -6,20 +11,91 @@ pub fn escape<'a>(string: String, bits: &mut Vec<ANSIString<'a>>, good: Style, b
         bits.push(good.paint(string));
     }
     else {
+        // The printable characters between control characters get painted
+        // together, rather than allocating a string for each one.
+        let mut run = String::new();
+
         for c in string.chars() {
             // The `escape_default` method on `char` is *almost* what we want here, but
             // it still escapes non-ASCII UTF-8 characters, which are still printable.
 
             if c >= 0x20 as char {
-                // TODO: This allocates way too much,
-                // hence the `all` check above.
-                let mut s = String::new();
-                s.push(c);
-                bits.push(good.paint(s));
+                run.push(c);
             } else {
+                if !run.is_empty() {
+                    bits.push(good.paint(mem::replace(&mut run, String::new())));
+                }
+
                 let s = c.escape_default().collect::<String>();
                 bits.push(bad.paint(s));
             }
         }
+
+        if !run.is_empty() {
+            bits.push(good.paint(run));
+        }
+    }
+}
+
+
+/// Escapes a file name or path straight from its bytes, which don’t have
+/// to be valid UTF-8. The parts that are valid get escaped in the same way
+/// as `escape`; the bytes that aren’t get shown as `\x` escapes in the bad
+/// style, instead of all turning into the same replacement character.
+pub fn escape_os<'a>(name: &OsStr, bits: &mut Vec<ANSIString<'a>>, good: Style, bad: Style) {
+    let mut bytes = name.as_bytes();
+
+    while !bytes.is_empty() {
+        let (valid_len, invalid_len) = match str::from_utf8(bytes) {
+            Ok(_)   => (bytes.len(), 0),
+            Err(e)  => (e.valid_up_to(), e.error_len().unwrap_or(bytes.len() - e.valid_up_to())),
+        };
+
+        let (valid, rest) = bytes.split_at(valid_len);
+        if let Ok(valid) = str::from_utf8(valid) {
+            if !valid.is_empty() {
+                escape(valid.to_owned(), bits, good, bad);
+            }
+        }
+
+        let (invalid, rest) = rest.split_at(invalid_len);
+        for byte in invalid {
+            bits.push(bad.paint(format!("\\x{:02x}", byte)));
+        }
+
+        bytes = rest;
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::ffi::OsStr;
+
+    fn escaped(bytes: &[u8]) -> String {
+        let mut bits = Vec::new();
+        escape_os(OsStr::from_bytes(bytes), &mut bits, Style::default(), Style::default());
+        bits.iter().map(|b| b.to_string()).collect()
+    }
+
+    #[test]
+    fn valid() {
+        assert_eq!(escaped(b"file.txt"), "file.txt");
+    }
+
+    #[test]
+    fn control_characters() {
+        assert_eq!(escaped(b"new\nline"), "new\\nline");
+    }
+
+    #[test]
+    fn invalid_bytes() {
+        assert_eq!(escaped(b"caf\xe9.txt"), "caf\\xe9.txt");
+    }
+
+    #[test]
+    fn truncated_character() {
+        assert_eq!(escaped(b"end\xe2\x82"), "end\\xe2\\x82");
     }
 }
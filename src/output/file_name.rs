@@ -1,11 +1,13 @@
 use std::path::Path;
 
 use ansi_term::{ANSIString, Style};
+use users::uid_t;
 
 use fs::{File, FileTarget};
 use info::filetype::FileExtensions;
 use output::Colours;
-use output::escape;
+use output::colours::overlay;
+use output::escape_os;
 use output::cell::TextCellContents;
 
 
@@ -18,6 +20,9 @@ pub struct FileStyle {
 
     /// Mapping of file extensions to colours, to highlight regular files.
     pub exts: FileExtensions,
+
+    /// Whether to highlight files that someone else owns.
+    pub owners: Owners,
 }
 
 impl FileStyle {
@@ -30,6 +35,7 @@ impl FileStyle {
             link_style: LinkStyle::JustFilenames,
             exts:       &self.exts,
             classify:   self.classify,
+            owners:     self.owners,
             target:     if file.is_link() { Some(file.link_target()) }
                                      else { None }
         }
@@ -72,6 +78,26 @@ impl Default for Classify {
 }
 
 
+/// Whether to highlight the names of files that the user doesn’t own, so
+/// files they can’t change stand out.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum Owners {
+
+    /// Paint every file’s name the same way, whoever owns it.
+    Ignore,
+
+    /// Add a style to the names of files that aren’t owned by the user with
+    /// this ID, with a different one for files owned by root.
+    Highlight(uid_t),
+}
+
+impl Default for Owners {
+    fn default() -> Owners {
+        Owners::Ignore
+    }
+}
+
+
 
 /// A **file name** holds all the information necessary to display the name
 /// of the given file. This is used in all of the views.
@@ -94,6 +120,9 @@ pub struct FileName<'a, 'dir: 'a> {
 
     /// Mapping of file extensions to colours, to highlight regular files.
     exts: &'a FileExtensions,
+
+    /// Whether to highlight files that someone else owns.
+    owners: Owners,
 }
 
 
@@ -131,7 +160,7 @@ impl<'a, 'dir> FileName<'a, 'dir> {
             match *target {
                 FileTarget::Ok(ref target) => {
                     bits.push(Style::default().paint(" "));
-                    bits.push(self.colours.punctuation.paint("->"));
+                    bits.push(self.colours.link_arrow.paint("->"));
                     bits.push(Style::default().paint(" "));
 
                     if let Some(parent) = target.path.parent() {
@@ -146,6 +175,7 @@ impl<'a, 'dir> FileName<'a, 'dir> {
                             link_style: LinkStyle::FullLinkPaths,
                             classify: Classify::JustFilenames,
                             exts: self.exts,
+                            owners: self.owners,
                         };
 
                         for bit in target.coloured_file_name() {
@@ -158,7 +188,7 @@ impl<'a, 'dir> FileName<'a, 'dir> {
                     bits.push(Style::default().paint(" "));
                     bits.push(self.colours.broken_arrow.paint("->"));
                     bits.push(Style::default().paint(" "));
-                    escape(broken_path.display().to_string(), &mut bits, self.colours.broken_filename, self.colours.control_char.underline());
+                    escape_os(broken_path.as_os_str(), &mut bits, self.colours.broken_filename, self.colours.control_char.underline());
                 },
 
                 FileTarget::Err(_) => {
@@ -172,6 +202,16 @@ impl<'a, 'dir> FileName<'a, 'dir> {
             }
         }
 
+        if self.file.duplicates > 0 {
+            bits.push(Style::default().paint(" "));
+            bits.push(self.colours.punctuation.paint(format!("(+{})", self.file.duplicates)));
+        }
+
+        if self.file.normalization_clash {
+            bits.push(Style::default().paint(" "));
+            bits.push(self.colours.punctuation.paint("(differs only in normalization)"));
+        }
+
         bits.into()
     }
 
@@ -185,7 +225,7 @@ impl<'a, 'dir> FileName<'a, 'dir> {
             bits.push(self.colours.symlink_path.paint("/"));
         }
         else if coconut >= 1 {
-            escape(parent.to_string_lossy().to_string(), bits, self.colours.symlink_path, self.colours.control_char);
+            escape_os(parent.as_os_str(), bits, self.colours.symlink_path, self.colours.control_char);
             bits.push(self.colours.symlink_path.paint("/"));
         }
     }
@@ -221,41 +261,92 @@ impl<'a, 'dir> FileName<'a, 'dir> {
     /// So in that situation, those characters will be escaped and highlighted in
     /// a different colour.
     fn coloured_file_name<'unused>(&self) -> Vec<ANSIString<'unused>> {
-        let file_style = self.style();
+        let file_style = self.owner_style(self.duplicate_style(self.style()));
         let mut bits = Vec::new();
-        escape(self.file.name.clone(), &mut bits, file_style, self.colours.control_char);
+        escape_os(self.file.raw_name(), &mut bits, file_style, self.colours.control_char);
         bits
     }
 
 
+    /// Adds the style for files that share their inode with other files to
+    /// the given style, if this is one of them.
+    fn duplicate_style(&self, style: Style) -> Style {
+        if self.file.duplicates > 0 || self.file.links().multiple {
+            overlay(style, self.colours.links.duplicate_file)
+        }
+        else {
+            style
+        }
+    }
+
+    /// Adds the style for files owned by someone else to the given style,
+    /// if files are being highlighted by their owners.
+    fn owner_style(&self, style: Style) -> Style {
+        if let Owners::Highlight(you) = self.owners {
+            let owner = self.file.user().0;
+
+            if owner != you && owner == 0 {
+                return overlay(style, self.colours.users.owned_by_root);
+            }
+            else if owner != you {
+                return overlay(style, self.colours.users.owned_by_someone_else);
+            }
+        }
+
+        style
+    }
+
     /// Figures out which colour to paint the filename part of the output,
     /// depending on which “type” of file it appears to be -- either from the
     /// class on the filesystem or from its name.
     pub fn style(&self) -> Style {
 
         // Override the style with the “broken link” style when this file is
-        // a link that we can’t follow for whatever reason. This is used when
-        // there’s no other place to show that the link doesn’t work.
-        if let LinkStyle::JustFilenames = self.link_style {
-            if let Some(ref target) = self.target {
-                if target.is_broken() {
-                    return self.colours.broken_arrow;
-                }
+        // a link that we can’t follow for whatever reason, the way `ls`
+        // colours orphaned links, even when the target gets shown too.
+        if let Some(ref target) = self.target {
+            if target.is_broken() {
+                return self.colours.broken_symlink;
             }
         }
 
+        // Colouring names by age replaces the colours for types of file.
+        if self.colours.scale.age_names {
+            return self.colours.age_style(self.file.modified_time().seconds_ago());
+        }
+
         // Otherwise, just apply a bunch of rules in order. For example,
         // executable image files should be executable rather than images.
+        if self.file.is_directory() {
+            return self.directory_style();
+        }
+        else if let Some(style) = self.setid_style() {
+            return style;
+        }
+
         match self.file {
-            f if f.is_directory()        => self.colours.filetypes.directory,
-            f if f.is_executable_file()  => self.colours.filetypes.executable,
-            f if f.is_link()             => self.colours.filetypes.symlink,
-            f if f.is_pipe()             => self.colours.filetypes.pipe,
+            f if f.is_executable_file()  => return self.colours.filetypes.executable,
+            f if f.is_link()             => return self.colours.filetypes.symlink,
+            f if f.is_pipe()             => return self.colours.filetypes.pipe,
             f if f.is_char_device()
-               | f.is_block_device()     => self.colours.filetypes.device,
-            f if f.is_socket()           => self.colours.filetypes.socket,
-            f if !f.is_file()            => self.colours.filetypes.special,
+               | f.is_block_device()     => return self.colours.filetypes.device,
+            f if f.is_socket()           => return self.colours.filetypes.socket,
+            f if !f.is_file()            => return self.colours.filetypes.special,
+            _                            => {},
+        }
 
+        // Patterns and suffixes from the theme and `LS_COLORS` come before
+        // exa’s own idea of what sort of file something is, so themes made
+        // for `ls` look the same.
+        if let Some(style) = self.colours.name_style(&self.file.name) {
+            return style;
+        }
+
+        if let Some(style) = self.colours.suffix_style(&self.file.name) {
+            return style;
+        }
+
+        match self.file {
             f if self.exts.is_immediate(f)   => self.colours.filetypes.immediate,
             f if self.exts.is_image(f)       => self.colours.filetypes.image,
             f if self.exts.is_video(f)       => self.colours.filetypes.video,
@@ -269,4 +360,41 @@ impl<'a, 'dir> FileName<'a, 'dir> {
             _                                => self.colours.filetypes.normal,
         }
     }
+
+    /// The style for a directory, which depends on whether it’s sticky or
+    /// writable by anyone.
+    fn directory_style(&self) -> Style {
+        let types = &self.colours.filetypes;
+        let perms = self.file.permissions();
+
+        let style = match (perms.sticky, perms.other_write) {
+            (true,  true)   => types.sticky_other_writable,
+            (false, true)   => types.other_writable,
+            (true,  false)  => types.sticky,
+            (false, false)  => None,
+        };
+
+        style.unwrap_or(types.directory)
+    }
+
+    /// The style for a regular file with its setuid or setgid bit set, if
+    /// they haven’t been turned off.
+    fn setid_style(&self) -> Option<Style> {
+        if !self.file.is_file() {
+            return None;
+        }
+
+        let types = &self.colours.filetypes;
+        let perms = self.file.permissions();
+
+        if perms.setuid && types.setuid.is_some() {
+            types.setuid
+        }
+        else if perms.setgid {
+            types.setgid
+        }
+        else {
+            None
+        }
+    }
 }
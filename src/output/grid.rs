@@ -30,22 +30,7 @@ pub struct Render<'a> {
 
 impl<'a> Render<'a> {
     pub fn render<W: Write>(&self, w: &mut W) -> IOResult<()> {
-        let mut grid = tg::Grid::new(tg::GridOptions {
-            direction:  self.opts.direction(),
-            filling:    tg::Filling::Spaces(2),
-        });
-
-        grid.reserve(self.files.len());
-
-        for file in self.files.iter() {
-            let filename = self.style.for_file(file, self.colours).paint();
-            let width = filename.width();
-
-            grid.add(tg::Cell {
-                contents:  filename.strings().to_string(),
-                width:     *width,
-            });
-        }
+        let grid = make_grid(&self.files, self.colours, self.style, self.opts);
 
         if let Some(display) = grid.fit_into_width(self.opts.console_width) {
             write!(w, "{}", display)
@@ -54,11 +39,54 @@ impl<'a> Render<'a> {
             // File names too long for a grid - drop down to just listing them!
             // This isn’t *quite* the same as the lines view, which also
             // displays full link paths.
+            let mut buffer = String::new();
             for file in self.files.iter() {
                 let name_cell = self.style.for_file(file, self.colours).paint();
-                writeln!(w, "{}", name_cell.strings())?;
+                name_cell.write_line(w, &mut buffer)?;
             }
             Ok(())
         }
     }
 }
+
+
+fn make_grid(files: &[File], colours: &Colours, style: &FileStyle, opts: &Options) -> tg::Grid {
+    let mut grid = tg::Grid::new(tg::GridOptions {
+        direction:  opts.direction(),
+        filling:    tg::Filling::Spaces(2),
+    });
+
+    grid.reserve(files.len());
+
+    for file in files.iter() {
+        let filename = style.for_file(file, colours).paint();
+        let width = filename.width();
+
+        grid.add(tg::Cell {
+            contents:  filename.strings().to_string(),
+            width:     *width,
+        });
+    }
+
+    grid
+}
+
+/// Returns how many rows the given files would take up in the grid view.
+/// This tries each number of columns in turn, the same way the
+/// grid-details view does, and stops at the first one that doesn’t fit.
+pub fn row_count(files: &[File], colours: &Colours, style: &FileStyle, opts: &Options) -> usize {
+    let grid = make_grid(files, colours, style, opts);
+    let mut columns = 1;
+
+    while columns < files.len() {
+        let display = grid.fit_into_columns(columns + 1);
+        if display.is_complete() && display.width() <= opts.console_width {
+            columns += 1;
+        }
+        else {
+            break;
+        }
+    }
+
+    (files.len() + columns - 1) / columns
+}
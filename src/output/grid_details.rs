@@ -48,17 +48,22 @@ impl<'a> Render<'a> {
         let (first_table, _) = self.make_table(options, &drender);
 
         let rows = self.files.iter()
-                       .map(|file| first_table.row_for_file(file, file_has_xattrs(file)))
+                       .map(|file| first_table.row_for_file(file, first_table.needs_xattrs() && file_has_xattrs(file)))
                        .collect::<Vec<TableRow>>();
 
+        let name_colours = self.colours.for_names();
         let file_names = self.files.iter()
-                             .map(|file| self.style.for_file(file, self.colours).paint().promote())
+                             .map(|file| self.style.for_file(file, &name_colours).paint().promote())
                              .collect::<Vec<TextCell>>();
 
-        let mut last_working_table = self.make_grid(1, options, &file_names, rows.clone(), &drender);
+        // Each number of columns gets tried with a grid that only knows how
+        // wide its cells are, which gets worked out from the widths of the
+        // rows that are already known. Only the grid that gets picked has
+        // its cells rendered.
+        let mut last_working_count = 1;
 
         for column_count in 2.. {
-            let grid = self.make_grid(column_count, options, &file_names, rows.clone(), &drender);
+            let grid = self.make_grid(column_count, options, &file_names, &rows, &drender, false);
 
             let the_grid_fits = {
                 let d = grid.fit_into_columns(column_count);
@@ -66,14 +71,15 @@ impl<'a> Render<'a> {
             };
 
             if the_grid_fits {
-                last_working_table = grid;
+                last_working_count = column_count;
             }
             else {
-                return write!(w, "{}", last_working_table.fit_into_columns(column_count - 1));
+                break;
             }
         }
 
-        Ok(())
+        let grid = self.make_grid(last_working_count, options, &file_names, &rows, &drender, true);
+        write!(w, "{}", grid.fit_into_columns(last_working_count))
     }
 
     fn make_table<'t>(&'a self, options: &'a TableOptions, drender: &DetailsRender) -> (Table<'a>, Vec<DetailsRow>) {
@@ -89,11 +95,16 @@ impl<'a> Render<'a> {
         (table, rows)
     }
 
-    fn make_grid(&'a self, column_count: usize, options: &'a TableOptions, file_names: &[TextCell], rows: Vec<TableRow>, drender: &DetailsRender) -> grid::Grid {
+    /// Lays out the rows as the given number of tables side by side. Each
+    /// table’s column widths get tracked as its rows are added to it, so
+    /// the width of every line in it is known without rendering anything.
+    /// Unless `render` is true, the grid’s cells get their widths but no
+    /// contents, which is all that’s needed to see whether it fits.
+    fn make_grid(&'a self, column_count: usize, options: &'a TableOptions, file_names: &[TextCell], rows: &[TableRow], drender: &DetailsRender, render: bool) -> grid::Grid {
 
         let mut tables = Vec::new();
         for _ in 0 .. column_count {
-            tables.push(self.make_table(options, drender));
+            tables.push((self.make_table(options, drender), Vec::new()));
         }
 
         let mut num_cells = rows.len();
@@ -104,7 +115,7 @@ impl<'a> Render<'a> {
         let original_height = divide_rounding_up(rows.len(), column_count);
         let height = divide_rounding_up(num_cells, column_count);
 
-        for (i, (file_name, row)) in file_names.iter().zip(rows.into_iter()).enumerate() {
+        for (i, row) in rows.iter().enumerate() {
             let index = if self.grid.across {
                     i % column_count
                 }
@@ -112,14 +123,30 @@ impl<'a> Render<'a> {
                     i / original_height
                 };
 
-            let (ref mut table, ref mut rows) = tables[index];
-            table.add_widths(&row);
-            let details_row = drender.render_file(row, file_name.clone(), TreeParams::new(TreeDepth::root(), false));
-            rows.push(details_row);
+            let ((ref mut table, _), ref mut members) = tables[index];
+            table.add_widths(row);
+            members.push(i);
         }
 
-        let columns: Vec<_> = tables.into_iter().map(|(table, details_rows)| {
-            drender.iterate_with_table(table, details_rows).collect::<Vec<_>>()
+        let columns: Vec<Vec<grid::Cell>> = tables.into_iter().map(|((table, mut details_rows), members)| {
+            if render {
+                for i in members {
+                    details_rows.push(drender.render_file(rows[i].clone(), file_names[i].clone(), TreeParams::new(TreeDepth::root(), false)));
+                }
+
+                drender.iterate_with_table(table, details_rows)
+                       .map(|cell| grid::Cell { contents: ANSIStrings(&cell.contents).to_string(), width: *cell.width })
+                       .collect()
+            }
+            else {
+                let total_width = table.widths().total();
+                let header = details_rows.iter().map(|row| *row.name.width);
+                let names = members.into_iter().map(|i| *file_names[i].width);
+
+                header.chain(names)
+                      .map(|width| grid::Cell { contents: String::new(), width: total_width + width })
+                      .collect()
+            }
         }).collect();
 
         let direction = if self.grid.across { grid::Direction::LeftToRight }
@@ -131,27 +158,19 @@ impl<'a> Render<'a> {
         });
 
         if self.grid.across {
-            for row in 0 .. height {
-                for column in &columns {
-                    if row < column.len() {
-                        let cell = grid::Cell {
-                            contents: ANSIStrings(&column[row].contents).to_string(),
-                            width:    *column[row].width,
-                        };
+            let mut columns: Vec<_> = columns.into_iter().map(|column| column.into_iter()).collect();
 
+            for _ in 0 .. height {
+                for column in &mut columns {
+                    if let Some(cell) = column.next() {
                         grid.add(cell);
                     }
                 }
             }
         }
         else {
-            for column in &columns {
-                for cell in column.iter() {
-                    let cell = grid::Cell {
-                        contents: ANSIStrings(&cell.contents).to_string(),
-                        width:    *cell.width,
-                    };
-
+            for column in columns {
+                for cell in column {
                     grid.add(cell);
                 }
             }
@@ -1,7 +1,5 @@
 use std::io::{Write, Result as IOResult};
 
-use ansi_term::ANSIStrings;
-
 use fs::File;
 
 use output::file_name::{FileName, FileStyle};
@@ -17,9 +15,10 @@ pub struct Render<'a> {
 
 impl<'a> Render<'a> {
     pub fn render<W: Write>(&self, w: &mut W) -> IOResult<()> {
+        let mut buffer = String::new();
         for file in &self.files {
             let name_cell = self.render_file(file).paint();
-            writeln!(w, "{}", ANSIStrings(&name_cell))?;
+            name_cell.write_line(w, &mut buffer)?;
         }
 
         Ok(())
@@ -0,0 +1,212 @@
+//! Parsing the `LS_COLORS` environment variable, which GNU `ls` and other
+//! programs use to pick the colours of file names.
+//!
+//! The variable is a colon-separated list of `key=value` pairs. Each key is
+//! either a two-letter code for a type of file, such as `di` for directories,
+//! or a pattern such as `*.tar` that matches the ends of file names. Each
+//! value is a list of ANSI style codes, separated by semicolons, the same as
+//! the ones that go between `ESC[` and `m` in an escape sequence. As well as
+//! those, exa understands colours written as `#rrggbb`, which set the
+//! foreground colour.
+
+use ansi_term::Style;
+use ansi_term::Colour::*;
+use ansi_term::Colour;
+
+
+/// The contents of an `LS_COLORS`-style variable.
+pub struct LSColors<'var>(pub &'var str);
+
+impl<'var> LSColors<'var> {
+
+    /// Calls the given function with each of the well-formed pairs in the
+    /// variable, in order. Anything that isn’t a key and a value separated
+    /// by a single `=` gets skipped, the same as `ls` skips it.
+    pub fn each_pair<C>(&self, mut callback: C) where C: FnMut(Pair<'var>) {
+        for next in self.0.split(':') {
+            let bits = next.split('=').collect::<Vec<_>>();
+
+            if bits.len() == 2 && !bits[0].is_empty() && !bits[1].is_empty() {
+                callback(Pair { key: bits[0], value: bits[1] });
+            }
+        }
+    }
+}
+
+
+/// One `key=value` pair from the variable.
+#[derive(PartialEq, Debug)]
+pub struct Pair<'var> {
+    pub key: &'var str,
+    pub value: &'var str,
+}
+
+impl<'var> Pair<'var> {
+
+    /// Turns this pair’s value into a style. Codes that exa can’t show,
+    /// or doesn’t recognise, get ignored rather than treated as errors.
+    pub fn to_style(&self) -> Style {
+        let mut style = Style::default();
+        let mut foreground = None;
+        let mut background = None;
+        let mut codes = self.value.split(';').map(|code| code.parse::<u8>().ok());
+
+        // The colours get kept apart from the rest of the style until the
+        // end, as a later code can take them away again.
+        for hex in self.value.split(';').filter(|code| code.starts_with('#')) {
+            if let Some(colour) = hex_colour(hex) {
+                foreground = Some(colour);
+            }
+        }
+
+        while let Some(code) = codes.next() {
+            match code {
+                Some(0)   => { style = Style::default(); foreground = None; background = None },
+                Some(1)   => style = style.bold(),
+                Some(2)   => style = style.dimmed(),
+                Some(3)   => style = style.italic(),
+                Some(4)   => style = style.underline(),
+                Some(5)   => style = style.blink(),
+                Some(7)   => style = style.reverse(),
+                Some(8)   => style = style.hidden(),
+                Some(9)   => style = style.strikethrough(),
+
+                Some(n) if n >= 30 && n <= 37    => foreground = Some(basic_colour(n - 30)),
+                Some(n) if n >= 40 && n <= 47    => background = Some(basic_colour(n - 40)),
+                Some(n) if n >= 90 && n <= 97    => foreground = Some(Fixed(n - 90 + 8)),
+                Some(n) if n >= 100 && n <= 107  => background = Some(Fixed(n - 100 + 8)),
+
+                Some(38)  => foreground = extended_colour(&mut codes),
+                Some(48)  => background = extended_colour(&mut codes),
+                Some(39)  => foreground = None,
+                Some(49)  => background = None,
+
+                _         => {},
+            }
+        }
+
+        if let Some(colour) = foreground {
+            style = style.fg(colour);
+        }
+
+        if let Some(colour) = background {
+            style = style.on(colour);
+        }
+
+        style
+    }
+}
+
+
+/// One of the eight colours that have their own codes.
+fn basic_colour(number: u8) -> Colour {
+    match number {
+        0 => Black,
+        1 => Red,
+        2 => Green,
+        3 => Yellow,
+        4 => Blue,
+        5 => Purple,
+        6 => Cyan,
+        _ => White,
+    }
+}
+
+/// Reads the codes that follow a `38` or `48`, which pick a colour from the
+/// 256-colour palette with `5;n`, or a 24-bit colour with `2;r;g;b`.
+fn extended_colour<I>(codes: &mut I) -> Option<Colour>
+where I: Iterator<Item=Option<u8>> {
+    match codes.next() {
+        Some(Some(5))  => codes.next().and_then(|n| n).map(Fixed),
+        Some(Some(2))  => match (codes.next(), codes.next(), codes.next()) {
+            (Some(Some(r)), Some(Some(g)), Some(Some(b)))  => Some(RGB(r, g, b)),
+            _                                              => None,
+        },
+        _              => None,
+    }
+}
+
+/// Reads a colour written as `#rrggbb`.
+fn hex_colour(hex: &str) -> Option<Colour> {
+    if hex.len() != 7 || !hex[1..].chars().all(|c| c.is_digit(16)) {
+        return None;
+    }
+
+    let channel = |index: usize| u8::from_str_radix(&hex[index .. index + 2], 16).ok();
+    match (channel(1), channel(3), channel(5)) {
+        (Some(r), Some(g), Some(b))  => Some(RGB(r, g, b)),
+        _                            => None,
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn pairs<'a>(input: &'a str) -> Vec<Pair<'a>> {
+        let mut pairs = Vec::new();
+        LSColors(input).each_pair(|pair| pairs.push(pair));
+        pairs
+    }
+
+    fn style(value: &str) -> Style {
+        Pair { key: "di", value }.to_style()
+    }
+
+    #[test]
+    fn splits_pairs() {
+        assert_eq!(pairs("di=34:*.txt=01;32"), vec![ Pair { key: "di", value: "34" },
+                                                     Pair { key: "*.txt", value: "01;32" } ]);
+    }
+
+    #[test]
+    fn skips_malformed_pairs() {
+        assert_eq!(pairs("di:=34:ln=:a=b=c::ex=32"), vec![ Pair { key: "ex", value: "32" } ]);
+    }
+
+    #[test]
+    fn bold_blue() {
+        assert_eq!(style("01;34"), Blue.bold());
+    }
+
+    #[test]
+    fn background() {
+        assert_eq!(style("37;41"), White.on(Red));
+    }
+
+    #[test]
+    fn bright() {
+        assert_eq!(style("91"), Fixed(9).normal());
+    }
+
+    #[test]
+    fn fixed() {
+        assert_eq!(style("38;5;208;48;5;17"), Fixed(208).on(Fixed(17)));
+    }
+
+    #[test]
+    fn reset() {
+        assert_eq!(style("1;0;4"), Style::default().underline());
+    }
+
+    #[test]
+    fn rgb() {
+        assert_eq!(style("38;2;255;128;0;1"), RGB(255, 128, 0).bold());
+    }
+
+    #[test]
+    fn rgb_background() {
+        assert_eq!(style("48;2;0;0;16"), Style::default().on(RGB(0, 0, 16)));
+    }
+
+    #[test]
+    fn hex() {
+        assert_eq!(style("#ff8800;4"), RGB(255, 136, 0).underline());
+    }
+
+    #[test]
+    fn bad_hex() {
+        assert_eq!(style("#ff88"), Style::default());
+    }
+}
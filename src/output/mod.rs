@@ -1,14 +1,17 @@
 use output::file_name::FileStyle;
 
 pub use self::cell::{TextCell, TextCellContents, DisplayWidth};
-pub use self::colours::Colours;
-pub use self::escape::escape;
+pub use self::colours::{Colours, Attributes, ColourDepth, ColourScale, PlainColumns, COLUMN_NAMES, PRESETS};
+pub use self::escape::{escape, escape_os};
+pub use self::lsc::LSColors;
 
 pub mod details;
 pub mod file_name;
 pub mod grid_details;
 pub mod grid;
 pub mod lines;
+pub mod lsc;
+pub mod porcelain;
 pub mod table;
 pub mod time;
 
@@ -35,4 +38,5 @@ pub enum Mode {
     Details(details::Options),
     GridDetails(grid::Options, details::Options),
     Lines,
+    Porcelain(porcelain::Format),
 }
@@ -0,0 +1,195 @@
+use std::fmt::Write as FmtWrite;
+use std::fs::read_link;
+use std::io::{Write, Result as IOResult};
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::PermissionsExt;
+use std::str;
+
+use ansi_term::{ANSIString, ANSIStrings};
+
+use fs::File;
+use fs::fields as f;
+use output::escape_os;
+use output::file_name::FileStyle;
+use super::colours::Colours;
+
+
+/// The porcelain view prints each file on a line of its own, in a fixed
+/// format meant for other programs to read rather than people.
+pub struct Render<'a> {
+    pub files: Vec<File<'a>>,
+    pub colours: &'a Colours,
+    pub style: &'a FileStyle,
+    pub format: Format,
+}
+
+/// Which machine-readable format to print the files in.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum Format {
+
+    /// The file’s path exactly as it is, then a tab, then the path again
+    /// decorated the way the lines view shows it. Fuzzy finders such as
+    /// fzf can display the second field and hand back the first.
+    Fzf,
+
+    /// Version 1 of exa’s own format, which has these fields, separated by
+    /// tabs: the file’s type, its permission bits in octal, its size in
+    /// bytes, its number of links, its inode, its user and group IDs, its
+    /// modified time in seconds since the epoch, its path, and the target
+    /// of the link if it’s a link, or nothing if it isn’t.
+    ///
+    /// The fields and their order never change: a format with different
+    /// ones would be a new version.
+    V1,
+}
+
+impl<'a> Render<'a> {
+    pub fn render<W: Write>(&self, w: &mut W) -> IOResult<()> {
+        for file in &self.files {
+            match self.format {
+                Format::Fzf  => self.render_fzf(file, w)?,
+                Format::V1   => render_v1(file, w)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    fn render_fzf<W: Write>(&self, file: &File<'a>, w: &mut W) -> IOResult<()> {
+        let path = file.relative_path();
+        w.write_all(path.as_os_str().as_bytes())?;
+        w.write_all(b"\t")?;
+
+        // Files inside a directory only get their names painted, so their
+        // parent directories have to be added here for the decorated path
+        // to say where they are when recursing.
+        let mut bits: Vec<ANSIString> = Vec::new();
+        if let (true, Some(parent)) = (file.parent_dir.is_some(), path.parent()) {
+            let parent = parent.as_os_str();
+
+            if !parent.is_empty() {
+                escape_os(parent, &mut bits, self.colours.symlink_path, self.colours.control_char);
+            }
+
+            if !parent.is_empty() && parent != "/" {
+                bits.push(self.colours.symlink_path.paint("/"));
+            }
+        }
+
+        let name = self.style.for_file(file, self.colours).with_link_paths().paint();
+        bits.extend(name.iter().cloned());
+        writeln!(w, "{}", ANSIStrings(&bits))
+    }
+}
+
+
+/// Prints a file in version 1 of the porcelain format, with its paths
+/// escaped so it can’t have more than one line or the wrong number of
+/// fields. This is also the format of the manifests that `--snapshot`
+/// writes.
+pub fn render_v1<W: Write>(file: &File, w: &mut W) -> IOResult<()> {
+    let mut line = String::new();
+
+    // Formatting into a `String` can’t fail.
+    let _ = write!(line, "{}\t{:04o}\t{}\t{}\t{}\t{}\t{}\t{}\t",
+                   type_letter(file.type_char()),
+                   file.metadata.permissions().mode() & 0o7777,
+                   file.metadata.len(),
+                   file.links().count,
+                   file.inode().0,
+                   file.user().0,
+                   file.group().0,
+                   file.modified_time().seconds);
+
+    escape_field(file.relative_path().as_os_str().as_bytes(), &mut line);
+    line.push('\t');
+
+    if file.is_link() {
+        if let Ok(target) = read_link(&file.path) {
+            escape_field(target.as_os_str().as_bytes(), &mut line);
+        }
+    }
+
+    line.push('\n');
+    w.write_all(line.as_bytes())
+}
+
+/// The letter for each type of file in the first field of the v1 format.
+fn type_letter(file_type: f::Type) -> char {
+    match file_type {
+        f::Type::File         => 'f',
+        f::Type::Directory    => 'd',
+        f::Type::Link         => 'l',
+        f::Type::Pipe         => 'p',
+        f::Type::Socket       => 's',
+        f::Type::CharDevice   => 'c',
+        f::Type::BlockDevice  => 'b',
+        f::Type::Special      => '?',
+    }
+}
+
+/// Escapes a path for the v1 format. Backslashes, tabs, and newlines become
+/// `\\`, `\t`, and `\n`, and other control characters and bytes that aren’t
+/// part of valid UTF-8 become `\xHH`, so the original bytes can always be
+/// got back.
+fn escape_field(bytes: &[u8], line: &mut String) {
+    let mut rest = bytes;
+
+    while !rest.is_empty() {
+        let (valid, invalid) = match str::from_utf8(rest) {
+            Ok(valid)  => (valid, 0),
+            Err(e)     => {
+                let valid = str::from_utf8(&rest[.. e.valid_up_to()]).unwrap_or_default();
+                (valid, e.error_len().unwrap_or(rest.len() - e.valid_up_to()))
+            },
+        };
+
+        for c in valid.chars() {
+            match c {
+                '\\'                 => line.push_str("\\\\"),
+                '\t'                 => line.push_str("\\t"),
+                '\n'                 => line.push_str("\\n"),
+                c if c.is_control()  => {
+                    let mut buf = [0; 4];
+                    for byte in c.encode_utf8(&mut buf).bytes() {
+                        let _ = write!(line, "\\x{:02x}", byte);
+                    }
+                },
+                c                    => line.push(c),
+            }
+        }
+
+        let start = valid.len();
+        for byte in &rest[start .. start + invalid] {
+            let _ = write!(line, "\\x{:02x}", byte);
+        }
+
+        rest = &rest[start + invalid ..];
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    macro_rules! test {
+        ($name:ident: $input:expr => $result:expr) => {
+            #[test]
+            fn $name() {
+                let mut line = String::new();
+                escape_field($input, &mut line);
+                assert_eq!(line, $result);
+            }
+        };
+    }
+
+    test!(plain:      b"src/exa.rs"          => "src/exa.rs");
+    test!(unicode:    "ünï cödé".as_bytes()  => "ünï cödé");
+    test!(tab:        b"a\tb"                => "a\\tb");
+    test!(newline:    b"a\nb"                => "a\\nb");
+    test!(backslash:  b"a\\b"                => "a\\\\b");
+    test!(escape:     b"\x1b[31m"            => "\\x1b[31m");
+    test!(invalid:    b"a\xffb\xc3"          => "a\\xffb\\xc3");
+    test!(c1:         "\u{85}".as_bytes()    => "\\xc2\\x85");
+}
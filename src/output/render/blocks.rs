@@ -6,7 +6,7 @@ use fs::fields as f;
 impl f::Blocks {
     pub fn render(&self, colours: &Colours) -> TextCell {
         match *self {
-            f::Blocks::Some(ref blk)  => TextCell::paint(colours.blocks, blk.to_string()),
+            f::Blocks::Some(ref blk)  => TextCell::paint(colours.blocks_style(*blk), blk.to_string()),
             f::Blocks::None           => TextCell::blank(colours.punctuation),
         }
     }
@@ -26,6 +26,7 @@ impl f::GitStatus {
             f::GitStatus::Deleted      => colours.git.deleted.paint("D"),
             f::GitStatus::Renamed      => colours.git.renamed.paint("R"),
             f::GitStatus::TypeChange   => colours.git.typechange.paint("T"),
+            f::GitStatus::Ignored      => colours.git.ignored.paint("I"),
         }
     }
 }
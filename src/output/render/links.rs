@@ -7,8 +7,7 @@ use locale;
 
 impl f::Links {
     pub fn render(&self, colours: &Colours, numeric: &locale::Numeric) -> TextCell {
-        let style = if self.multiple { colours.links.multi_link_file }
-                                else { colours.links.normal };
+        let style = colours.links_style(self.count, self.multiple);
 
         TextCell::paint(style, numeric.format_int(self.count))
     }
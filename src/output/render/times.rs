@@ -1,3 +1,5 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
 use datetime::TimeZone;
 
 use fs::fields as f;
@@ -11,14 +13,27 @@ impl f::Time {
                          tz: &Option<TimeZone>,
                          style: &TimeFormat) -> TextCell {
 
+        let colour = if colours.scale.age { colours.age_style(self.seconds_ago()) }
+                                     else { colours.date };
+
         if let Some(ref tz) = *tz {
             let datestamp = style.format_zoned(self, tz);
-            TextCell::paint(colours.date, datestamp)
+            TextCell::paint(colour, datestamp)
         }
         else {
             let datestamp = style.format_local(self);
-            TextCell::paint(colours.date, datestamp)
+            TextCell::paint(colour, datestamp)
         }
     }
-}
 
+    /// How many seconds ago this time was. Times in the future are a
+    /// negative number of seconds ago.
+    pub fn seconds_ago(self) -> i64 {
+        let now = match SystemTime::now().duration_since(UNIX_EPOCH) {
+            Ok(duration)  => duration.as_secs() as i64,
+            Err(_)        => 0,
+        };
+
+        now - i64::from(self.seconds)
+    }
+}
@@ -1,7 +1,10 @@
 use std::cmp::max;
+use std::ffi::OsStr;
 use std::fmt;
+use std::io::{stderr, Write};
 use std::ops::Deref;
-use std::sync::{Mutex, MutexGuard};
+use std::os::unix::ffi::OsStrExt;
+use std::sync::{Arc, Mutex, MutexGuard};
 
 use datetime::TimeZone;
 use zoneinfo_compiled::{CompiledData, Result as TZResult};
@@ -10,11 +13,15 @@ use locale;
 
 use users::UsersCache;
 
-use output::cell::TextCell;
+use ansi_term::Style;
+
+use output::cell::{TextCell, TextCellContents};
 use output::colours::Colours;
+use output::escape_os;
 use output::time::TimeFormat;
 
 use fs::{File, Dir, fields as f};
+use fs::feature::xattr::{self, FileAttributes};
 
 
 
@@ -28,7 +35,10 @@ pub struct Options {
     pub links: bool,
     pub blocks: bool,
     pub group: bool,
-    pub git: bool
+    pub git: bool,
+
+    /// Any extra columns to display after the built-in ones, in order.
+    pub custom_columns: Vec<Arc<CustomColumn>>,
 }
 
 impl fmt::Debug for Options {
@@ -89,6 +99,10 @@ impl Options {
             }
         }
 
+        for custom in &self.custom_columns {
+            columns.push(Column::Custom(Arc::clone(custom)));
+        }
+
         columns
     }
 }
@@ -106,6 +120,7 @@ pub enum Column {
     HardLinks,
     Inode,
     GitStatus,
+    Custom(Arc<CustomColumn>),
 }
 
 /// Each column can pick its own **Alignment**. Usually, numbers are
@@ -124,14 +139,61 @@ impl Column {
             | Column::HardLinks
             | Column::Inode
             | Column::Blocks
-            | Column::GitStatus => Alignment::Right,
-            _                   => Alignment::Left,
+            | Column::GitStatus      => Alignment::Right,
+            Column::Custom(ref c)    => c.alignment(),
+            _                        => Alignment::Left,
+        }
+    }
+
+    /// Whether this column needs to know if a file has any extended
+    /// attributes, which takes an extra system call per file to find out.
+    pub fn needs_xattrs(&self) -> bool {
+        match *self {
+            Column::Permissions  => true,
+            _                    => false,
+        }
+    }
+
+    /// The style from the theme that goes underneath everything in this
+    /// column.
+    pub fn base_style(&self, colours: &Colours) -> Style {
+        let styles = &colours.columns;
+
+        match *self {
+            Column::Permissions   => styles.permissions,
+            Column::FileSize(_)   => styles.size,
+            Column::Timestamp(_)  => styles.date,
+            Column::Blocks        => styles.blocks,
+            Column::User          => styles.user,
+            Column::Group         => styles.group,
+            Column::HardLinks     => styles.links,
+            Column::Inode         => styles.inode,
+            Column::GitStatus     => styles.git,
+            Column::Custom(_)     => styles.custom,
+        }
+    }
+
+    /// Whether this column has been made to have no colours.
+    pub fn is_plain(&self, colours: &Colours) -> bool {
+        let plain = &colours.plain_columns;
+
+        match *self {
+            Column::Permissions   => plain.permissions,
+            Column::FileSize(_)   => plain.size,
+            Column::Timestamp(_)  => plain.date,
+            Column::Blocks        => plain.blocks,
+            Column::User          => plain.user,
+            Column::Group         => plain.group,
+            Column::HardLinks     => plain.links,
+            Column::Inode         => plain.inode,
+            Column::GitStatus     => plain.git,
+            Column::Custom(_)     => plain.custom,
         }
     }
 
     /// Get the text that should be printed at the top, when the user elects
     /// to have a header row printed.
-    pub fn header(&self) -> &'static str {
+    pub fn header(&self) -> &str {
         match *self {
             Column::Permissions   => "Permissions",
             Column::FileSize(_)   => "Size",
@@ -142,6 +204,63 @@ impl Column {
             Column::HardLinks     => "Links",
             Column::Inode         => "inode",
             Column::GitStatus     => "Git",
+            Column::Custom(ref c) => c.header(),
+        }
+    }
+}
+
+
+/// A **custom column** is one that isn’t built in to exa, and can be added
+/// to a table through its options. The user can add columns that display
+/// extended attributes, and programs using exa as a library can add their
+/// own columns by implementing this trait.
+///
+/// Custom columns get displayed after all the built-in ones, in the order
+/// they were added, and before the file name.
+pub trait CustomColumn: fmt::Debug + Send + Sync {
+
+    /// The text to print at the top of the column, when the user elects to
+    /// have a header row printed.
+    fn header(&self) -> &str;
+
+    /// Renders the value of this column for the given file. This gets
+    /// called from several threads at once, one for each file.
+    fn render(&self, file: &File, colours: &Colours) -> TextCell;
+
+    /// The alignment this column should use. Text is left-aligned, so this
+    /// should be changed for columns of numbers.
+    fn alignment(&self) -> Alignment {
+        Alignment::Left
+    }
+}
+
+
+/// A custom column that displays the value of one of each file’s extended
+/// attributes, such as `user.tag`. Files without the attribute get a blank.
+#[derive(PartialEq, Debug)]
+pub struct XattrColumn {
+
+    /// The attribute’s full name, including its namespace.
+    pub name: String,
+}
+
+impl CustomColumn for XattrColumn {
+    fn header(&self) -> &str {
+        &self.name
+    }
+
+    fn render(&self, file: &File, colours: &Colours) -> TextCell {
+        if !xattr::ENABLED {
+            return TextCell::blank(colours.punctuation);
+        }
+
+        match file.path.attribute_value(&self.name) {
+            Ok(Some(value)) => {
+                let mut bits = Vec::new();
+                escape_os(OsStr::from_bytes(&value), &mut bits, Style::default(), colours.control_char);
+                TextCellContents::from(bits).promote()
+            },
+            _ => TextCell::blank(colours.punctuation),
         }
     }
 }
@@ -248,7 +367,7 @@ impl Environment {
         let tz = match determine_time_zone() {
             Ok(t) => Some(t),
             Err(ref e) => {
-                println!("Unable to determine time zone: {}", e);
+                let _ = writeln!(stderr(), "Unable to determine time zone: {}", e);
                 None
             }
         };
@@ -260,6 +379,16 @@ impl Environment {
 
         Environment { tz, numeric, users }
     }
+
+    /// An environment that’s the same on every computer, for listings that
+    /// have to come out the same wherever they’re run: numbers get
+    /// formatted in English, and times get shown in UTC.
+    pub fn fixed() -> Self {
+        let numeric = locale::Numeric::english();
+        let users = Mutex::new(UsersCache::new());
+
+        Environment { tz: None, numeric, users }
+    }
 }
 
 fn determine_time_zone() -> TZResult<TimeZone> {
@@ -273,6 +402,11 @@ fn determine_time_zone() -> TZResult<TimeZone> {
 pub struct Table<'a> {
     columns: Vec<Column>,
     colours: &'a Colours,
+
+    /// The colours to use for each column instead, for the columns that
+    /// the theme gives a base style to or that have been made plain.
+    column_colours: Vec<Option<Colours>>,
+
     env: &'a Environment,
     widths: TableWidths,
     time_format: &'a TimeFormat,
@@ -287,7 +421,20 @@ impl<'a, 'f> Table<'a> {
     pub fn new(options: &'a Options, dir: Option<&'a Dir>, colours: &'a Colours) -> Table<'a> {
         let colz = options.for_dir(dir);
         let widths = TableWidths::zero(colz.len());
-        Table { columns: colz, colours, env: &options.env, widths, time_format: &options.time_format }
+        let column_colours = colz.iter().map(|c| {
+            let base = c.base_style(colours);
+            if c.is_plain(colours)            { Some(Colours::plain()) }
+            else if base == Style::default()  { None }
+            else                              { Some(colours.with_base_style(base)) }
+        }).collect();
+
+        Table { columns: colz, colours, column_colours, env: &options.env, widths, time_format: &options.time_format }
+    }
+
+    /// Whether any of this table’s columns need to know if a file has
+    /// extended attributes. If none of them do, there’s no point looking.
+    pub fn needs_xattrs(&self) -> bool {
+        self.columns.iter().any(Column::needs_xattrs)
     }
 
     pub fn widths(&self) -> &TableWidths {
@@ -296,15 +443,15 @@ impl<'a, 'f> Table<'a> {
 
     pub fn header_row(&self) -> Row {
         let cells = self.columns.iter()
-                        .map(|c| TextCell::paint_str(self.colours.header, c.header()))
+                        .map(|c| TextCell::paint(self.colours.header, c.header().to_owned()))
                         .collect();
 
         Row { cells }
     }
 
     pub fn row_for_file(&self, file: &File, xattrs: bool) -> Row {
-        let cells = self.columns.iter()
-                        .map(|c| self.display(file, c, xattrs))
+        let cells = self.columns.iter().zip(self.column_colours.iter())
+                        .map(|(c, colours)| self.display(file, c, colours.as_ref().unwrap_or(self.colours), xattrs))
                         .collect();
 
         Row { cells }
@@ -322,22 +469,23 @@ impl<'a, 'f> Table<'a> {
         }
     }
 
-    fn display(&self, file: &File, column: &Column, xattrs: bool) -> TextCell {
+    fn display(&self, file: &File, column: &Column, colours: &Colours, xattrs: bool) -> TextCell {
         use output::table::TimeType::*;
 
         match *column {
-            Column::Permissions    => self.permissions_plus(file, xattrs).render(&self.colours),
-            Column::FileSize(fmt)  => file.size().render(&self.colours, fmt, &self.env.numeric),
-            Column::HardLinks      => file.links().render(&self.colours, &self.env.numeric),
-            Column::Inode          => file.inode().render(&self.colours),
-            Column::Blocks         => file.blocks().render(&self.colours),
-            Column::User           => file.user().render(&self.colours, &*self.env.lock_users()),
-            Column::Group          => file.group().render(&self.colours, &*self.env.lock_users()),
-            Column::GitStatus      => file.git_status().render(&self.colours),
-
-            Column::Timestamp(Modified)  => file.modified_time().render(&self.colours, &self.env.tz, &self.time_format),
-            Column::Timestamp(Created)   => file.created_time().render( &self.colours, &self.env.tz, &self.time_format),
-            Column::Timestamp(Accessed)  => file.accessed_time().render(&self.colours, &self.env.tz, &self.time_format),
+            Column::Permissions    => self.permissions_plus(file, xattrs).render(colours),
+            Column::FileSize(fmt)  => file.size().render(colours, fmt, &self.env.numeric),
+            Column::HardLinks      => file.links().render(colours, &self.env.numeric),
+            Column::Inode          => file.inode().render(colours),
+            Column::Blocks         => file.blocks().render(colours),
+            Column::User           => file.user().render(colours, &*self.env.lock_users()),
+            Column::Group          => file.group().render(colours, &*self.env.lock_users()),
+            Column::GitStatus      => file.git_status().render(colours),
+            Column::Custom(ref c)  => c.render(file, colours),
+
+            Column::Timestamp(Modified)  => file.modified_time().render(colours, &self.env.tz, &self.time_format),
+            Column::Timestamp(Created)   => file.created_time().render( colours, &self.env.tz, &self.time_format),
+            Column::Timestamp(Accessed)  => file.accessed_time().render(colours, &self.env.tz, &self.time_format),
         }
     }
 
@@ -352,7 +500,7 @@ impl<'a, 'f> Table<'a> {
                 Alignment::Right => { cell.add_spaces(padding); cell.append(this_cell); }
             }
 
-            cell.add_spaces(1);
+            cell.push(self.colours.column_separator.paint(" "), 1);
         }
 
         cell
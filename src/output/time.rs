@@ -11,6 +11,10 @@ pub enum TimeFormat {
     ISOFormat(ISOFormat),
     LongISO,
     FullISO,
+
+    /// The number of seconds since the Unix epoch, which is the same
+    /// whatever the time zone, locale, or current time are.
+    Epoch,
 }
 
 impl TimeFormat {
@@ -20,6 +24,7 @@ impl TimeFormat {
             TimeFormat::ISOFormat(ref iso)     => iso.format_local(time),
             TimeFormat::LongISO                => long_local(time),
             TimeFormat::FullISO                => full_local(time),
+            TimeFormat::Epoch                  => time.seconds.to_string(),
         }
     }
 
@@ -29,6 +34,7 @@ impl TimeFormat {
             TimeFormat::ISOFormat(ref iso)     => iso.format_zoned(time, zone),
             TimeFormat::LongISO                => long_zoned(time, zone),
             TimeFormat::FullISO                => full_zoned(time, zone),
+            TimeFormat::Epoch                  => time.seconds.to_string(),
         }
     }
 }
@@ -145,6 +145,12 @@ impl TreeParams {
     pub fn is_at_root(&self) -> bool {
         self.depth.0 == 0
     }
+
+    /// Moves these parameters up the tree by the given number of levels,
+    /// stopping at the top.
+    pub fn shallower(self, levels: usize) -> TreeParams {
+        TreeParams { depth: TreeDepth(self.depth.0.saturating_sub(levels)), last: self.last }
+    }
 }
 
 impl TreeDepth {